@@ -0,0 +1,219 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// actix/core/lib
+
+// ----------------------------------------------------------------
+
+pub use autortr_core::*;
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use actix_web::web::{self, ServiceConfig};
+use actix_web::{Resource, Route, Scope};
+
+// ----------------------------------------------------------------
+
+/// A handler registered by the mapping macros, with its `Route` factory, e.g. `web::get().to(handler)`.
+pub type RouteMapping = HandlerMapping<Route>;
+
+// ----------------------------------------------------------------
+
+/// Converts a Rocket-style path template to actix-web's path syntax.
+///
+/// `<name>` becomes `{name}`, a trailing `<name..>` becomes `{name:.*}`. The query part
+/// (`?<q>`) is dropped, since actix-web binds queries with the `Query` extractor.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_actix_core::actix_path;
+///
+/// assert_eq!(actix_path("/get").unwrap(), "/get");
+/// assert_eq!(actix_path("/users/<id>").unwrap(), "/users/{id}");
+/// assert_eq!(actix_path("/files/<path..>?<q>").unwrap(), "/files/{path:.*}");
+///
+/// assert!(actix_path("/users/<id").is_err());
+/// assert!(actix_path("/files/<path..>/raw").is_err());
+/// ```
+pub fn actix_path(template: &str) -> Result<String, String> {
+    convert_path(template, |name, trailing| match trailing {
+        true => format!("{{{}:.*}}", name),
+        false => format!("{{{}}}", name),
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// The actix-web `Backend`: groups each namespace into its own `web::scope`, the root namespace
+/// (`/`) is registered directly. Routes sharing a path share a `web::resource`. actix-web has
+/// no catchers.
+pub struct ActixBackend;
+
+/// The mappings of each namespace, without its trailing `/`, by path.
+pub type ActixApp = BTreeMap<String, BTreeMap<String, Vec<RouteMapping>>>;
+
+/// The services built by `ActixBackend`, applied to an `App` with `configure`.
+#[derive(Default)]
+pub struct ActixServices {
+    root: Vec<Resource>,
+    scopes: Vec<Scope>,
+}
+
+impl ActixServices {
+    pub fn configure(self, cfg: &mut ServiceConfig) {
+        // The root namespace first: a scope would otherwise shadow root paths sharing its prefix.
+        for resource in self.root {
+            cfg.service(resource);
+        }
+        for scope in self.scopes {
+            cfg.service(scope);
+        }
+    }
+}
+
+impl Backend for ActixBackend {
+    const NAME: &'static str = "actix-web";
+
+    type App = ActixApp;
+    type Mapping = RouteMapping;
+    type Catcher = Infallible;
+    type Output = ActixServices;
+
+    fn route_path(template: &str) -> Result<String, String> {
+        actix_path(template)
+    }
+
+    fn route_factory(method: &str, handler: &str) -> Option<String> {
+        Some(format!("|| actix_web::web::{}().to({})", method, handler))
+    }
+
+    fn descriptor(mapping: &RouteMapping) -> RouteDescriptor {
+        mapping.descriptor()
+    }
+
+    fn catcher_namespace(catcher: &Infallible) -> String {
+        match *catcher {}
+    }
+
+    fn app() -> ActixApp {
+        BTreeMap::new()
+    }
+
+    fn mount(app: ActixApp, namespace: &str, mappings: Vec<RouteMapping>) -> ActixApp {
+        let mut app = app;
+        let namespace = namespace.trim_end_matches('/').to_string();
+
+        let resources = app.entry(namespace.clone()).or_default();
+        for mapping in mappings {
+            let path = match namespace.is_empty() {
+                true => mapping.route_path.clone(),
+                // `/` inside a scope is the scope itself.
+                false => mapping.route_path.trim_end_matches('/').to_string(),
+            };
+            resources.entry(path).or_default().push(mapping);
+        }
+
+        app
+    }
+
+    fn mount_catchers(app: ActixApp, _namespace: &str, catchers: Vec<Infallible>) -> ActixApp {
+        match catchers.into_iter().next() {
+            Some(catcher) => match catcher {},
+            None => app,
+        }
+    }
+
+    fn finish(app: ActixApp) -> ActixServices {
+        let mut services = ActixServices::default();
+        for (namespace, resources) in app {
+            match namespace.is_empty() {
+                true => {
+                    for (path, mappings) in resources {
+                        services.root.push(resource(&path, &mappings));
+                    }
+                }
+                false => {
+                    let mut scope = web::scope(&namespace);
+                    for (path, mappings) in resources {
+                        scope = scope.service(resource(&path, &mappings));
+                    }
+                    services.scopes.push(scope);
+                }
+            }
+        }
+
+        services
+    }
+}
+
+// ----------------------------------------------------------------
+
+static ROUTE_MAPPINGS: Registry<RouteMapping> = Registry::new();
+
+// ----------------------------------------------------------------
+
+pub fn register_route_mapping(mapping: RouteMapping) {
+    ROUTE_MAPPINGS.register(mapping);
+}
+
+// ----------------------------------------------------------------
+
+pub fn try_acquire_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.acquire()
+}
+
+// ----------------------------------------------------------------
+
+pub fn clean_route_mappings() {
+    ROUTE_MAPPINGS.clean();
+}
+
+// ----------------------------------------------------------------
+
+/// Returns every route mapping registered so far, regardless of `clean_route_mappings`.
+pub fn snapshot_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.snapshot()
+}
+
+/// Returns the framework-neutral descriptors of the given route mappings.
+pub fn route_descriptors(mappings: &[RouteMapping]) -> Vec<RouteDescriptor> {
+    handler_descriptors(mappings)
+}
+
+/// Returns the sorted `RouteTable` of the given route mappings.
+pub fn route_table(mappings: &[RouteMapping]) -> RouteTable {
+    handler_table(mappings)
+}
+
+/// Returns the sorted `RouteTable` of every registered route mapping.
+pub fn snapshot_route_table() -> RouteTable {
+    route_table(&snapshot_route_mappings())
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn resource(path: &str, mappings: &[RouteMapping]) -> Resource {
+    mappings
+        .iter()
+        .fold(web::resource(path), |resource, mapping| {
+            resource.route((mapping.route)())
+        })
+}
@@ -0,0 +1,82 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// actix/router/lib
+
+// ----------------------------------------------------------------
+
+use std::sync::OnceLock;
+
+use actix_web::web::ServiceConfig;
+
+use autortr_actix_core::{
+    build, clean_route_mappings, try_acquire_route_mappings, ActixBackend, RouteMapping,
+};
+
+// ----------------------------------------------------------------
+
+/// Applies the registered route mappings to an actix-web `App`, and cleans the registry afterward.
+///
+/// Each namespace is grouped into its own `web::scope`, the root namespace (`/`) is
+/// registered directly. Routes sharing a path share a `web::resource`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use actix_web::{App, HttpServer};
+/// use autortr_actix_router::configure;
+///
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     HttpServer::new(|| App::new().configure(configure))
+///         .bind(("127.0.0.1", 8000))?
+///         .run()
+///         .await
+/// }
+/// ```
+///
+/// Note: `HttpServer` calls the factory once per worker; the mappings acquired by the
+/// first call are kept for the others.
+pub fn configure(cfg: &mut ServiceConfig) {
+    static MAPPINGS: OnceLock<Vec<RouteMapping>> = OnceLock::new();
+
+    let mappings = MAPPINGS.get_or_init(|| {
+        let mappings = try_acquire_route_mappings();
+        clean_route_mappings();
+
+        mappings
+    });
+
+    configure_with(cfg, mappings.clone());
+}
+
+/// Applies the given route mappings to an actix-web `App`, with `ActixBackend`.
+///
+/// Unlike `configure`, the registry is left untouched, which allows configuring several
+/// apps from `snapshot_route_mappings`, e.g. one per test.
+///
+/// # Example
+///
+/// ```rust
+/// use actix_web::App;
+/// use autortr_actix_core::snapshot_route_mappings;
+/// use autortr_actix_router::configure_with;
+///
+/// let app = App::new().configure(|cfg| configure_with(cfg, snapshot_route_mappings()));
+/// ```
+pub fn configure_with(cfg: &mut ServiceConfig, mappings: Vec<RouteMapping>) {
+    build::<ActixBackend>(mappings, Vec::new()).configure(cfg);
+}
@@ -0,0 +1,446 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/codegen/lib
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, ExprLit, Ident, ItemFn, Lit, LitStr, Token};
+
+pub use autortr_core::PathConstraint;
+
+use autortr_core::{
+    join_path, pattern_constraints, split_namespace, translate_pattern, Backend, RouteDescriptor,
+    DATA, DEPRECATED, EMPTY, METHOD, NAMESPACE, PATH, ROOT,
+};
+
+// ----------------------------------------------------------------
+
+/// One mapping attribute argument: a bare path literal or a `key = value` pair.
+///
+/// @since 0.3.0
+#[derive(Clone)]
+pub enum MappingArg {
+    Path(LitStr),
+    Named(Ident, Box<Expr>),
+}
+
+impl Parse for MappingArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            return Ok(MappingArg::Path(input.parse()?));
+        }
+
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value: Expr = input.parse()?;
+
+        Ok(MappingArg::Named(name, Box::new(value)))
+    }
+}
+
+/// The arguments of a mapping attribute, e.g. `#[get_mapping("/get", namespace = "/rocket")]`.
+///
+/// Values are arbitrary expressions, so that adapters and extensions can accept
+/// more than string literals (e.g. `guards = [A, B]`).
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_codegen::MappingArgs;
+///
+/// let args: MappingArgs = syn::parse_str(r#""/get", namespace = "/rocket", rank = 2"#).unwrap();
+///
+/// assert_eq!(args.path().as_deref(), Some("/get"));
+/// assert_eq!(args.string("namespace").as_deref(), Some("/rocket"));
+/// assert!(args.expr("rank").is_some());
+/// assert!(args.expr("data").is_none());
+///
+/// let args: MappingArgs = syn::parse_str(r#""/get", rest = true, tags = ["a", "b"]"#).unwrap();
+/// assert!(args.flag("rest"));
+/// assert_eq!(args.strings("tags"), Some(vec!["a".to_string(), "b".to_string()]));
+///
+/// let args: MappingArgs = syn::parse_str(r#"guards = [Admin], "/get", guards = ApiKey"#).unwrap();
+/// assert_eq!(args.exprs("guards").len(), 2);
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Default)]
+pub struct MappingArgs {
+    pub args: Vec<MappingArg>,
+}
+
+impl Parse for MappingArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = Punctuated::<MappingArg, Token![,]>::parse_terminated(input)?;
+
+        Ok(MappingArgs {
+            args: args.into_iter().collect(),
+        })
+    }
+}
+
+impl MappingArgs {
+    /// The first bare path literal, if any.
+    pub fn path(&self) -> Option<String> {
+        self.args.iter().find_map(|arg| match arg {
+            MappingArg::Path(path) => Some(path.value()),
+            _ => None,
+        })
+    }
+
+    /// The value of the `name` argument.
+    pub fn expr(&self, name: &str) -> Option<&Expr> {
+        self.args.iter().find_map(|arg| match arg {
+            MappingArg::Named(ident, value) if ident == name => Some(value.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// The value of the `name` argument, if it is a string literal.
+    pub fn string(&self, name: &str) -> Option<String> {
+        match self.expr(name) {
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            })) => Some(value.value()),
+            _ => None,
+        }
+    }
+
+    /// The values of the `name` argument, a string literal or an array of them.
+    pub fn strings(&self, name: &str) -> Option<Vec<String>> {
+        let string = |expr: &Expr| match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => Some(value.value()),
+            _ => None,
+        };
+
+        match self.expr(name)? {
+            Expr::Array(array) => array.elems.iter().map(string).collect(),
+            expr => string(expr).map(|value| vec![value]),
+        }
+    }
+
+    /// The values of every `name` argument, in order, arrays flattened.
+    pub fn exprs(&self, name: &str) -> Vec<Expr> {
+        self.args
+            .iter()
+            .filter_map(|arg| match arg {
+                MappingArg::Named(ident, value) if ident == name => Some(value.as_ref()),
+                _ => None,
+            })
+            .flat_map(|value| match value {
+                Expr::Array(array) => array.elems.iter().cloned().collect(),
+                value => vec![value.clone()],
+            })
+            .collect()
+    }
+
+    /// Whether the `name` argument is `true`.
+    pub fn flag(&self, name: &str) -> bool {
+        matches!(
+            self.expr(name),
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Bool(value),
+                ..
+            })) if value.value
+        )
+    }
+
+    pub fn has_positional(&self) -> bool {
+        self.args
+            .iter()
+            .any(|arg| matches!(arg, MappingArg::Path(_)))
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The resolved `namespace`, `method`, `path` and `data` of a mapping attribute.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_codegen::{Mapping, MappingArgs};
+///
+/// let args: MappingArgs = syn::parse_str(r#"path = "/post", data = "<form>""#).unwrap();
+/// let mapping = Mapping::http_mapping("post", &args).unwrap();
+///
+/// assert_eq!(mapping.namespace, "/");
+/// assert_eq!(mapping.method, "post");
+/// assert_eq!(mapping.path, "/post");
+/// assert_eq!(mapping.data, "<form>");
+/// assert!(mapping.has_data());
+///
+/// let args: MappingArgs = syn::parse_str(r#""/static/**""#).unwrap();
+/// let mapping = Mapping::http_mapping("get", &args).unwrap();
+///
+/// assert_eq!(mapping.path, "/static/<path..>");
+/// assert_eq!(mapping.pattern, "/static/**");
+///
+/// let args: MappingArgs = syn::parse_str(r##"r"/orders/{id:\d+}""##).unwrap();
+/// let mapping = Mapping::http_mapping("get", &args).unwrap();
+///
+/// assert_eq!(mapping.path, "/orders/<id>");
+/// assert_eq!(mapping.constraints[0].regex, r"\d+");
+///
+/// let args: MappingArgs = syn::parse_str(r#"namespace = "/tenants/{tenant}", path = "/users""#).unwrap();
+/// let mapping = Mapping::http_mapping("get", &args).unwrap();
+///
+/// assert_eq!(mapping.namespace, "/tenants");
+/// assert_eq!(mapping.dynamic_namespace, "/<tenant>");
+/// assert_eq!(mapping.path, "/<tenant>/users");
+///
+/// let args: MappingArgs = syn::parse_str(r#"path = "/get""#).unwrap();
+/// assert!(Mapping::request_mapping(&args).is_none());
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    /// The static mount base of the namespace.
+    pub namespace: String,
+    /// The dynamic rest of the namespace, e.g. `/<tenant>`, folded into `path`; empty if none.
+    pub dynamic_namespace: String,
+    pub method: String,
+    /// The Rocket-style path template, translated from `pattern`, under `dynamic_namespace`.
+    pub path: String,
+    /// The path as written, e.g. a Spring-style `/users/{id}`.
+    pub pattern: String,
+    /// The body binding, `_` (`EMPTY`) if none.
+    pub data: String,
+    /// The regex constraints of the `pattern`, e.g. `{id:\d+}`, checked at build time.
+    pub constraints: Vec<PathConstraint>,
+}
+
+impl Mapping {
+    /// Resolves `#[request_mapping(namespace = "..", method = "..", path = "..", data = "..")]`.
+    pub fn request_mapping(args: &MappingArgs) -> Option<Self> {
+        if args.has_positional() {
+            return None;
+        }
+
+        match (args.string(METHOD), args.string(PATH)) {
+            (Some(method), Some(path)) => Some(Mapping::new(args, method, path)),
+            _ => None,
+        }
+    }
+
+    /// Resolves `#[xxx_mapping("..")]` or `#[xxx_mapping(namespace = "..", path = "..", data = "..")]`.
+    pub fn http_mapping(method: &str, args: &MappingArgs) -> Option<Self> {
+        args.string(PATH)
+            .or_else(|| args.path())
+            .map(|path| Mapping::new(args, method.to_string(), path))
+    }
+
+    pub fn has_data(&self) -> bool {
+        self.data != EMPTY
+    }
+
+    /// The framework-neutral descriptor of the mapped `handler`.
+    pub fn descriptor(&self, handler: &str) -> RouteDescriptor {
+        let descriptor = RouteDescriptor::new(handler, &self.method, &self.namespace, &self.path);
+
+        match self.has_data() {
+            true => descriptor.body(&self.data),
+            false => descriptor,
+        }
+    }
+
+    /// Spring-style placeholders and Ant wildcards (`{id}`, `*`, `**`) are translated to
+    /// Rocket-style segments, see `translate_pattern`. Dynamic namespace segments are folded
+    /// into the path, see `split_namespace`.
+    ///
+    /// Panics on an invalid pattern.
+    #[doc(hidden)]
+    fn new(args: &MappingArgs, method: String, pattern: String) -> Self {
+        let namespace = args.string(NAMESPACE).unwrap_or_else(|| ROOT.to_string());
+        let (namespace_template, mut constraints) = translate(&namespace);
+        let (path, path_constraints) = translate(&pattern);
+        constraints.extend(path_constraints);
+
+        for constraint in &constraints {
+            if let Err(error) = regex::Regex::new(&constraint.anchored()) {
+                panic!(
+                    "Invalid constraint `{}` of `{}` in `{}`: {}",
+                    constraint.regex, constraint.name, pattern, error
+                );
+            }
+        }
+
+        let (namespace, dynamic_namespace) = split_namespace(&namespace_template);
+        let path = match dynamic_namespace.is_empty() {
+            true => path,
+            false => join_path(&dynamic_namespace, &path),
+        };
+
+        Mapping {
+            namespace,
+            dynamic_namespace,
+            method,
+            path,
+            pattern,
+            data: args.string(DATA).unwrap_or_else(|| EMPTY.to_string()),
+            constraints,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Whether the handler is marked `#[deprecated]`.
+///
+/// @since 0.3.0
+pub fn is_deprecated(function: &ItemFn) -> bool {
+    function
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident(DEPRECATED))
+}
+
+/// The backend's framework attribute for the mapped `function`, if any.
+///
+/// Panics if the backend emits an attribute that is not valid Rust.
+///
+/// @since 0.3.0
+pub fn route_attribute<B: Backend>(mapping: &Mapping, function: &ItemFn) -> TokenStream {
+    let descriptor = mapping
+        .descriptor(&function.sig.ident.to_string())
+        .deprecated(is_deprecated(function));
+
+    match B::route_attribute(&descriptor) {
+        Some(attribute) => match attribute.parse() {
+            Ok(attribute) => attribute,
+            Err(_) => panic!("Invalid route attribute `{}`", attribute),
+        },
+        None => TokenStream::new(),
+    }
+}
+
+/// Expands a mapping attribute of an adapter registering `HandlerMapping`s: `#[request_mapping]`
+/// if `method` is `None`, `#[<method>_mapping]` otherwise.
+///
+/// The handler gets the backend's `route_attribute` and is registered with `register_route_mapping`,
+/// its path translated by `Backend::route_path` and its route built by `Backend::route_factory`.
+/// Panics on invalid arguments, an unsupported method or a regex-constrained path.
+///
+/// @since 0.3.0
+pub fn expand_handler<B: Backend>(
+    method: Option<&str>,
+    args: TokenStream,
+    item: TokenStream,
+) -> TokenStream {
+    let args: MappingArgs = match syn::parse2(args) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error(),
+    };
+    let function: ItemFn = match syn::parse2(item) {
+        Ok(function) => function,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let (mapping, register_fn_name) = match method {
+        None => (
+            Mapping::request_mapping(&args)
+                .unwrap_or_else(|| panic!("Invalid arguments to `#[request_mapping]`")),
+            format_ident!("_register_{}_", function.sig.ident),
+        ),
+        Some(method) => (
+            Mapping::http_mapping(method, &args)
+                .unwrap_or_else(|| panic!("Invalid arguments to `#[{}_mapping]`", method)),
+            format_ident!("_register_{}_{}_", method, function.sig.ident),
+        ),
+    };
+
+    let function_ident = &function.sig.ident;
+    let function_name = function_ident.to_string();
+
+    if !mapping.constraints.is_empty() {
+        panic!(
+            "Regex-constrained path variables are not supported by the {} adapter",
+            B::NAME
+        );
+    }
+    if !matches!(
+        mapping.method.as_str(),
+        "get" | "post" | "put" | "patch" | "delete" | "head"
+    ) {
+        panic!("Unsupported HTTP method");
+    }
+
+    let route_path = match B::route_path(&mapping.path) {
+        Ok(route_path) => route_path,
+        Err(message) => panic!("Invalid path of `{}`: {}", function_name, message),
+    };
+    let route: TokenStream = match B::route_factory(&mapping.method, &function_name) {
+        Some(factory) => match factory.parse() {
+            Ok(factory) => factory,
+            Err(_) => panic!("Invalid route factory `{}`", factory),
+        },
+        None => panic!("The {} backend doesn't register handler mappings", B::NAME),
+    };
+
+    let attribute = route_attribute::<B>(&mapping, &function);
+    let deprecated = is_deprecated(&function);
+
+    let Mapping {
+        namespace,
+        method,
+        path,
+        pattern,
+        data,
+        ..
+    } = mapping;
+
+    quote! {
+        #attribute
+        #function
+
+        #[ctor::ctor]
+        #[allow(deprecated)]
+        fn #register_fn_name() {
+            register_route_mapping(RouteMapping {
+                function: #function_name.to_string(),
+                namespace: #namespace.to_string(),
+                method: #method.to_string(),
+                path: #path.to_string(),
+                pattern: #pattern.to_string(),
+                route_path: #route_path.to_string(),
+                data: #data.to_string(),
+                deprecated: #deprecated,
+                route: #route,
+            });
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Translates a pattern and collects its constraints, panics if it is invalid.
+#[doc(hidden)]
+fn translate(pattern: &str) -> (String, Vec<PathConstraint>) {
+    match (translate_pattern(pattern), pattern_constraints(pattern)) {
+        (Ok(template), Ok(constraints)) => (template, constraints),
+        (Err(message), _) | (_, Err(message)) => panic!("Invalid path pattern {}", message),
+    }
+}
@@ -0,0 +1,361 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/authority
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+
+// ----------------------------------------------------------------
+
+/// The authorities of a request, as seen by an `Authority` expression.
+///
+/// @since 0.3.0
+pub trait Authorities {
+    fn is_authenticated(&self) -> bool;
+
+    fn has_role(&self, role: &str) -> bool;
+
+    fn has_permission(&self, permission: &str) -> bool;
+}
+
+/// A parsed `#[pre_authorize]` expression.
+///
+/// Supported functions are `has_role('..')`, `has_any_role('..', ..)`, `has_permission('..')`,
+/// `has_any_permission('..', ..)`, `is_authenticated()`, `permit_all()` and `deny_all()`,
+/// combined with `and`, `or`, `not` and parentheses.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{Authorities, Authority};
+///
+/// struct Admin;
+///
+/// impl Authorities for Admin {
+///     fn is_authenticated(&self) -> bool {
+///         true
+///     }
+///
+///     fn has_role(&self, role: &str) -> bool {
+///         role == "ADMIN"
+///     }
+///
+///     fn has_permission(&self, _: &str) -> bool {
+///         false
+///     }
+/// }
+///
+/// let authority =
+///     Authority::parse("has_role('ADMIN') or has_permission('orders:write')").unwrap();
+///
+/// assert!(authority.evaluate(&Admin));
+/// assert_eq!(authority.authorities(), vec!["role:ADMIN", "permission:orders:write"]);
+///
+/// let authority = Authority::parse("has_role('ADMIN') and not has_permission('orders:read')");
+/// assert!(authority.unwrap().evaluate(&Admin));
+///
+/// assert!(Authority::parse("has_role('ADMIN') or").is_err());
+/// assert!(Authority::parse("has_group('ADMIN')").is_err());
+///
+/// // arguments are separated by commas
+/// let authority = Authority::parse("has_any_role('ADMIN', 'OPS')").unwrap();
+/// assert_eq!(authority.authorities(), vec!["role:ADMIN", "role:OPS"]);
+/// assert!(Authority::parse("has_any_role('ADMIN' 'OPS')").is_err());
+/// assert!(Authority::parse("has_any_role('ADMIN',)").is_err());
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Authority {
+    PermitAll,
+    DenyAll,
+    Authenticated,
+    Role(String),
+    AnyRole(Vec<String>),
+    Permission(String),
+    AnyPermission(Vec<String>),
+    Not(Box<Authority>),
+    And(Box<Authority>, Box<Authority>),
+    Or(Box<Authority>, Box<Authority>),
+}
+
+impl Authority {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut parser = AuthorityParser {
+            tokens: tokenize(expression)?,
+            position: 0,
+        };
+
+        let authority = parser.or()?;
+        match parser.peek() {
+            None => Ok(authority),
+            Some(token) => Err(format!("Unexpected `{}` in `{}`", token, expression)),
+        }
+    }
+
+    pub fn evaluate(&self, authorities: &dyn Authorities) -> bool {
+        match self {
+            Authority::PermitAll => true,
+            Authority::DenyAll => false,
+            Authority::Authenticated => authorities.is_authenticated(),
+            Authority::Role(role) => authorities.has_role(role),
+            Authority::AnyRole(roles) => roles.iter().any(|role| authorities.has_role(role)),
+            Authority::Permission(permission) => authorities.has_permission(permission),
+            Authority::AnyPermission(permissions) => permissions
+                .iter()
+                .any(|permission| authorities.has_permission(permission)),
+            Authority::Not(authority) => !authority.evaluate(authorities),
+            Authority::And(left, right) => {
+                left.evaluate(authorities) && right.evaluate(authorities)
+            }
+            Authority::Or(left, right) => left.evaluate(authorities) || right.evaluate(authorities),
+        }
+    }
+
+    /// The roles and permissions mentioned by the expression, as `role:..` and `permission:..`,
+    /// in order and without duplicates.
+    pub fn authorities(&self) -> Vec<String> {
+        let mut authorities = Vec::new();
+        self.collect(&mut authorities);
+
+        authorities
+    }
+
+    #[doc(hidden)]
+    fn collect(&self, authorities: &mut Vec<String>) {
+        let mut push = |authority: String| {
+            if !authorities.contains(&authority) {
+                authorities.push(authority);
+            }
+        };
+
+        match self {
+            Authority::PermitAll | Authority::DenyAll | Authority::Authenticated => {}
+            Authority::Role(role) => push(format!("role:{}", role)),
+            Authority::AnyRole(roles) => {
+                roles.iter().for_each(|role| push(format!("role:{}", role)))
+            }
+            Authority::Permission(permission) => push(format!("permission:{}", permission)),
+            Authority::AnyPermission(permissions) => permissions
+                .iter()
+                .for_each(|permission| push(format!("permission:{}", permission))),
+            Authority::Not(authority) => authority.collect(authorities),
+            Authority::And(left, right) | Authority::Or(left, right) => {
+                left.collect(authorities);
+                right.collect(authorities);
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Literal(String),
+    Open,
+    Close,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "{}", ident),
+            Token::Literal(literal) => write!(f, "'{}'", literal),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+#[doc(hidden)]
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Comma,
+                });
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == c => break,
+                        Some(next) => literal.push(next),
+                        None => return Err(format!("Unterminated string in `{}`", expression)),
+                    }
+                }
+                tokens.push(Token::Literal(literal));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    ident.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => return Err(format!("Unexpected `{}` in `{}`", c, expression)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[doc(hidden)]
+struct AuthorityParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl AuthorityParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident == keyword => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("Expected `{}`, found `{}`", expected, token)),
+            None => Err(format!("Expected `{}`, found the end", expected)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Authority, String> {
+        let mut authority = self.and()?;
+        while self.keyword("or") {
+            authority = Authority::Or(Box::new(authority), Box::new(self.and()?));
+        }
+
+        Ok(authority)
+    }
+
+    fn and(&mut self) -> Result<Authority, String> {
+        let mut authority = self.not()?;
+        while self.keyword("and") {
+            authority = Authority::And(Box::new(authority), Box::new(self.not()?));
+        }
+
+        Ok(authority)
+    }
+
+    fn not(&mut self) -> Result<Authority, String> {
+        match self.keyword("not") {
+            true => Ok(Authority::Not(Box::new(self.not()?))),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Authority, String> {
+        let function = match self.next() {
+            Some(Token::Open) => {
+                let authority = self.or()?;
+                self.expect(Token::Close)?;
+                return Ok(authority);
+            }
+            Some(Token::Ident(function)) => function,
+            Some(token) => return Err(format!("Unexpected `{}`", token)),
+            None => return Err("Unexpected end of expression".to_string()),
+        };
+
+        // `(` [literal (`,` literal)*] `)`
+        let mut arguments = Vec::new();
+        if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let mut closed = self.peek() == Some(&Token::Close);
+            while !closed {
+                match self.next() {
+                    Some(Token::Literal(argument)) => arguments.push(argument),
+                    Some(token) => return Err(format!("Unexpected `{}` in `{}`", token, function)),
+                    None => return Err(format!("Unterminated `{}`", function)),
+                }
+                match self.peek() {
+                    Some(Token::Comma) => self.position += 1,
+                    Some(Token::Close) => closed = true,
+                    Some(token) => {
+                        return Err(format!(
+                            "Expected `,` or `)` before `{}` in `{}`",
+                            token, function
+                        ))
+                    }
+                    None => return Err(format!("Unterminated `{}`", function)),
+                }
+            }
+            self.position += 1;
+        }
+
+        let single = |arguments: Vec<String>| match <[String; 1]>::try_from(arguments) {
+            Ok([argument]) => Ok(argument),
+            Err(_) => Err(format!("`{}` takes exactly one argument", function)),
+        };
+        let none = |arguments: Vec<String>, authority: Authority| match arguments.is_empty() {
+            true => Ok(authority),
+            false => Err(format!("`{}` takes no argument", function)),
+        };
+        let some = |arguments: Vec<String>| match arguments.is_empty() {
+            true => Err(format!("`{}` takes at least one argument", function)),
+            false => Ok(arguments),
+        };
+
+        match function.as_str() {
+            "has_role" => single(arguments).map(Authority::Role),
+            "has_any_role" => some(arguments).map(Authority::AnyRole),
+            "has_permission" => single(arguments).map(Authority::Permission),
+            "has_any_permission" => some(arguments).map(Authority::AnyPermission),
+            "is_authenticated" => none(arguments, Authority::Authenticated),
+            "permit_all" => none(arguments, Authority::PermitAll),
+            "deny_all" => none(arguments, Authority::DenyAll),
+            _ => Err(format!("Unknown function `{}`", function)),
+        }
+    }
+}
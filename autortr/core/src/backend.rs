@@ -0,0 +1,189 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/backend
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use crate::RouteDescriptor;
+
+// ----------------------------------------------------------------
+
+/// A web framework autortr can drive.
+///
+/// A backend answers three questions, which is all a framework needs to get the mapping macros:
+///
+/// - how to emit the framework attribute on a mapped handler (`route_attribute`, at compile time),
+/// - how to mount a group of mappings under a namespace (`mount`),
+/// - how to mount error catchers under a namespace (`mount_catchers`).
+///
+/// Backends of frameworks routing plain handlers also translate the path (`route_path`) and
+/// build the handler's route (`route_factory`), so that `autortr_codegen::expand_handler`
+/// generates the whole `HandlerMapping` registration.
+///
+/// `build` drives any backend: mappings are grouped by namespace, each group is mounted,
+/// then the catchers of each namespace, and the result is `finish`ed.
+///
+/// A third-party backend is usually made of a `core` crate (the backend, its `Mapping`
+/// type and a `Registry<Mapping>`), a proc-macro `codegen` crate (the mapping macros, built
+/// on `autortr_codegen`) and a `router` crate (`app()` = `build` over the registry).
+/// Every adapter of this repository goes through it: `RocketBackend`, `AxumBackend`,
+/// `ActixBackend` and `PoemBackend`; see `poem/` for the reference implementation.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{build, Backend, RouteDescriptor};
+///
+/// /// Mounts `METHOD /full/path` lines.
+/// struct Lines;
+///
+/// impl Backend for Lines {
+///     const NAME: &'static str = "lines";
+///
+///     type App = Vec<String>;
+///     type Mapping = RouteDescriptor;
+///     type Catcher = (String, u16);
+///     type Output = String;
+///
+///     fn descriptor(mapping: &Self::Mapping) -> RouteDescriptor {
+///         mapping.clone()
+///     }
+///
+///     fn catcher_namespace(catcher: &Self::Catcher) -> String {
+///         catcher.0.clone()
+///     }
+///
+///     fn app() -> Self::App {
+///         Vec::new()
+///     }
+///
+///     fn mount(mut app: Self::App, _namespace: &str, mappings: Vec<Self::Mapping>) -> Self::App {
+///         for mapping in mappings {
+///             app.push(format!("{} {}", mapping.method.to_uppercase(), mapping.full_path()));
+///         }
+///         app
+///     }
+///
+///     fn mount_catchers(mut app: Self::App, namespace: &str, catchers: Vec<Self::Catcher>) -> Self::App {
+///         for (_, status) in catchers {
+///             app.push(format!("CATCH {} {}", status, namespace));
+///         }
+///         app
+///     }
+///
+///     fn finish(app: Self::App) -> Self::Output {
+///         app.join("\n")
+///     }
+/// }
+///
+/// let mappings = vec![
+///     RouteDescriptor::new("get_fn", "get", "/", "/get"),
+///     RouteDescriptor::new("namespace_fn", "get", "/hello", "/namespace"),
+/// ];
+/// let catchers = vec![("/hello".to_string(), 404)];
+///
+/// assert_eq!(
+///     build::<Lines>(mappings, catchers),
+///     "GET /get\nGET /hello/namespace\nCATCH 404 /hello"
+/// );
+/// ```
+///
+/// @since 0.3.0
+pub trait Backend {
+    /// The framework name, e.g. in the errors of the mapping macros.
+    const NAME: &'static str;
+
+    /// The app under construction, e.g. a builder or the framework's router.
+    type App;
+    /// A registered handler, as stored in the backend's registry.
+    type Mapping;
+    /// A registered error catcher.
+    type Catcher;
+    /// The built app.
+    type Output;
+
+    /// The framework attribute to put on a mapped handler, e.g. `#[rocket::get("/get")]`,
+    /// or `None` if the handler is registered as is.
+    ///
+    /// Called by the backend's `codegen` crate at compile time, see `autortr_codegen::route_attribute`.
+    fn route_attribute(descriptor: &RouteDescriptor) -> Option<String> {
+        let _ = descriptor;
+        None
+    }
+
+    /// The Rocket-style path template in the framework's syntax, e.g. `/users/{id}`.
+    fn route_path(template: &str) -> Result<String, String> {
+        Ok(template.to_string())
+    }
+
+    /// An expression building the route of the `handler` for the `method`, e.g.
+    /// `|| axum::routing::get(get_fn)`; `None` if the backend doesn't register `HandlerMapping`s.
+    fn route_factory(method: &str, handler: &str) -> Option<String> {
+        let _ = (method, handler);
+        None
+    }
+
+    /// The framework-neutral descriptor of a mapping.
+    fn descriptor(mapping: &Self::Mapping) -> RouteDescriptor;
+
+    /// The namespace a catcher applies to.
+    fn catcher_namespace(catcher: &Self::Catcher) -> String;
+
+    fn app() -> Self::App;
+
+    /// Mounts the mappings of one namespace.
+    fn mount(app: Self::App, namespace: &str, mappings: Vec<Self::Mapping>) -> Self::App;
+
+    /// Mounts the catchers of one namespace.
+    fn mount_catchers(app: Self::App, namespace: &str, catchers: Vec<Self::Catcher>) -> Self::App;
+
+    fn finish(app: Self::App) -> Self::Output;
+}
+
+// ----------------------------------------------------------------
+
+/// Builds an app with the given backend: mounts the mappings, grouped by namespace,
+/// then the catchers, grouped by namespace.
+///
+/// Namespaces are mounted in order, the registration order is kept within a namespace.
+///
+/// @since 0.3.0
+pub fn build<B: Backend>(mappings: Vec<B::Mapping>, catchers: Vec<B::Catcher>) -> B::Output {
+    let mut groups: BTreeMap<String, Vec<B::Mapping>> = BTreeMap::new();
+    for mapping in mappings {
+        let namespace = B::descriptor(&mapping).namespace;
+        groups.entry(namespace).or_default().push(mapping);
+    }
+
+    let mut catcher_groups: BTreeMap<String, Vec<B::Catcher>> = BTreeMap::new();
+    for catcher in catchers {
+        let namespace = B::catcher_namespace(&catcher);
+        catcher_groups.entry(namespace).or_default().push(catcher);
+    }
+
+    let mut app = B::app();
+    for (namespace, mappings) in groups {
+        app = B::mount(app, &namespace, mappings);
+    }
+    for (namespace, catchers) in catcher_groups {
+        app = B::mount_catchers(app, &namespace, catchers);
+    }
+
+    B::finish(app)
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/conflict
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+
+use crate::{dynamic_segment, RouteDescriptor};
+
+// ----------------------------------------------------------------
+
+/// Two routes matching the same requests with the same precedence.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteConflict {
+    pub first: RouteDescriptor,
+    pub second: RouteDescriptor,
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}) {} {} conflicts with ({}) {} {}",
+            self.first.handler,
+            self.first.method.to_uppercase(),
+            self.first.full_path(),
+            self.second.handler,
+            self.second.method.to_uppercase(),
+            self.second.full_path()
+        )
+    }
+}
+
+/// Finds routes that can never be told apart: same method, rank and format,
+/// and colliding paths (a dynamic segment collides with any segment).
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{detect_conflicts, RouteDescriptor};
+///
+/// let descriptors = vec![
+///     RouteDescriptor::new("get_user", "get", "/users", "/<id>").rank(-5),
+///     RouteDescriptor::new("get_me", "get", "/users", "/me").rank(-5),
+///     RouteDescriptor::new("delete_user", "delete", "/users", "/<id>").rank(-5),
+/// ];
+///
+/// let conflicts = detect_conflicts(&descriptors);
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(conflicts[0].first.handler, "get_user");
+/// assert_eq!(conflicts[0].second.handler, "get_me");
+/// ```
+///
+/// @since 0.3.0
+pub fn detect_conflicts(descriptors: &[RouteDescriptor]) -> Vec<RouteConflict> {
+    let mut conflicts = Vec::new();
+
+    for (index, first) in descriptors.iter().enumerate() {
+        for second in &descriptors[index + 1..] {
+            if collide(first, second) {
+                conflicts.push(RouteConflict {
+                    first: first.clone(),
+                    second: second.clone(),
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn collide(first: &RouteDescriptor, second: &RouteDescriptor) -> bool {
+    first.method.eq_ignore_ascii_case(&second.method)
+        && first.rank == second.rank
+        && first.format == second.format
+        && paths_collide(&first.full_path(), &second.full_path())
+}
+
+#[doc(hidden)]
+fn paths_collide(first: &str, second: &str) -> bool {
+    let segments = |path: &str| -> Vec<String> {
+        path.split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (first, second) = (segments(first), segments(second));
+
+    let mut index = 0;
+    loop {
+        match (first.get(index), second.get(index)) {
+            (None, None) => return true,
+            (Some(a), _) if is_trailing(a) => return true,
+            (_, Some(b)) if is_trailing(b) => return true,
+            (Some(a), Some(b)) => {
+                if a != b && dynamic_segment(a).is_none() && dynamic_segment(b).is_none() {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+        index += 1;
+    }
+}
+
+#[doc(hidden)]
+fn is_trailing(segment: &str) -> bool {
+    matches!(dynamic_segment(segment), Some(name) if name.ends_with(".."))
+}
@@ -0,0 +1,117 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/descriptor
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use crate::join_path;
+
+// ----------------------------------------------------------------
+
+/// A framework-neutral description of a mapped handler.
+///
+/// Every adapter keeps its own framework objects (e.g. `rocket::Route`) next to a descriptor,
+/// which is all the shared conflict detection and exporters need.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::RouteDescriptor;
+///
+/// let descriptor = RouteDescriptor::new("http_post_mapping_fn", "post", "/http", "/post")
+///     .body("<form>")
+///     .metadata("tags", "http");
+///
+/// assert_eq!(descriptor.full_path(), "/http/post");
+/// assert_eq!(descriptor.body.as_deref(), Some("<form>"));
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteDescriptor {
+    /// The handler id, the mapped function's name.
+    pub handler: String,
+    /// The lowercase `HTTP` method, e.g. `get`.
+    pub method: String,
+    pub namespace: String,
+    /// The route path template, relative to the `namespace`.
+    pub path: String,
+    /// The Rust types of the path and query parameters, by name, where known, e.g. `u64`.
+    pub parameters: BTreeMap<String, String>,
+    /// The body binding, e.g. `<form>`, if the route takes a body.
+    pub body: Option<String>,
+    pub rank: Option<isize>,
+    pub format: Option<String>,
+    pub deprecated: bool,
+    /// Free-form metadata, for adapters and extensions.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl RouteDescriptor {
+    pub fn new<H, M, N, P>(handler: H, method: M, namespace: N, path: P) -> Self
+    where
+        H: Into<String>,
+        M: Into<String>,
+        N: Into<String>,
+        P: Into<String>,
+    {
+        RouteDescriptor {
+            handler: handler.into(),
+            method: method.into(),
+            namespace: namespace.into(),
+            path: path.into(),
+            ..RouteDescriptor::default()
+        }
+    }
+
+    pub fn parameter<N: Into<String>, T: Into<String>>(mut self, name: N, ty: T) -> Self {
+        self.parameters.insert(name.into(), ty.into());
+        self
+    }
+
+    pub fn body<B: Into<String>>(mut self, body: B) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = Some(rank);
+        self
+    }
+
+    pub fn format<F: Into<String>>(mut self, format: F) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    pub fn metadata<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// The `namespace` joined with the `path`, as mounted by the router.
+    pub fn full_path(&self) -> String {
+        join_path(&self.namespace, &self.path)
+    }
+}
@@ -0,0 +1,277 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/diff
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+
+use crate::{path_shape, template_parameters, RouteEntry, RouteTable};
+
+// ----------------------------------------------------------------
+
+/// How a route changed between two `RouteTable`s.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    MethodChanged { from: String, to: String },
+    PathParameterRenamed { from: Vec<String>, to: Vec<String> },
+    BodyAdded,
+    Deprecated,
+}
+
+impl ChangeKind {
+    /// Whether existing clients may break: removed routes, changed methods and newly required bodies.
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            ChangeKind::Removed | ChangeKind::MethodChanged { .. } | ChangeKind::BodyAdded
+        )
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::MethodChanged { from, to } => {
+                write!(f, "method changed {} -> {}", from, to)
+            }
+            ChangeKind::PathParameterRenamed { from, to } => write!(
+                f,
+                "path parameter renamed <{}> -> <{}>",
+                from.join(">, <"),
+                to.join(">, <")
+            ),
+            ChangeKind::BodyAdded => write!(f, "body added"),
+            ChangeKind::Deprecated => write!(f, "deprecated"),
+        }
+    }
+}
+
+/// A classified difference, with the route before and/or after the change.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteChange {
+    pub kind: ChangeKind,
+    pub before: Option<RouteEntry>,
+    pub after: Option<RouteEntry>,
+}
+
+impl RouteChange {
+    pub fn is_breaking(&self) -> bool {
+        self.kind.is_breaking()
+    }
+
+    /// The route as it is after the change, or as it was if removed.
+    pub fn route(&self) -> &RouteEntry {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .expect("a changed route")
+    }
+}
+
+impl fmt::Display for RouteChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let route = self.route();
+        write!(
+            f,
+            "{}: {} {} ({})",
+            self.kind, route.method, route.path, route.function
+        )
+    }
+}
+
+/// Every classified difference between two `RouteTable`s.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{diff_route_tables, ChangeKind, RouteTable};
+///
+/// let old = RouteTable::from_text(concat!(
+///     "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+///     "DELETE /users/<id> rank=-5 format=- data=- -> delete_user\n",
+/// ))
+/// .unwrap();
+/// let new = RouteTable::from_text(concat!(
+///     "GET /users/<user_id> rank=-5 format=- data=- -> get_user\n",
+///     "POST /users rank=-9 format=- data=<form> -> create_user\n",
+/// ))
+/// .unwrap();
+///
+/// let diff = diff_route_tables(&old, &new);
+/// assert!(diff.has_breaking_changes());
+/// assert_eq!(diff.breaking().len(), 1);
+/// assert_eq!(diff.breaking()[0].kind, ChangeKind::Removed);
+/// assert_eq!(diff.non_breaking().len(), 2);
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteDiff {
+    pub changes: Vec<RouteChange>,
+}
+
+impl RouteDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(RouteChange::is_breaking)
+    }
+
+    pub fn breaking(&self) -> Vec<&RouteChange> {
+        self.changes.iter().filter(|c| c.is_breaking()).collect()
+    }
+
+    pub fn non_breaking(&self) -> Vec<&RouteChange> {
+        self.changes.iter().filter(|c| !c.is_breaking()).collect()
+    }
+
+    /// A human-readable report, breaking changes first.
+    pub fn to_report(&self) -> String {
+        if self.is_empty() {
+            return "No route changes.\n".to_string();
+        }
+
+        let mut report = String::new();
+        for (title, changes) in [
+            ("Breaking changes", self.breaking()),
+            ("Non-breaking changes", self.non_breaking()),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            report.push_str(&format!("{} ({}):\n", title, changes.len()));
+            for change in changes {
+                report.push_str(&format!("  - {}\n", change));
+            }
+        }
+
+        report
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Compares two route tables, e.g. exported from `main` and from a pull request.
+///
+/// Routes are matched by method and path shape (dynamic segment names ignored);
+/// unmatched routes with the same shape and handler are reported as method changes.
+///
+/// @since 0.3.0
+pub fn diff_route_tables(old: &RouteTable, new: &RouteTable) -> RouteDiff {
+    let mut changes = Vec::new();
+    let mut unmatched_new: Vec<&RouteEntry> = new.routes.iter().collect();
+    let mut unmatched_old = Vec::new();
+
+    for before in &old.routes {
+        let position = unmatched_new
+            .iter()
+            .position(|after| after.method == before.method && shape(after) == shape(before));
+
+        match position {
+            Some(position) => {
+                let after = unmatched_new.remove(position);
+                changes.extend(compare(before, after));
+            }
+            None => unmatched_old.push(before),
+        }
+    }
+
+    for before in unmatched_old {
+        let position = unmatched_new
+            .iter()
+            .position(|after| after.function == before.function && shape(after) == shape(before));
+
+        match position {
+            Some(position) => {
+                let after = unmatched_new.remove(position);
+                changes.push(change(
+                    ChangeKind::MethodChanged {
+                        from: before.method.clone(),
+                        to: after.method.clone(),
+                    },
+                    Some(before),
+                    Some(after),
+                ));
+            }
+            None => changes.push(change(ChangeKind::Removed, Some(before), None)),
+        }
+    }
+
+    for after in unmatched_new {
+        changes.push(change(ChangeKind::Added, None, Some(after)));
+    }
+
+    RouteDiff { changes }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn compare(before: &RouteEntry, after: &RouteEntry) -> Vec<RouteChange> {
+    let mut changes = Vec::new();
+
+    let (from, to) = (parameters(before), parameters(after));
+    if from != to {
+        changes.push(change(
+            ChangeKind::PathParameterRenamed { from, to },
+            Some(before),
+            Some(after),
+        ));
+    }
+    if before.data.is_none() && after.data.is_some() {
+        changes.push(change(ChangeKind::BodyAdded, Some(before), Some(after)));
+    }
+    if !before.deprecated && after.deprecated {
+        changes.push(change(ChangeKind::Deprecated, Some(before), Some(after)));
+    }
+
+    changes
+}
+
+#[doc(hidden)]
+fn change(
+    kind: ChangeKind,
+    before: Option<&RouteEntry>,
+    after: Option<&RouteEntry>,
+) -> RouteChange {
+    RouteChange {
+        kind,
+        before: before.cloned(),
+        after: after.cloned(),
+    }
+}
+
+#[doc(hidden)]
+fn shape(entry: &RouteEntry) -> String {
+    path_shape(&entry.path)
+}
+
+#[doc(hidden)]
+fn parameters(entry: &RouteEntry) -> Vec<String> {
+    template_parameters(&entry.path)
+}
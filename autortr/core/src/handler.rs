@@ -0,0 +1,106 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/handler
+
+// ----------------------------------------------------------------
+
+use crate::{join_path, RouteDescriptor, RouteTable, EMPTY};
+
+// ----------------------------------------------------------------
+
+/// A handler registered by the mapping macros of an adapter whose framework routes plain
+/// handlers, e.g. axum, actix-web or poem; `R` is the framework's route, e.g. an axum `MethodRouter`.
+///
+/// The adapter's `Backend` translates the `path` (`Backend::route_path`) and builds the `route`
+/// factory (`Backend::route_factory`) at compile time, see `autortr_codegen::expand_handler`.
+///
+/// @since 0.3.0
+pub struct HandlerMapping<R> {
+    pub function: String,
+    pub namespace: String,
+    pub method: String,
+    /// The Rocket-style path template, e.g. `/users/<id>`.
+    pub path: String,
+    /// The path as written on the mapping, e.g. a Spring-style `/users/{id}`.
+    pub pattern: String,
+    /// The `path` in the framework's syntax, e.g. `/users/{id}` or `/users/:id`.
+    pub route_path: String,
+    pub data: String,
+    /// Whether the handler is marked `#[deprecated]`.
+    pub deprecated: bool,
+    /// Builds the handler's route.
+    pub route: fn() -> R,
+}
+
+impl<R> HandlerMapping<R> {
+    /// The `namespace` joined with the `path`, as mounted by the router.
+    pub fn full_path(&self) -> String {
+        join_path(&self.namespace, &self.path)
+    }
+
+    /// The framework-neutral descriptor of the mapped handler.
+    pub fn descriptor(&self) -> RouteDescriptor {
+        RouteDescriptor {
+            handler: self.function.clone(),
+            method: self.method.clone(),
+            namespace: self.namespace.clone(),
+            path: self.path.clone(),
+            parameters: Default::default(),
+            body: match self.data.as_str() {
+                EMPTY => None,
+                data => Some(data.to_string()),
+            },
+            rank: None,
+            format: None,
+            deprecated: self.deprecated,
+            metadata: Default::default(),
+        }
+    }
+}
+
+// `fn() -> R` is `Clone` whatever `R` is.
+impl<R> Clone for HandlerMapping<R> {
+    fn clone(&self) -> Self {
+        HandlerMapping {
+            function: self.function.clone(),
+            namespace: self.namespace.clone(),
+            method: self.method.clone(),
+            path: self.path.clone(),
+            pattern: self.pattern.clone(),
+            route_path: self.route_path.clone(),
+            data: self.data.clone(),
+            deprecated: self.deprecated,
+            route: self.route,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Returns the framework-neutral descriptors of the given handler mappings.
+///
+/// @since 0.3.0
+pub fn handler_descriptors<R>(mappings: &[HandlerMapping<R>]) -> Vec<RouteDescriptor> {
+    mappings.iter().map(HandlerMapping::descriptor).collect()
+}
+
+/// Returns the sorted `RouteTable` of the given handler mappings.
+///
+/// @since 0.3.0
+pub fn handler_table<R>(mappings: &[HandlerMapping<R>]) -> RouteTable {
+    RouteTable::from_descriptors(&handler_descriptors(mappings))
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/lib
+
+// ----------------------------------------------------------------
+
+mod authority;
+mod backend;
+mod conflict;
+mod descriptor;
+mod diff;
+mod handler;
+mod openapi;
+mod rate_limit;
+mod registry;
+mod table;
+mod template;
+
+pub use authority::*;
+pub use backend::*;
+pub use conflict::*;
+pub use descriptor::*;
+pub use diff::*;
+pub use handler::*;
+pub use openapi::*;
+pub use rate_limit::*;
+pub use registry::*;
+pub use table::*;
+pub use template::*;
+
+// ----------------------------------------------------------------
+
+pub const GET: &str = "get";
+pub const POST: &str = "post";
+pub const PUT: &str = "put";
+pub const PATCH: &str = "patch";
+pub const DELETE: &str = "delete";
+pub const HEAD: &str = "head";
+
+// ----------------------------------------------------------------
+
+pub const NAMESPACE: &str = "namespace";
+pub const METHOD: &str = "method";
+pub const PATH: &str = "path";
+pub const DATA: &str = "data";
+pub const DEPRECATED: &str = "deprecated";
+pub const RANK: &str = "rank";
+pub const REST: &str = "rest";
+pub const EXCEPT: &str = "except";
+pub const VALIDATE: &str = "validate";
+pub const TAGS: &str = "tags";
+pub const GUARDS: &str = "guards";
+pub const AUTHENTICATED: &str = "authenticated";
+pub const PRE_AUTHORIZE: &str = "pre_authorize";
+pub const AUTHORITIES: &str = "authorities";
+pub const SCHEME: &str = "scheme";
+pub const SECRET: &str = "secret";
+pub const CORS: &str = "cors";
+pub const RATE_LIMIT: &str = "rate_limit";
+pub const RATE_LIMIT_KEY: &str = "rate_limit_key";
+
+// ----------------------------------------------------------------
+
+pub const PATH_VAR: &str = "path_var";
+pub const QUERY_PARAM: &str = "query_param";
+pub const REQUEST_BODY: &str = "request_body";
+pub const VALID: &str = "valid";
+
+// ----------------------------------------------------------------
+
+pub const ORDER: &str = "order";
+pub const INCLUDE: &str = "include";
+pub const EXCLUDE: &str = "exclude";
+pub const FUNCTIONS: &str = "functions";
+
+// ----------------------------------------------------------------
+
+pub const ROOT: &str = "/";
+pub const EMPTY: &str = "_";
@@ -0,0 +1,231 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/openapi
+
+// ----------------------------------------------------------------
+
+use serde_json::{json, Map, Value};
+
+use crate::{dynamic_segment, RouteDescriptor, GUARDS, PRE_AUTHORIZE, RATE_LIMIT};
+
+// ----------------------------------------------------------------
+
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// The operation extension of routes collecting every query field, e.g. `?<params..>`.
+pub const ADDITIONAL_QUERY: &str = "x-autortr-additional-query";
+
+/// The path parameter extension of trailing segments matching the rest of the path, e.g. `<path..>`.
+pub const TRAILING_SEGMENTS: &str = "x-autortr-trailing";
+
+// ----------------------------------------------------------------
+
+/// The OpenAPI path of a route template: `<id>` becomes `{id}`, the query is dropped.
+///
+/// Ignored segments are numbered, `<_>` becomes `{_0}`, then `{_1}`, and so on.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::openapi_path;
+///
+/// assert_eq!(openapi_path("/users/<id>/files/<path..>?<q>"), "/users/{id}/files/{path}");
+/// assert_eq!(openapi_path("/users/<_>/posts/<_..>"), "/users/{_0}/posts/{_1}");
+/// ```
+///
+/// @since 0.3.0
+pub fn openapi_path(template: &str) -> String {
+    let path = template.split('?').next().unwrap_or_default();
+    let mut ignored = 0;
+
+    path.split('/')
+        .map(|segment| match dynamic_segment(segment) {
+            Some(name) => format!("{{{}}}", parameter_name(name, &mut ignored)),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// A minimal OpenAPI document of the descriptors: one operation per route, named after
+/// its handler, with its path and query parameters typed after the handler's parameters
+/// (`integer`, `number`, `boolean`, `string` otherwise) and the statuses it can answer:
+///
+/// - `2XX` from the handler;
+/// - `422` for parameters or a body failing to parse, `400`, `413` and `415` for bodies;
+/// - `401` and `403` for `guards` and `pre_authorize` expressions;
+/// - `429` for a `rate_limit`.
+///
+/// The document carries no schemas, nor the statuses answered by the handlers themselves,
+/// e.g. through an exception handler: it is meant to be extended, e.g. before handing it to
+/// a contract validator.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{openapi_document, RouteDescriptor};
+///
+/// let descriptors = vec![
+///     RouteDescriptor::new("user_fn", "get", "/users", "/<id>?<verbose>").parameter("id", "u64"),
+/// ];
+/// let document = openapi_document("hello", "0.1.0", &descriptors);
+///
+/// let operation = &document["paths"]["/users/{id}"]["get"];
+/// assert_eq!(operation["operationId"], "user_fn");
+/// assert_eq!(operation["parameters"][0]["in"], "path");
+/// assert_eq!(operation["parameters"][0]["schema"]["type"], "integer");
+/// assert_eq!(operation["parameters"][1]["name"], "verbose");
+/// assert_eq!(operation["parameters"][1]["schema"]["type"], "string");
+/// assert!(operation["responses"]["2XX"].is_object());
+/// assert!(operation["responses"]["422"].is_object());
+/// assert!(operation["responses"]["401"].is_null());
+/// ```
+///
+/// @since 0.3.0
+pub fn openapi_document(title: &str, version: &str, descriptors: &[RouteDescriptor]) -> Value {
+    let mut paths = Map::new();
+
+    for descriptor in descriptors {
+        let template = descriptor.full_path();
+        let item = paths
+            .entry(openapi_path(&template))
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        if let Value::Object(item) = item {
+            item.insert(descriptor.method.clone(), operation(descriptor, &template));
+        }
+    }
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": title, "version": version },
+        "paths": paths,
+    })
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn operation(descriptor: &RouteDescriptor, template: &str) -> Value {
+    let (path, query) = match template.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (template, None),
+    };
+
+    let mut parameters = Vec::new();
+    let mut ignored = 0;
+    for segment in path.split('/').filter_map(dynamic_segment) {
+        let name = parameter_name(segment, &mut ignored);
+
+        let mut parameter = parameter(descriptor, &name, "path", true);
+        if segment.ends_with("..") {
+            parameter[TRAILING_SEGMENTS] = Value::Bool(true);
+        }
+        parameters.push(parameter);
+    }
+
+    let mut additional_query = false;
+    for name in query.into_iter().flat_map(|query| query.split('&')) {
+        match dynamic_segment(name) {
+            Some(name) if name.ends_with("..") => additional_query = true,
+            Some(name) => parameters.push(parameter(descriptor, name, "query", false)),
+            None => {}
+        }
+    }
+
+    let mut operation = json!({
+        "operationId": descriptor.handler,
+        "parameters": parameters,
+        "responses": responses(descriptor, !parameters.is_empty() || additional_query),
+    });
+
+    if descriptor.body.is_some() {
+        operation["requestBody"] = json!({ "content": { "*/*": {} } });
+    }
+    if descriptor.deprecated {
+        operation["deprecated"] = Value::Bool(true);
+    }
+    if additional_query {
+        operation[ADDITIONAL_QUERY] = Value::Bool(true);
+    }
+
+    operation
+}
+
+#[doc(hidden)]
+fn parameter(descriptor: &RouteDescriptor, name: &str, location: &str, required: bool) -> Value {
+    let ty = descriptor.parameters.get(name).map(String::as_str);
+
+    json!({
+        "name": name,
+        "in": location,
+        "required": required,
+        "schema": { "type": schema_type(ty.unwrap_or_default()) },
+    })
+}
+
+/// The JSON type of a parameter's Rust type; `Option`s and `Result`s accept any value.
+#[doc(hidden)]
+fn schema_type(ty: &str) -> &'static str {
+    match ty.rsplit("::").next().unwrap_or_default() {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => "integer",
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => "integer",
+        "f32" | "f64" => "number",
+        "bool" => "boolean",
+        _ => "string",
+    }
+}
+
+#[doc(hidden)]
+fn responses(descriptor: &RouteDescriptor, parameters: bool) -> Value {
+    let mut statuses = vec![("2XX", "Success")];
+    if parameters || descriptor.body.is_some() {
+        statuses.push(("422", "Unprocessable Entity"));
+    }
+    if descriptor.body.is_some() {
+        statuses.extend([
+            ("400", "Bad Request"),
+            ("413", "Payload Too Large"),
+            ("415", "Unsupported Media Type"),
+        ]);
+    }
+    let metadata = &descriptor.metadata;
+    if metadata.contains_key(GUARDS) || metadata.contains_key(PRE_AUTHORIZE) {
+        statuses.extend([("401", "Unauthorized"), ("403", "Forbidden")]);
+    }
+    if metadata.contains_key(RATE_LIMIT) {
+        statuses.push(("429", "Too Many Requests"));
+    }
+
+    statuses
+        .into_iter()
+        .map(|(status, description)| (status.to_string(), json!({ "description": description })))
+        .collect::<Map<String, Value>>()
+        .into()
+}
+
+/// The parameter name of a dynamic segment, numbering the ignored ones.
+#[doc(hidden)]
+fn parameter_name(segment: &str, ignored: &mut usize) -> String {
+    match segment.trim_end_matches("..") {
+        "_" => {
+            *ignored += 1;
+            format!("_{}", *ignored - 1)
+        }
+        name => name.to_string(),
+    }
+}
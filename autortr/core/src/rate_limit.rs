@@ -0,0 +1,113 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/rate_limit
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+
+// ----------------------------------------------------------------
+
+/// A parsed `rate_limit` of a mapping: `requests` per `period` seconds.
+///
+/// Limits are written `<requests>/<period>`, where the period is a unit, optionally preceded
+/// by a count: `second`, `minute`, `hour` or `day`, their plurals, or `s`, `m`, `h` and `d`.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::RateLimit;
+///
+/// let limit = RateLimit::parse("100/minute").unwrap();
+/// assert_eq!(limit, RateLimit::new(100, 60));
+/// assert_eq!(limit.policy(), "100;w=60");
+///
+/// assert_eq!(RateLimit::parse("10/30s"), Ok(RateLimit::new(10, 30)));
+/// assert_eq!(RateLimit::parse("5 / 2 hours"), Ok(RateLimit::new(5, 7200)));
+/// assert_eq!(RateLimit::new(10, 30).to_string(), "10/30s");
+///
+/// assert!(RateLimit::parse("100").is_err());
+/// assert!(RateLimit::parse("0/minute").is_err());
+/// assert!(RateLimit::parse("100/fortnight").is_err());
+/// assert!(RateLimit::parse("1/999999999999999d").is_err());
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    pub requests: u64,
+    /// The window, in seconds.
+    pub period: u64,
+}
+
+impl RateLimit {
+    pub const fn new(requests: u64, period: u64) -> Self {
+        RateLimit { requests, period }
+    }
+
+    pub fn parse(limit: &str) -> Result<Self, String> {
+        let (requests, period) = match limit.split_once('/') {
+            Some((requests, period)) => (requests.trim(), period.trim()),
+            None => return Err(format!("Expected `<requests>/<period>`, got `{}`", limit)),
+        };
+
+        let requests = match requests.parse::<u64>() {
+            Ok(requests) if requests > 0 => requests,
+            _ => {
+                return Err(format!(
+                    "Invalid request count `{}` in `{}`",
+                    requests, limit
+                ))
+            }
+        };
+
+        let split = period
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(period.len());
+        let (count, unit) = period.split_at(split);
+        let count = match count {
+            "" => 1,
+            count => match count.parse::<u64>() {
+                Ok(count) if count > 0 => count,
+                _ => return Err(format!("Invalid period `{}` in `{}`", period, limit)),
+            },
+        };
+        let seconds = match unit.trim() {
+            "s" | "sec" | "second" | "seconds" => 1,
+            "m" | "min" | "minute" | "minutes" => 60,
+            "h" | "hour" | "hours" => 3600,
+            "d" | "day" | "days" => 86400,
+            unit => return Err(format!("Unknown period unit `{}` in `{}`", unit, limit)),
+        };
+
+        match count.checked_mul(seconds) {
+            Some(period) => Ok(RateLimit::new(requests, period)),
+            None => Err(format!("Period `{}` is too long in `{}`", period, limit)),
+        }
+    }
+
+    /// The `RateLimit-Policy` header value, e.g. `100;w=60`.
+    pub fn policy(&self) -> String {
+        format!("{};w={}", self.requests, self.period)
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}s", self.requests, self.period)
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/registry
+
+// ----------------------------------------------------------------
+
+use std::sync::Mutex;
+
+// ----------------------------------------------------------------
+
+/// A registry of route mappings, filled by the mapping macros before `main` runs.
+///
+/// Keeps two views: the live mappings, cleaned once a router has consumed them, and
+/// a snapshot of every mapping ever registered, e.g. to build one app per test.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::Registry;
+///
+/// static MAPPINGS: Registry<&'static str> = Registry::new();
+///
+/// MAPPINGS.register("get_fn");
+/// assert_eq!(MAPPINGS.acquire(), vec!["get_fn"]);
+///
+/// MAPPINGS.clean();
+/// assert!(MAPPINGS.acquire().is_empty());
+/// assert_eq!(MAPPINGS.snapshot(), vec!["get_fn"]);
+/// ```
+///
+/// @since 0.3.0
+pub struct Registry<T> {
+    mappings: Mutex<Vec<T>>,
+    snapshots: Mutex<Vec<T>>,
+}
+
+impl<T: Clone> Registry<T> {
+    pub const fn new() -> Self {
+        Registry {
+            mappings: Mutex::new(Vec::new()),
+            snapshots: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, mapping: T) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        snapshots.push(mapping.clone());
+
+        let mut mappings = self.mappings.lock().unwrap();
+        mappings.push(mapping);
+    }
+
+    /// The live mappings, not yet cleaned.
+    pub fn acquire(&self) -> Vec<T> {
+        let mappings = self.mappings.lock().unwrap();
+        mappings.clone()
+    }
+
+    /// Every mapping registered so far, regardless of `clean`.
+    pub fn snapshot(&self) -> Vec<T> {
+        let snapshots = self.snapshots.lock().unwrap();
+        snapshots.clone()
+    }
+
+    pub fn clean(&self) {
+        let mut mappings = self.mappings.lock().unwrap();
+        mappings.clear();
+    }
+}
+
+impl<T: Clone> Default for Registry<T> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
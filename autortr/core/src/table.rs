@@ -0,0 +1,192 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/table
+
+// ----------------------------------------------------------------
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RouteDescriptor, DEPRECATED};
+
+// ----------------------------------------------------------------
+
+/// One row of a `RouteTable`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RouteEntry {
+    pub method: String,
+    pub path: String,
+    pub rank: isize,
+    pub format: Option<String>,
+    pub data: Option<String>,
+    pub function: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+}
+
+impl RouteEntry {
+    /// The stable, single-line text form used by `RouteTable::to_text`.
+    pub fn to_line(&self) -> String {
+        let line = format!(
+            "{} {} rank={} format={} data={} -> {}",
+            self.method,
+            self.path,
+            self.rank,
+            self.format.as_deref().unwrap_or("-"),
+            self.data.as_deref().unwrap_or("-"),
+            self.function
+        );
+
+        match self.deprecated {
+            true => format!("{} {}", line, DEPRECATED),
+            false => line,
+        }
+    }
+
+    /// Parses a line written by `to_line`.
+    pub fn from_line(line: &str) -> Option<Self> {
+        let (route, function) = line.split_once(" -> ")?;
+        let mut function = function.split_whitespace();
+        let (function, deprecated) = (function.next()?, function.next() == Some(DEPRECATED));
+
+        let mut parts = route.split_whitespace();
+        let (method, path) = (parts.next()?, parts.next()?);
+        let rank = parts.next()?.strip_prefix("rank=")?.parse().ok()?;
+        let format = optional(parts.next()?.strip_prefix("format=")?);
+        let data = optional(parts.next()?.strip_prefix("data=")?);
+
+        Some(RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            rank,
+            format,
+            data,
+            function: function.to_string(),
+            deprecated,
+        })
+    }
+}
+
+/// A sorted, serializable view of registered route mappings: the public `HTTP` API of an app.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::{RouteEntry, RouteTable};
+///
+/// let table = RouteTable::new(vec![RouteEntry {
+///     method: "GET".to_string(),
+///     path: "/http/get".to_string(),
+///     rank: -12,
+///     format: None,
+///     data: None,
+///     function: "http_get_mapping_fn".to_string(),
+///     deprecated: false,
+/// }]);
+///
+/// assert_eq!(
+///     table.to_text(),
+///     "GET /http/get rank=-12 format=- data=- -> http_get_mapping_fn\n"
+/// );
+/// assert_eq!(RouteTable::from_json(&table.to_json()).unwrap(), table);
+/// assert_eq!(RouteTable::from_text(&table.to_text()).unwrap(), table);
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteTable {
+    pub routes: Vec<RouteEntry>,
+}
+
+impl RouteTable {
+    pub fn new(routes: Vec<RouteEntry>) -> Self {
+        let mut routes = routes;
+        routes.sort_by(|a, b| {
+            (&a.path, &a.method, a.rank, &a.function).cmp(&(
+                &b.path,
+                &b.method,
+                b.rank,
+                &b.function,
+            ))
+        });
+
+        RouteTable { routes }
+    }
+
+    pub fn from_descriptors(descriptors: &[RouteDescriptor]) -> Self {
+        let routes = descriptors
+            .iter()
+            .map(|descriptor| RouteEntry {
+                method: descriptor.method.to_uppercase(),
+                path: descriptor.full_path(),
+                rank: descriptor.rank.unwrap_or_default(),
+                format: descriptor.format.clone(),
+                data: descriptor.body.clone(),
+                function: descriptor.handler.clone(),
+                deprecated: descriptor.deprecated,
+            })
+            .collect();
+
+        RouteTable::new(routes)
+    }
+
+    pub fn to_text(&self) -> String {
+        self.routes
+            .iter()
+            .map(|entry| format!("{}\n", entry.to_line()))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).expect("serializable route table");
+        json.push('\n');
+        json
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Parses a table written by `to_text`, returning the first invalid line on failure.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut routes = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match RouteEntry::from_line(line) {
+                Some(entry) => routes.push(entry),
+                None => return Err(line.to_string()),
+            }
+        }
+
+        Ok(RouteTable::new(routes))
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[doc(hidden)]
+fn optional(value: &str) -> Option<String> {
+    match value {
+        "-" => None,
+        value => Some(value.to_string()),
+    }
+}
@@ -0,0 +1,548 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/template
+
+// ----------------------------------------------------------------
+
+use crate::ROOT;
+
+// ----------------------------------------------------------------
+
+/// Joins a mount base and a route path the way `Rocket::mount` does.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::join_path;
+///
+/// assert_eq!(join_path("/", "/get"), "/get");
+/// assert_eq!(join_path("/rocket", "/data"), "/rocket/data");
+/// assert_eq!(join_path("/rocket/", "/"), "/rocket");
+/// ```
+///
+/// @since 0.3.0
+pub fn join_path(namespace: &str, path: &str) -> String {
+    let namespace = namespace.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+
+    match (namespace.is_empty(), path.is_empty()) {
+        (true, true) => ROOT.to_string(),
+        (true, false) => format!("/{}", path),
+        (false, true) => namespace.to_string(),
+        (false, false) => format!("{}/{}", namespace, path),
+    }
+}
+
+/// Splits a namespace at its first dynamic segment into the static mount base and the
+/// dynamic rest, which has to be folded into the route path.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::split_namespace;
+///
+/// assert_eq!(split_namespace("/rocket"), ("/rocket".to_string(), "".to_string()));
+/// assert_eq!(
+///     split_namespace("/tenants/<tenant>/admin"),
+///     ("/tenants".to_string(), "/<tenant>/admin".to_string())
+/// );
+/// assert_eq!(split_namespace("/<tenant>"), ("/".to_string(), "/<tenant>".to_string()));
+/// ```
+///
+/// @since 0.3.0
+pub fn split_namespace(namespace: &str) -> (String, String) {
+    let segments: Vec<&str> = namespace.split('/').collect();
+
+    match segments
+        .iter()
+        .position(|segment| dynamic_segment(segment).is_some())
+    {
+        Some(index) => (
+            join_path(&segments[..index].join("/"), ""),
+            format!("/{}", segments[index..].join("/")),
+        ),
+        None => (namespace.to_string(), String::new()),
+    }
+}
+
+/// Whether `namespace` lies within `scope`, segment-wise; `/` contains every namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::namespace_contains;
+///
+/// assert!(namespace_contains("/", "/rocket"));
+/// assert!(namespace_contains("/rocket", "/rocket/admin"));
+/// assert!(!namespace_contains("/rocket", "/rockets"));
+/// ```
+///
+/// @since 0.3.0
+pub fn namespace_contains(scope: &str, namespace: &str) -> bool {
+    let scope = scope.trim_end_matches('/');
+
+    scope.is_empty()
+        || namespace.trim_end_matches('/') == scope
+        || namespace
+            .strip_prefix(scope)
+            .map(|rest| rest.starts_with('/'))
+            .unwrap_or(false)
+}
+
+/// Whether a request path matches a route template: `<name>` matches one segment,
+/// a trailing `<name..>` the rest of the path. The query is ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::path_matches;
+///
+/// assert!(path_matches("/users/<id>", "/users/7"));
+/// assert!(path_matches("/static/<path..>", "/static/css/site.css"));
+/// assert!(path_matches("/static/<path..>", "/static"));
+/// assert!(!path_matches("/users/<id>", "/users/7/posts"));
+/// ```
+///
+/// @since 0.3.0
+pub fn path_matches(template: &str, path: &str) -> bool {
+    let template = template.split('?').next().unwrap_or_default();
+    let parts: Vec<&str> = template
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    let segments: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+
+    for (index, part) in parts.iter().enumerate() {
+        match dynamic_segment(part) {
+            Some(name) if name.ends_with("..") => return index + 1 == parts.len(),
+            Some(_) if index < segments.len() => {}
+            None if segments.get(index) == Some(part) => {}
+            _ => return false,
+        }
+    }
+
+    parts.len() == segments.len()
+}
+
+/// Whether a route template matches an Ant-style pattern: `*` matches one segment,
+/// `**` any number of segments, anything else the same segment.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::ant_matches;
+///
+/// assert!(ant_matches("/api/**", "/api/users/<id>"));
+/// assert!(ant_matches("/api/*/<id>", "/api/users/<id>"));
+/// assert!(ant_matches("/**", "/"));
+/// assert!(!ant_matches("/api/public/**", "/api/users"));
+/// ```
+///
+/// @since 0.3.0
+pub fn ant_matches(pattern: &str, template: &str) -> bool {
+    let template = template.split('?').next().unwrap_or_default();
+    let pattern: Vec<&str> = pattern.split('/').filter(|part| !part.is_empty()).collect();
+    let segments: Vec<&str> = template
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    ant_segments(&pattern, &segments)
+}
+
+/// The name of a dynamic `<name>` (or `<name..>`) template segment.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::dynamic_segment;
+///
+/// assert_eq!(dynamic_segment("<id>"), Some("id"));
+/// assert_eq!(dynamic_segment("<path..>"), Some("path.."));
+/// assert_eq!(dynamic_segment("users"), None);
+/// ```
+///
+/// @since 0.3.0
+pub fn dynamic_segment(segment: &str) -> Option<&str> {
+    segment.strip_prefix('<')?.strip_suffix('>')
+}
+
+/// Converts a Rocket-style path template to a framework's path syntax, writing each dynamic
+/// segment with `segment(name, trailing)`, where `trailing` is set for a trailing `<name..>`.
+///
+/// The query part (`?<q>`) is dropped, since the frameworks bind queries with extractors.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::convert_path;
+///
+/// let braces = |name: &str, trailing: bool| match trailing {
+///     true => format!("{{*{}}}", name),
+///     false => format!("{{{}}}", name),
+/// };
+///
+/// assert_eq!(convert_path("/users/<id>", braces).unwrap(), "/users/{id}");
+/// assert_eq!(convert_path("/files/<path..>?<q>", braces).unwrap(), "/files/{*path}");
+///
+/// assert!(convert_path("/users/<id", braces).is_err());
+/// assert!(convert_path("/files/<path..>/raw", braces).is_err());
+/// ```
+///
+/// @since 0.3.0
+pub fn convert_path<F>(template: &str, segment: F) -> Result<String, String>
+where
+    F: Fn(&str, bool) -> String,
+{
+    let path = template.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').collect();
+    let last = segments.len() - 1;
+
+    let mut converted = Vec::with_capacity(segments.len());
+    for (index, part) in segments.into_iter().enumerate() {
+        match dynamic_segment(part) {
+            Some(name) if name.ends_with("..") => {
+                if index != last {
+                    return Err(format!(
+                        "`<{}>` must be the last segment of `{}`",
+                        name, template
+                    ));
+                }
+                converted.push(segment(name.trim_end_matches(".."), true));
+            }
+            Some(name) => converted.push(segment(name, false)),
+            None if part.contains(['<', '>']) => {
+                return Err(format!("Invalid segment `{}` in `{}`", part, template));
+            }
+            None => converted.push(part.to_string()),
+        }
+    }
+
+    Ok(converted.join("/"))
+}
+
+/// The template with dynamic segment names erased, so that equivalent routes compare equal.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::path_shape;
+///
+/// assert_eq!(path_shape("/users/<id>/files/<path..>"), "/users/<>/files/<..>");
+/// ```
+///
+/// @since 0.3.0
+pub fn path_shape(template: &str) -> String {
+    let mut shape = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('<') {
+        shape.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => {
+                let name = &rest[start + 1..start + end];
+                shape.push_str(if name.ends_with("..") { "<..>" } else { "<>" });
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    shape.push_str(rest);
+
+    shape
+}
+
+/// The names of the dynamic path and query segments, in order.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::template_parameters;
+///
+/// assert_eq!(
+///     template_parameters("/users/<id>/files/<path..>?<q>"),
+///     vec!["id", "path", "q"]
+/// );
+/// ```
+///
+/// @since 0.3.0
+pub fn template_parameters(template: &str) -> Vec<String> {
+    template
+        .split(['/', '?', '&'])
+        .filter_map(dynamic_segment)
+        .map(|name| name.trim_end_matches("..").to_string())
+        .collect()
+}
+
+/// Whether the template uses Spring-style placeholders (`{name}`) or Ant wildcards (`*`, `**`).
+///
+/// @since 0.3.0
+pub fn is_spring_pattern(template: &str) -> bool {
+    template.contains('{')
+        || template
+            .split(['/', '?', '&'])
+            .any(|segment| segment == "*" || segment == "**")
+}
+
+/// A regex constraint on a path variable: `{id:\d+}`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathConstraint {
+    pub name: String,
+    /// The regex as written, unanchored.
+    pub regex: String,
+}
+
+impl PathConstraint {
+    /// The regex anchored to match a whole segment.
+    pub fn anchored(&self) -> String {
+        format!("^(?:{})$", self.regex)
+    }
+}
+
+/// Translates a Spring-style path pattern into a Rocket-style template.
+///
+/// | pattern        | template    |
+/// |----------------|-------------|
+/// | `{name}`       | `<name>`    |
+/// | `{name:regex}` | `<name>`    |
+/// | `*`            | `<_>`       |
+/// | `**`           | `<path..>`  |
+/// | `{*name}`      | `<name..>`  |
+///
+/// Placeholders and wildcards must span a whole segment, `**` and `{*name}` must be the last one.
+/// Rocket-style segments are kept as is, so templates without placeholders are returned unchanged.
+/// Regex constraints are dropped here, see `pattern_constraints`.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::translate_pattern;
+///
+/// assert_eq!(translate_pattern("/users/{id}").unwrap(), "/users/<id>");
+/// assert_eq!(translate_pattern("/users/*/posts").unwrap(), "/users/<_>/posts");
+/// assert_eq!(translate_pattern("/static/**").unwrap(), "/static/<path..>");
+/// assert_eq!(translate_pattern("/files/{*rest}").unwrap(), "/files/<rest..>");
+/// assert_eq!(translate_pattern("/search?{q}&<page>").unwrap(), "/search?<q>&<page>");
+/// assert_eq!(translate_pattern("/users/<id>").unwrap(), "/users/<id>");
+/// assert_eq!(translate_pattern(r"/zip/{code:\d{5}}/x").unwrap(), "/zip/<code>/x");
+///
+/// assert!(translate_pattern("/static/**/raw").is_err());
+/// assert!(translate_pattern("/files/{name}.pdf").is_err());
+/// ```
+///
+/// @since 0.3.0
+pub fn translate_pattern(pattern: &str) -> Result<String, String> {
+    if !is_spring_pattern(pattern) {
+        return Ok(pattern.to_string());
+    }
+
+    let (path, query) = split_pattern(pattern)?;
+    let last = path.len() - 1;
+
+    let mut translated = Vec::with_capacity(path.len());
+    for (index, segment) in path.iter().enumerate() {
+        let (segment, _) = translate_segment(pattern, segment)?;
+        if segment.ends_with("..>") && index != last {
+            return Err(format!(
+                "`{}` must be the last segment of `{}`",
+                segment, pattern
+            ));
+        }
+        translated.push(segment);
+    }
+
+    let mut template = translated.join("/");
+    if let Some(query) = query {
+        let mut fields = Vec::with_capacity(query.len());
+        for field in &query {
+            fields.push(translate_segment(pattern, field)?.0);
+        }
+
+        template.push('?');
+        template.push_str(&fields.join("&"));
+    }
+
+    Ok(template)
+}
+
+/// The regex constraints of a Spring-style path pattern, in order.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_core::pattern_constraints;
+///
+/// let constraints = pattern_constraints(r"/orders/{id:\d+}/files/{name:[a-z0-9_-]+\.pdf}").unwrap();
+///
+/// assert_eq!(constraints.len(), 2);
+/// assert_eq!((constraints[0].name.as_str(), constraints[0].regex.as_str()), ("id", r"\d+"));
+/// assert_eq!(constraints[1].anchored(), r"^(?:[a-z0-9_-]+\.pdf)$");
+///
+/// assert!(pattern_constraints("/users/{id}").unwrap().is_empty());
+/// ```
+///
+/// @since 0.3.0
+pub fn pattern_constraints(pattern: &str) -> Result<Vec<PathConstraint>, String> {
+    if !is_spring_pattern(pattern) {
+        return Ok(Vec::new());
+    }
+
+    let (path, query) = split_pattern(pattern)?;
+
+    let mut constraints = Vec::new();
+    for segment in path.iter().chain(query.iter().flatten()) {
+        if let (_, Some(constraint)) = translate_segment(pattern, segment)? {
+            constraints.push(constraint);
+        }
+    }
+
+    Ok(constraints)
+}
+
+// ----------------------------------------------------------------
+
+/// Splits a pattern into its path segments and query fields, ignoring separators inside `{}`.
+#[doc(hidden)]
+fn split_pattern(pattern: &str) -> Result<(Vec<String>, Option<Vec<String>>), String> {
+    let mut path = Vec::new();
+    let mut query: Option<Vec<String>> = None;
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in pattern.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = match depth.checked_sub(1) {
+                    Some(depth) => depth,
+                    None => return Err(format!("unbalanced `}}` in `{}`", pattern)),
+                }
+            }
+            '/' if depth == 0 && query.is_none() => {
+                path.push(std::mem::take(&mut current));
+                continue;
+            }
+            '?' if depth == 0 && query.is_none() => {
+                path.push(std::mem::take(&mut current));
+                query = Some(Vec::new());
+                continue;
+            }
+            '&' if depth == 0 => {
+                if let Some(query) = query.as_mut() {
+                    query.push(std::mem::take(&mut current));
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+
+    if depth != 0 {
+        return Err(format!("unbalanced `{{` in `{}`", pattern));
+    }
+
+    match query.as_mut() {
+        Some(query) => query.push(current),
+        None => path.push(current),
+    }
+
+    Ok((path, query))
+}
+
+#[doc(hidden)]
+fn translate_segment(
+    pattern: &str,
+    segment: &str,
+) -> Result<(String, Option<PathConstraint>), String> {
+    match segment {
+        "*" => return Ok(("<_>".to_string(), None)),
+        "**" => return Ok(("<path..>".to_string(), None)),
+        _ => {}
+    }
+
+    if !segment.contains(['{', '}']) {
+        return Ok((segment.to_string(), None));
+    }
+
+    let placeholder = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        Some(placeholder) => placeholder,
+        None => {
+            return Err(format!(
+                "`{}` in `{}`: placeholders must span a whole segment",
+                segment, pattern
+            ))
+        }
+    };
+
+    let (name, regex) = match placeholder.split_once(':') {
+        Some((name, regex)) => (name, Some(regex)),
+        None => (placeholder, None),
+    };
+    let (name, trailing) = match name.strip_prefix('*') {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!(
+            "`{}` in `{}`: invalid placeholder name",
+            segment, pattern
+        ));
+    }
+
+    let constraint = match regex {
+        Some(_) if trailing => {
+            return Err(format!(
+                "`{}` in `{}`: trailing placeholders cannot be constrained",
+                segment, pattern
+            ))
+        }
+        Some("") => return Err(format!("`{}` in `{}`: empty constraint", segment, pattern)),
+        Some(regex) => Some(PathConstraint {
+            name: name.to_string(),
+            regex: regex.to_string(),
+        }),
+        None => None,
+    };
+
+    let segment = match trailing {
+        true => format!("<{}..>", name),
+        false => format!("<{}>", name),
+    };
+
+    Ok((segment, constraint))
+}
+
+#[doc(hidden)]
+fn ant_segments(pattern: &[&str], segments: &[&str]) -> bool {
+    match (pattern.first(), segments.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            ant_segments(&pattern[1..], segments)
+                || (!segments.is_empty() && ant_segments(pattern, &segments[1..]))
+        }
+        (Some(part), Some(segment)) if *part == "*" || part == segment => {
+            ant_segments(&pattern[1..], &segments[1..])
+        }
+        _ => false,
+    }
+}
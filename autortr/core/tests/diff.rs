@@ -0,0 +1,184 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// autortr/core/tests/diff
+
+// ----------------------------------------------------------------
+
+use autortr_core::{diff_route_tables, ChangeKind, RouteDiff, RouteTable};
+
+// ----------------------------------------------------------------
+
+const GET_USER: &str = "GET /users/<id> rank=-5 format=- data=- -> get_user";
+
+// ----------------------------------------------------------------
+
+#[test]
+fn identical_tables_have_no_changes() {
+    let diff = diff(GET_USER, GET_USER);
+
+    assert!(diff.is_empty());
+    assert!(!diff.has_breaking_changes());
+    assert_eq!(diff.to_report(), "No route changes.\n");
+}
+
+#[test]
+fn added_routes_are_not_breaking() {
+    let diff = diff(
+        GET_USER,
+        concat!(
+            "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+            "GET /users rank=-9 format=- data=- -> list_users\n",
+        ),
+    );
+
+    assert_eq!(kinds(&diff), vec![ChangeKind::Added]);
+    assert_eq!(diff.changes[0].route().function, "list_users");
+    assert!(diff.changes[0].before.is_none());
+    assert!(!diff.has_breaking_changes());
+}
+
+#[test]
+fn removed_routes_are_breaking() {
+    let diff = diff(
+        concat!(
+            "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+            "DELETE /users/<id> rank=-5 format=- data=- -> delete_user\n",
+        ),
+        GET_USER,
+    );
+
+    assert_eq!(kinds(&diff), vec![ChangeKind::Removed]);
+    assert_eq!(diff.changes[0].route().function, "delete_user");
+    assert!(diff.changes[0].after.is_none());
+    assert!(diff.has_breaking_changes());
+}
+
+#[test]
+fn changed_methods_are_breaking() {
+    let diff = diff(
+        GET_USER,
+        "POST /users/<id> rank=-5 format=- data=- -> get_user",
+    );
+
+    assert_eq!(
+        kinds(&diff),
+        vec![ChangeKind::MethodChanged {
+            from: "GET".to_string(),
+            to: "POST".to_string(),
+        }]
+    );
+    assert!(diff.has_breaking_changes());
+}
+
+#[test]
+fn routes_moved_to_another_handler_are_removed_and_added() {
+    let diff = diff(
+        GET_USER,
+        "POST /users/<id> rank=-5 format=- data=- -> update_user",
+    );
+
+    assert_eq!(kinds(&diff), vec![ChangeKind::Removed, ChangeKind::Added]);
+    assert!(diff.has_breaking_changes());
+}
+
+#[test]
+fn renamed_path_parameters_are_not_breaking() {
+    let diff = diff(
+        GET_USER,
+        "GET /users/<user_id> rank=-5 format=- data=- -> get_user",
+    );
+
+    assert_eq!(
+        kinds(&diff),
+        vec![ChangeKind::PathParameterRenamed {
+            from: vec!["id".to_string()],
+            to: vec!["user_id".to_string()],
+        }]
+    );
+    assert!(!diff.has_breaking_changes());
+}
+
+#[test]
+fn added_bodies_are_breaking() {
+    let diff = diff(
+        "PUT /users/<id> rank=-5 format=- data=- -> update_user",
+        "PUT /users/<id> rank=-5 format=- data=<form> -> update_user",
+    );
+
+    assert_eq!(kinds(&diff), vec![ChangeKind::BodyAdded]);
+    assert!(diff.has_breaking_changes());
+}
+
+#[test]
+fn removed_bodies_are_not_reported() {
+    let diff = diff(
+        "PUT /users/<id> rank=-5 format=- data=<form> -> update_user",
+        "PUT /users/<id> rank=-5 format=- data=- -> update_user",
+    );
+
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn deprecated_routes_are_not_breaking() {
+    let diff = diff(
+        GET_USER,
+        "GET /users/<id> rank=-5 format=- data=- -> get_user deprecated",
+    );
+
+    assert_eq!(kinds(&diff), vec![ChangeKind::Deprecated]);
+    assert!(!diff.has_breaking_changes());
+}
+
+#[test]
+fn reports_list_breaking_changes_first() {
+    let diff = diff(
+        concat!(
+            "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+            "DELETE /users/<id> rank=-5 format=- data=- -> delete_user\n",
+        ),
+        "GET /users/<id> rank=-5 format=- data=- -> get_user deprecated",
+    );
+
+    assert_eq!(
+        diff.to_report(),
+        concat!(
+            "Breaking changes (1):\n",
+            "  - removed: DELETE /users/<id> (delete_user)\n",
+            "Non-breaking changes (1):\n",
+            "  - deprecated: GET /users/<id> (get_user)\n",
+        )
+    );
+}
+
+// ----------------------------------------------------------------
+
+fn diff(old: &str, new: &str) -> RouteDiff {
+    let (old, new) = (table(old), table(new));
+    diff_route_tables(&old, &new)
+}
+
+fn table(text: &str) -> RouteTable {
+    RouteTable::from_text(text).expect("a valid route table")
+}
+
+fn kinds(diff: &RouteDiff) -> Vec<ChangeKind> {
+    diff.changes
+        .iter()
+        .map(|change| change.kind.clone())
+        .collect()
+}
@@ -0,0 +1,139 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// axum/codegen/lib
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+// ----------------------------------------------------------------
+
+use proc_macro::TokenStream;
+
+use autortr_axum_core::AxumBackend;
+use autortr_codegen::expand_handler;
+
+// ----------------------------------------------------------------
+
+/// A macro for simplifying route mapping and registration in axum applications.
+///
+/// The handler is left untouched and registered with its method router, the Rocket-style
+/// `path` template (e.g. `/users/<id>`) is converted to axum's syntax (`/users/{id}`) at compile time.
+/// The `data` argument is only recorded: axum binds the body with the handler's extractors.
+///
+/// # Examples
+///
+/// ```rust
+/// use autortr_axum_core::{register_route_mapping, RouteMapping};
+/// use autortr_axum_codegen::request_mapping;
+/// // use autortr_axum::prelude::*;
+///
+/// use axum::extract::{Form, Path};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Account {
+///     account: String,
+/// }
+///
+/// #[request_mapping(method = "get", path = "/get")]
+/// async fn get_fn() -> &'static str {
+///     "Hello, get!"
+/// }
+///
+/// // with namespace and path parameter
+/// #[request_mapping(namespace = "/axum", method = "get", path = "/users/<id>")]
+/// async fn user_fn(Path(id): Path<u64>) -> String {
+///     format!("Hello, user {}!", id)
+/// }
+///
+/// // with data
+/// #[request_mapping(
+///     namespace = "/axum",
+///     method = "post",
+///     path = "/data",
+///     data = "<form>"
+/// )]
+/// async fn data_fn(Form(account): Form<Account>) -> &'static str {
+///     println!("form.account: {}", account.account);
+///
+///     "Hello, data!"
+/// }
+/// ```
+///
+/// If the `namespace` attribute is not provided, it defaults to "/".
+///
+/// Note: This macro requires the `axum` and `ctor` crates to be included in your project.
+#[proc_macro_attribute]
+pub fn request_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(None, args.into(), item.into()).into()
+}
+
+// ----------------------------------------------------------------
+
+/// HTTP GET method request mapping. Equivalent to `#[request_mapping(method = "get",...)]`
+///
+/// # Examples
+///
+/// ```rust
+/// use autortr_axum_core::{register_route_mapping, RouteMapping};
+/// use autortr_axum_codegen::get_mapping;
+///
+/// #[get_mapping("/get")]
+/// async fn default_get_fn() -> &'static str {
+///     "Hello, get!"
+/// }
+///
+/// #[get_mapping(namespace = "/axum", path = "/get")]
+/// async fn get_fn() -> &'static str {
+///     "Hello, get!"
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn get_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("get"), args.into(), item.into()).into()
+}
+
+/// HTTP POST method request mapping. Equivalent to `#[request_mapping(method = "post",...)]`
+#[proc_macro_attribute]
+pub fn post_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("post"), args.into(), item.into()).into()
+}
+
+/// HTTP PUT method request mapping. Equivalent to `#[request_mapping(method = "put",...)]`
+#[proc_macro_attribute]
+pub fn put_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("put"), args.into(), item.into()).into()
+}
+
+/// HTTP PATCH method request mapping. Equivalent to `#[request_mapping(method = "patch",...)]`
+#[proc_macro_attribute]
+pub fn patch_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("patch"), args.into(), item.into()).into()
+}
+
+/// HTTP DELETE method request mapping. Equivalent to `#[request_mapping(method = "delete",...)]`
+#[proc_macro_attribute]
+pub fn delete_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("delete"), args.into(), item.into()).into()
+}
+
+/// HTTP HEAD method request mapping. Equivalent to `#[request_mapping(method = "head",...)]`
+#[proc_macro_attribute]
+pub fn head_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_handler::<AxumBackend>(Some("head"), args.into(), item.into()).into()
+}
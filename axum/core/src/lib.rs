@@ -0,0 +1,172 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// axum/core/lib
+
+// ----------------------------------------------------------------
+
+pub use autortr_core::*;
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use axum::routing::MethodRouter;
+use axum::Router;
+
+// ----------------------------------------------------------------
+
+/// A handler registered by the mapping macros, with its `MethodRouter` factory.
+pub type RouteMapping = HandlerMapping<MethodRouter>;
+
+// ----------------------------------------------------------------
+
+/// Converts a Rocket-style path template to axum's path syntax.
+///
+/// `<name>` becomes `{name}`, a trailing `<name..>` becomes `{*name}`. The query part
+/// (`?<q>`) is dropped, since axum binds queries with the `Query` extractor.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_axum_core::axum_path;
+///
+/// assert_eq!(axum_path("/get").unwrap(), "/get");
+/// assert_eq!(axum_path("/users/<id>").unwrap(), "/users/{id}");
+/// assert_eq!(axum_path("/files/<path..>?<q>").unwrap(), "/files/{*path}");
+///
+/// assert!(axum_path("/users/<id").is_err());
+/// assert!(axum_path("/files/<path..>/raw").is_err());
+/// ```
+pub fn axum_path(template: &str) -> Result<String, String> {
+    convert_path(template, |name, trailing| match trailing {
+        true => format!("{{*{}}}", name),
+        false => format!("{{{}}}", name),
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// The axum `Backend`: nests each namespace as its own `Router`, the root namespace (`/`)
+/// is merged into the top-level one. axum has no catchers.
+pub struct AxumBackend;
+
+impl Backend for AxumBackend {
+    const NAME: &'static str = "axum";
+
+    /// The `Router` of each namespace, without its trailing `/`.
+    type App = BTreeMap<String, Router>;
+    type Mapping = RouteMapping;
+    type Catcher = Infallible;
+    type Output = Router;
+
+    fn route_path(template: &str) -> Result<String, String> {
+        axum_path(template)
+    }
+
+    fn route_factory(method: &str, handler: &str) -> Option<String> {
+        Some(format!("|| axum::routing::{}({})", method, handler))
+    }
+
+    fn descriptor(mapping: &RouteMapping) -> RouteDescriptor {
+        mapping.descriptor()
+    }
+
+    fn catcher_namespace(catcher: &Infallible) -> String {
+        match *catcher {}
+    }
+
+    fn app() -> Self::App {
+        BTreeMap::new()
+    }
+
+    fn mount(app: Self::App, namespace: &str, mappings: Vec<RouteMapping>) -> Self::App {
+        let mut app = app;
+        let namespace = namespace.trim_end_matches('/').to_string();
+
+        let mut router = app.remove(&namespace).unwrap_or_default();
+        for mapping in mappings {
+            router = router.route(&mapping.route_path, (mapping.route)());
+        }
+        app.insert(namespace, router);
+
+        app
+    }
+
+    fn mount_catchers(app: Self::App, _namespace: &str, catchers: Vec<Infallible>) -> Self::App {
+        match catchers.into_iter().next() {
+            Some(catcher) => match catcher {},
+            None => app,
+        }
+    }
+
+    fn finish(app: Self::App) -> Router {
+        let mut router = Router::new();
+        for (namespace, nested) in app {
+            router = match namespace.is_empty() {
+                true => router.merge(nested),
+                false => router.nest(&namespace, nested),
+            };
+        }
+
+        router
+    }
+}
+
+// ----------------------------------------------------------------
+
+static ROUTE_MAPPINGS: Registry<RouteMapping> = Registry::new();
+
+// ----------------------------------------------------------------
+
+pub fn register_route_mapping(mapping: RouteMapping) {
+    ROUTE_MAPPINGS.register(mapping);
+}
+
+// ----------------------------------------------------------------
+
+pub fn try_acquire_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.acquire()
+}
+
+// ----------------------------------------------------------------
+
+pub fn clean_route_mappings() {
+    ROUTE_MAPPINGS.clean();
+}
+
+// ----------------------------------------------------------------
+
+/// Returns every route mapping registered so far, regardless of `clean_route_mappings`.
+pub fn snapshot_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.snapshot()
+}
+
+/// Returns the framework-neutral descriptors of the given route mappings.
+pub fn route_descriptors(mappings: &[RouteMapping]) -> Vec<RouteDescriptor> {
+    handler_descriptors(mappings)
+}
+
+/// Returns the sorted `RouteTable` of the given route mappings.
+pub fn route_table(mappings: &[RouteMapping]) -> RouteTable {
+    handler_table(mappings)
+}
+
+/// Returns the sorted `RouteTable` of every registered route mapping.
+pub fn snapshot_route_table() -> RouteTable {
+    route_table(&snapshot_route_mappings())
+}
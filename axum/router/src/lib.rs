@@ -0,0 +1,73 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// axum/router/lib
+
+// ----------------------------------------------------------------
+
+use axum::Router;
+
+use autortr_axum_core::{
+    build, clean_route_mappings, try_acquire_route_mappings, AxumBackend, RouteMapping,
+};
+
+// ----------------------------------------------------------------
+
+/// Constructs and returns an axum `Router` with the registered route mappings.
+///
+/// Each namespace is nested as its own `Router`, the root namespace (`/`) is merged
+/// into the top-level one. The registry is cleaned afterward.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use autortr_axum_router::app;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let listener = tokio::net::TcpListener::bind("127.0.0.1:8000").await.unwrap();
+///     axum::serve(listener, app()).await.unwrap();
+/// }
+/// ```
+pub fn app() -> Router {
+    let app = build_with(try_acquire_route_mappings());
+
+    clean_route_mappings();
+
+    app
+}
+
+/// Constructs an axum `Router` from the given route mappings, with `AxumBackend`.
+///
+/// Unlike `app`, the registry is left untouched, which allows building several
+/// instances from `snapshot_route_mappings`, e.g. one per test.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_axum_core::snapshot_route_mappings;
+/// use autortr_axum_router::build_with;
+///
+/// let mappings = snapshot_route_mappings()
+///     .into_iter()
+///     .filter(|mapping| mapping.namespace == "/axum")
+///     .collect();
+///
+/// let app = build_with(mappings);
+/// ```
+pub fn build_with(mappings: Vec<RouteMapping>) -> Router {
+    build::<AxumBackend>(mappings, Vec::new())
+}
@@ -0,0 +1,307 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// poem/core/lib
+
+// ----------------------------------------------------------------
+
+pub use autortr_core::*;
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use poem::endpoint::BoxEndpoint;
+use poem::http::{Method, StatusCode};
+use poem::{Endpoint, EndpointExt, Response, Route, RouteMethod};
+
+// ----------------------------------------------------------------
+
+/// A handler registered by the mapping macros, with its endpoint factory.
+pub type RouteMapping = HandlerMapping<BoxEndpoint<'static>>;
+
+// ----------------------------------------------------------------
+
+/// Replaces the error responses of a namespace: `status` ones, or any `4xx`/`5xx` one if `None`.
+///
+/// Catchers of the root namespace (`/`) apply to the whole app.
+#[derive(Clone)]
+pub struct Catcher {
+    pub namespace: String,
+    pub status: Option<StatusCode>,
+    pub handler: fn(StatusCode) -> Response,
+}
+
+impl Catcher {
+    pub fn new(namespace: &str, status: StatusCode, handler: fn(StatusCode) -> Response) -> Self {
+        Catcher {
+            namespace: namespace.to_string(),
+            status: Some(status),
+            handler,
+        }
+    }
+
+    /// A catcher of any error response.
+    pub fn default(namespace: &str, handler: fn(StatusCode) -> Response) -> Self {
+        Catcher {
+            namespace: namespace.to_string(),
+            status: None,
+            handler,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Converts a Rocket-style path template to poem's path syntax.
+///
+/// `<name>` becomes `:name`, a trailing `<name..>` becomes `*name`. The query part
+/// (`?<q>`) is dropped, since poem binds queries with the `Query` extractor.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_poem_core::poem_path;
+///
+/// assert_eq!(poem_path("/get").unwrap(), "/get");
+/// assert_eq!(poem_path("/users/<id>").unwrap(), "/users/:id");
+/// assert_eq!(poem_path("/files/<path..>?<q>").unwrap(), "/files/*path");
+///
+/// assert!(poem_path("/users/<id").is_err());
+/// assert!(poem_path("/files/<path..>/raw").is_err());
+/// ```
+pub fn poem_path(template: &str) -> Result<String, String> {
+    convert_path(template, |name, trailing| match trailing {
+        true => format!("*{}", name),
+        false => format!(":{}", name),
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// The poem `Backend`: emits `#[poem::handler]`, nests each namespace as its own `Route`
+/// and wraps it with the namespace's catchers.
+pub struct PoemBackend;
+
+/// The app under construction by `PoemBackend`.
+#[derive(Default)]
+pub struct PoemApp {
+    root: Route,
+    namespaces: BTreeMap<String, Route>,
+    catchers: BTreeMap<String, Vec<Catcher>>,
+}
+
+impl Backend for PoemBackend {
+    const NAME: &'static str = "poem";
+
+    type App = PoemApp;
+    type Mapping = RouteMapping;
+    type Catcher = Catcher;
+    type Output = BoxEndpoint<'static>;
+
+    fn route_attribute(_descriptor: &RouteDescriptor) -> Option<String> {
+        Some("#[poem::handler]".to_string())
+    }
+
+    fn route_path(template: &str) -> Result<String, String> {
+        poem_path(template)
+    }
+
+    fn route_factory(_method: &str, handler: &str) -> Option<String> {
+        Some(format!(
+            "|| poem::EndpointExt::boxed(poem::EndpointExt::map_to_response({}))",
+            handler
+        ))
+    }
+
+    fn descriptor(mapping: &RouteMapping) -> RouteDescriptor {
+        mapping.descriptor()
+    }
+
+    fn catcher_namespace(catcher: &Catcher) -> String {
+        catcher.namespace.clone()
+    }
+
+    fn app() -> PoemApp {
+        PoemApp::default()
+    }
+
+    fn mount(app: PoemApp, namespace: &str, mappings: Vec<RouteMapping>) -> PoemApp {
+        let mut app = app;
+        let namespace = namespace.trim_end_matches('/');
+
+        let mut resources: BTreeMap<String, RouteMethod> = BTreeMap::new();
+        for mapping in mappings {
+            let method = match Method::from_bytes(mapping.method.to_uppercase().as_bytes()) {
+                Ok(method) => method,
+                Err(_) => panic!("Unsupported HTTP method `{}`", mapping.method),
+            };
+
+            let resource = resources.remove(&mapping.route_path).unwrap_or_default();
+            resources.insert(
+                mapping.route_path.clone(),
+                resource.method(method, (mapping.route)()),
+            );
+        }
+
+        let mut route = match namespace.is_empty() {
+            true => std::mem::take(&mut app.root),
+            false => app.namespaces.remove(namespace).unwrap_or_default(),
+        };
+        for (path, resource) in resources {
+            route = route.at(path, resource);
+        }
+
+        match namespace.is_empty() {
+            true => app.root = route,
+            false => {
+                app.namespaces.insert(namespace.to_string(), route);
+            }
+        }
+
+        app
+    }
+
+    fn mount_catchers(app: PoemApp, namespace: &str, catchers: Vec<Catcher>) -> PoemApp {
+        let mut app = app;
+        let namespace = namespace.trim_end_matches('/').to_string();
+        app.catchers.entry(namespace).or_default().extend(catchers);
+
+        app
+    }
+
+    fn finish(app: PoemApp) -> BoxEndpoint<'static> {
+        let PoemApp {
+            mut root,
+            namespaces,
+            mut catchers,
+        } = app;
+
+        for (namespace, route) in namespaces {
+            let endpoint = match catchers.remove(&namespace) {
+                Some(catchers) => catch(route.boxed(), catchers),
+                None => route.boxed(),
+            };
+
+            root = root.nest(namespace, endpoint);
+        }
+
+        match catchers.remove("") {
+            Some(catchers) => catch(root.boxed(), catchers),
+            None => root.boxed(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+static ROUTE_MAPPINGS: Registry<RouteMapping> = Registry::new();
+static CATCHERS: Registry<Catcher> = Registry::new();
+
+// ----------------------------------------------------------------
+
+pub fn register_route_mapping(mapping: RouteMapping) {
+    ROUTE_MAPPINGS.register(mapping);
+}
+
+pub fn register_catcher(catcher: Catcher) {
+    CATCHERS.register(catcher);
+}
+
+// ----------------------------------------------------------------
+
+pub fn try_acquire_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.acquire()
+}
+
+pub fn try_acquire_catchers() -> Vec<Catcher> {
+    CATCHERS.acquire()
+}
+
+// ----------------------------------------------------------------
+
+pub fn clean_route_mappings() {
+    ROUTE_MAPPINGS.clean();
+    CATCHERS.clean();
+}
+
+// ----------------------------------------------------------------
+
+/// Returns every route mapping registered so far, regardless of `clean_route_mappings`.
+pub fn snapshot_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.snapshot()
+}
+
+/// Returns every catcher registered so far, regardless of `clean_route_mappings`.
+pub fn snapshot_catchers() -> Vec<Catcher> {
+    CATCHERS.snapshot()
+}
+
+/// Returns the framework-neutral descriptors of the given route mappings.
+pub fn route_descriptors(mappings: &[RouteMapping]) -> Vec<RouteDescriptor> {
+    handler_descriptors(mappings)
+}
+
+/// Returns the sorted `RouteTable` of the given route mappings.
+pub fn route_table(mappings: &[RouteMapping]) -> RouteTable {
+    handler_table(mappings)
+}
+
+/// Returns the sorted `RouteTable` of every registered route mapping.
+pub fn snapshot_route_table() -> RouteTable {
+    route_table(&snapshot_route_mappings())
+}
+
+// ----------------------------------------------------------------
+
+/// Marks a response produced by a catcher, so that outer namespaces keep it.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+struct Caught;
+
+#[doc(hidden)]
+fn catch(endpoint: BoxEndpoint<'static>, catchers: Vec<Catcher>) -> BoxEndpoint<'static> {
+    let catchers = Arc::new(catchers);
+
+    endpoint
+        .around(move |endpoint, request| {
+            let catchers = catchers.clone();
+            async move {
+                let response = endpoint.get_response(request).await;
+                let status = response.status();
+                let caught = response.extensions().get::<Caught>().is_some();
+                if caught || (!status.is_client_error() && !status.is_server_error()) {
+                    return Ok(response);
+                }
+
+                let catcher = catchers
+                    .iter()
+                    .find(|catcher| catcher.status == Some(status))
+                    .or_else(|| catchers.iter().find(|catcher| catcher.status.is_none()));
+
+                Ok(match catcher {
+                    Some(catcher) => {
+                        let mut response = (catcher.handler)(status);
+                        response.extensions_mut().insert(Caught);
+                        response
+                    }
+                    None => response,
+                })
+            }
+        })
+        .boxed()
+}
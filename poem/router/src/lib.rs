@@ -0,0 +1,70 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// poem/router/lib
+
+// ----------------------------------------------------------------
+
+use poem::endpoint::BoxEndpoint;
+
+use autortr_poem_core::{
+    build, clean_route_mappings, try_acquire_catchers, try_acquire_route_mappings, Catcher,
+    PoemBackend, RouteMapping,
+};
+
+// ----------------------------------------------------------------
+
+/// Constructs and returns a poem endpoint with the registered route mappings and catchers.
+///
+/// Each namespace is nested as its own `Route`, wrapped with the namespace's catchers.
+/// The registry is cleaned afterward.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use poem::listener::TcpListener;
+/// use poem::Server;
+/// use autortr_poem_router::app;
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     Server::new(TcpListener::bind("127.0.0.1:8000")).run(app()).await
+/// }
+/// ```
+pub fn app() -> BoxEndpoint<'static> {
+    let app = build_with(try_acquire_route_mappings(), try_acquire_catchers());
+
+    clean_route_mappings();
+
+    app
+}
+
+/// Constructs a poem endpoint from the given route mappings and catchers, with `PoemBackend`.
+///
+/// Unlike `app`, the registry is left untouched, which allows building several
+/// instances from `snapshot_route_mappings`, e.g. one per test.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_poem_core::{snapshot_catchers, snapshot_route_mappings};
+/// use autortr_poem_router::build_with;
+///
+/// let app = build_with(snapshot_route_mappings(), snapshot_catchers());
+/// ```
+pub fn build_with(mappings: Vec<RouteMapping>, catchers: Vec<Catcher>) -> BoxEndpoint<'static> {
+    build::<PoemBackend>(mappings, catchers)
+}
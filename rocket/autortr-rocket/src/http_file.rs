@@ -0,0 +1,510 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/autortr-rocket/http_file
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use rocket::http::{Header, Method};
+use rocket::local::asynchronous::Client;
+use rocket::{Build, Rocket};
+
+// ----------------------------------------------------------------
+
+pub const REQUEST_SEPARATOR: &str = "###";
+pub const EXPECT_STATUS: &str = "@expect-status";
+pub const EXPECT_BODY: &str = "@expect-body";
+
+// ----------------------------------------------------------------
+
+/// Errors raised while loading or parsing a `.http` file.
+///
+/// @since 0.3.0
+#[derive(Debug)]
+pub enum HttpFileError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for HttpFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpFileError::Io(e) => write!(f, "failed to read `.http` file: {}", e),
+            HttpFileError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for HttpFileError {}
+
+impl From<std::io::Error> for HttpFileError {
+    fn from(e: std::io::Error) -> Self {
+        HttpFileError::Io(e)
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Expectations declared through comment annotations, e.g.:
+///
+/// ```http
+/// ### Get
+/// # @expect-status 200
+/// # @expect-body Hello, get!
+/// GET http://127.0.0.1:8000/get
+/// ```
+///
+/// Without an `@expect-status` annotation any `2xx` status is accepted.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Expectation {
+    pub status: Option<u16>,
+    pub body_contains: Vec<String>,
+}
+
+/// A single request of a `.http` file, with variables already substituted.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub name: String,
+    pub line: usize,
+    pub method: Method,
+    /// The origin-form target (`/path?query`), scheme and authority stripped.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub expect: Expectation,
+}
+
+/// A parsed REST-client (`.http`) file.
+///
+/// Supported syntax:
+///
+/// - `###` separates requests, the rest of the line is the request name.
+/// - `@name = value` declares a file variable, referenced as `{{name}}`.
+/// - `#` and `//` lines are comments outside of a request body, which keeps them.
+/// - `METHOD URL [HTTP/1.1]` followed by `Name: value` headers, a blank line and the body.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket::http_file::HttpFile;
+///
+/// let content = concat!(
+///     "@host = http://127.0.0.1:8000\n",
+///     "\n",
+///     "### Get\n",
+///     "# @expect-status 200\n",
+///     "GET {{host}}/get\n",
+///     "\n",
+///     "### Post\n",
+///     "POST {{host}}/rocket/data\n",
+///     "Content-Type: application/x-www-form-urlencoded\n",
+///     "\n",
+///     "account=photowey\n",
+///     "\n",
+///     "### Markdown\n",
+///     "POST {{host}}/notes\n",
+///     "Content-Type: text/markdown\n",
+///     "\n",
+///     "# Title\n",
+///     "## Section\n",
+/// );
+///
+/// let file = HttpFile::parse(content).unwrap();
+/// assert_eq!(file.requests.len(), 3);
+/// assert_eq!(file.requests[0].target, "/get");
+/// assert_eq!(file.requests[0].expect.status, Some(200));
+/// assert_eq!(file.requests[1].body.as_deref(), Some("account=photowey"));
+/// assert_eq!(file.requests[2].body.as_deref(), Some("# Title\n## Section"));
+///
+/// // a request line substituted to nothing
+/// let error = HttpFile::parse("@base =\n\n###\n{{base}}\n").unwrap_err();
+/// assert_eq!(error.to_string(), "line 4: empty request line");
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpFile {
+    pub variables: BTreeMap<String, String>,
+    pub requests: Vec<HttpRequest>,
+}
+
+// ----------------------------------------------------------------
+
+#[derive(Default)]
+struct Block {
+    name: String,
+    line: usize,
+    request_line: Option<(usize, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<String>,
+    in_body: bool,
+    expect: Expectation,
+}
+
+impl HttpFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<HttpFile, HttpFileError> {
+        let content = std::fs::read_to_string(path)?;
+        HttpFile::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<HttpFile, HttpFileError> {
+        let mut file = HttpFile::default();
+        let mut blocks = vec![Block::default()];
+
+        for (index, raw) in content.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+
+            if let Some(name) = trimmed.strip_prefix(REQUEST_SEPARATOR) {
+                blocks.push(Block {
+                    name: name.trim().to_string(),
+                    line,
+                    ..Block::default()
+                });
+                continue;
+            }
+
+            let block = blocks.last_mut().unwrap();
+
+            if block.in_body {
+                block.body.push(raw.to_string());
+                continue;
+            }
+
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                parse_annotation(comment.trim(), line, &mut block.expect)?;
+                continue;
+            }
+
+            if trimmed.starts_with("//") {
+                continue;
+            }
+
+            if block.request_line.is_none() {
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if let Some(variable) = trimmed.strip_prefix('@') {
+                    let (name, value) = variable.split_once('=').ok_or(HttpFileError::Parse {
+                        line,
+                        message: format!("invalid variable declaration `{}`", trimmed),
+                    })?;
+                    file.variables
+                        .insert(name.trim().to_string(), value.trim().to_string());
+                    continue;
+                }
+                block.request_line = Some((line, trimmed.to_string()));
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                block.in_body = true;
+                continue;
+            }
+
+            let (name, value) = trimmed.split_once(':').ok_or(HttpFileError::Parse {
+                line,
+                message: format!("invalid header `{}`", trimmed),
+            })?;
+            block
+                .headers
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+
+        for block in blocks {
+            if let Some(request) = file.build_request(block)? {
+                file.requests.push(request);
+            }
+        }
+
+        Ok(file)
+    }
+
+    fn build_request(&self, block: Block) -> Result<Option<HttpRequest>, HttpFileError> {
+        let (line, request_line) = match block.request_line {
+            Some(request_line) => request_line,
+            None => return Ok(None),
+        };
+
+        let request_line = self.substitute(&request_line, line)?;
+        let mut parts = request_line.split_whitespace();
+        let (method, url) = match (parts.next(), parts.next()) {
+            (Some(m), Some(u)) => match Method::from_str(m) {
+                Ok(method) => (method, u),
+                Err(_) => {
+                    return Err(HttpFileError::Parse {
+                        line,
+                        message: format!("unsupported HTTP method `{}`", m),
+                    })
+                }
+            },
+            (Some(u), None) => (Method::Get, u),
+            _ => {
+                return Err(HttpFileError::Parse {
+                    line,
+                    message: "empty request line".to_string(),
+                })
+            }
+        };
+
+        let mut headers = Vec::with_capacity(block.headers.len());
+        for (name, value) in block.headers {
+            headers.push((name, self.substitute(&value, line)?));
+        }
+
+        let body = block.body.join("\n");
+        let body = match body.trim() {
+            "" => None,
+            trimmed => Some(self.substitute(trimmed, line)?),
+        };
+
+        Ok(Some(HttpRequest {
+            name: block.name,
+            line: block.line.max(1),
+            method,
+            target: origin_of(url),
+            headers,
+            body,
+            expect: block.expect,
+        }))
+    }
+
+    fn substitute(&self, text: &str, line: usize) -> Result<String, HttpFileError> {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let end = rest[start..].find("}}").ok_or(HttpFileError::Parse {
+                line,
+                message: format!("unterminated variable in `{}`", text),
+            })?;
+            let name = rest[start + 2..start + end].trim();
+            let value = self.variables.get(name).ok_or(HttpFileError::Parse {
+                line,
+                message: format!("undefined variable `{}`", name),
+            })?;
+            output.push_str(value);
+            rest = &rest[start + end + 2..];
+        }
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
+    /// Sends every request to `client`, in file order, and collects the outcomes.
+    pub async fn execute(&self, client: &Client) -> HttpFileReport {
+        let mut outcomes = Vec::with_capacity(self.requests.len());
+
+        for request in &self.requests {
+            let mut local = client.req(request.method, request.target.clone());
+            for (name, value) in &request.headers {
+                local.add_header(Header::new(name.clone(), value.clone()));
+            }
+            if let Some(body) = &request.body {
+                local.set_body(body.clone());
+            }
+
+            let response = local.dispatch().await;
+            let status = response.status().code;
+            let body = response.into_string().await.unwrap_or_default();
+
+            let mut failures = Vec::new();
+            match request.expect.status {
+                Some(expected) if expected != status => {
+                    failures.push(format!("expected status {}, got {}", expected, status))
+                }
+                None if !(200..300).contains(&status) => {
+                    failures.push(format!("expected a 2xx status, got {}", status))
+                }
+                _ => {}
+            }
+            for expected in &request.expect.body_contains {
+                if !body.contains(expected.as_str()) {
+                    failures.push(format!("expected body to contain `{}`", expected));
+                }
+            }
+
+            outcomes.push(HttpOutcome {
+                request: request.clone(),
+                status,
+                body,
+                failures,
+            });
+        }
+
+        HttpFileReport { outcomes }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The result of one executed request.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug)]
+pub struct HttpOutcome {
+    pub request: HttpRequest,
+    pub status: u16,
+    pub body: String,
+    pub failures: Vec<String>,
+}
+
+impl HttpOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// The results of an executed `.http` file.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default)]
+pub struct HttpFileReport {
+    pub outcomes: Vec<HttpOutcome>,
+}
+
+impl HttpFileReport {
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(HttpOutcome::passed)
+    }
+
+    pub fn failures(&self) -> Vec<&HttpOutcome> {
+        self.outcomes.iter().filter(|o| !o.passed()).collect()
+    }
+
+    /// Panics with a summary of every failed request.
+    pub fn assert_success(&self) {
+        let failures = self.failures();
+        if failures.is_empty() {
+            return;
+        }
+
+        let mut message = format!(
+            "{} of {} `.http` requests failed:\n",
+            failures.len(),
+            self.outcomes.len()
+        );
+        for outcome in failures {
+            let request = &outcome.request;
+            message.push_str(&format!(
+                "  - `{}` (line {}) {} {}: {}\n",
+                request.name,
+                request.line,
+                request.method,
+                request.target,
+                outcome.failures.join("; ")
+            ));
+        }
+
+        panic!("{}", message);
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Loads the `.http` file at `path` and executes it against a local asynchronous
+/// client built from `rocket`, blocking until every request has been sent.
+///
+/// Panics if the file can't be parsed or the Rocket instance fails to ignite.
+///
+/// @since 0.3.0
+pub fn run_http_file<P: AsRef<Path>>(rocket: Rocket<Build>, path: P) -> HttpFileReport {
+    let path = path.as_ref();
+    let file = match HttpFile::load(path) {
+        Ok(file) => file,
+        Err(e) => panic!("Invalid `.http` file `{}`: {}", path.display(), e),
+    };
+
+    rocket::execute(async move {
+        let client = Client::tracked(rocket)
+            .await
+            .expect("valid rocket instance");
+
+        file.execute(&client).await
+    })
+}
+
+/// Generates a `#[test]` that executes a `.http` file, relative to the calling crate's
+/// `CARGO_MANIFEST_DIR`, against a local client and fails on any unmet expectation.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::http_file_test;
+/// use autortr_rocket::testing;
+///
+/// http_file_test!(autortr_rocket_http, "../../../autortr_rocket.http", {
+///     hello_rocket::__trigger_init__();
+///     testing::rocket()
+/// });
+/// ```
+///
+/// @since 0.3.0
+#[macro_export]
+macro_rules! http_file_test {
+    ($name:ident, $path:expr, $rocket:expr) => {
+        #[test]
+        fn $name() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/", $path);
+            $crate::http_file::run_http_file($rocket, path).assert_success();
+        }
+    };
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn parse_annotation(
+    comment: &str,
+    line: usize,
+    expect: &mut Expectation,
+) -> Result<(), HttpFileError> {
+    if let Some(status) = comment.strip_prefix(EXPECT_STATUS) {
+        let status = status.trim().trim_start_matches(':').trim();
+        expect.status = Some(status.parse().map_err(|_| HttpFileError::Parse {
+            line,
+            message: format!("invalid expected status `{}`", status),
+        })?);
+    } else if let Some(body) = comment.strip_prefix(EXPECT_BODY) {
+        let body = body.trim().trim_start_matches(':').trim();
+        expect.body_contains.push(body.to_string());
+    }
+
+    Ok(())
+}
+
+#[doc(hidden)]
+fn origin_of(url: &str) -> String {
+    let without_scheme = match url.find("://") {
+        Some(index) => &url[index + 3..],
+        None => return url.to_string(),
+    };
+
+    match without_scheme.find('/') {
+        Some(index) => without_scheme[index..].to_string(),
+        None => "/".to_string(),
+    }
+}
@@ -18,6 +18,8 @@
 
 // ----------------------------------------------------------------
 
+pub mod http_file;
 pub mod prelude;
+pub mod testing;
 
 // ----------------------------------------------------------------
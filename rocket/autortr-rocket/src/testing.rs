@@ -0,0 +1,467 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/autortr-rocket/testing
+
+// ----------------------------------------------------------------
+
+pub mod fuzz;
+pub mod snapshot;
+
+// ----------------------------------------------------------------
+
+use std::str::FromStr;
+
+use rocket::http::{ContentType, Header, Method};
+use rocket::local::blocking::Client;
+use rocket::{Build, Rocket};
+
+use autortr_rocket_core::{snapshot_route_mappings, RouteMapping};
+use autortr_rocket_router::build_with;
+
+// ----------------------------------------------------------------
+
+/// Builds a `Rocket` instance from a snapshot of the registry.
+///
+/// The registry itself is left untouched, so it can be called once per test.
+///
+/// @since 0.3.0
+pub fn rocket() -> Rocket<Build> {
+    rocket_with(|_| true)
+}
+
+/// Builds a `Rocket` instance from the registered mappings accepted by `filter`.
+///
+/// @since 0.3.0
+pub fn rocket_with<F>(filter: F) -> Rocket<Build>
+where
+    F: FnMut(&RouteMapping) -> bool,
+{
+    let mut filter = filter;
+    let mappings = snapshot_route_mappings()
+        .into_iter()
+        .filter(|mapping| filter(mapping))
+        .collect();
+
+    build_with(mappings)
+}
+
+/// Builds an isolated blocking local client serving every registered route.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::testing::test_client;
+///
+/// #[test]
+/// fn get() {
+///     hello_rocket::__trigger_init__();
+///
+///     let client = test_client();
+///     let response = client.get("/http/get").dispatch();
+///     assert_eq!(response.into_string().unwrap(), "Hello, http.get mapping!");
+/// }
+/// ```
+///
+/// @since 0.3.0
+pub fn test_client() -> Client {
+    test_client_with(|_| true)
+}
+
+/// Builds an isolated blocking local client serving the registered routes accepted by `filter`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::testing::test_client_with;
+///
+/// let client = test_client_with(|mapping| mapping.namespace == "/http");
+/// ```
+///
+/// @since 0.3.0
+pub fn test_client_with<F>(filter: F) -> Client
+where
+    F: FnMut(&RouteMapping) -> bool,
+{
+    Client::tracked(rocket_with(filter)).expect("valid rocket instance")
+}
+
+// ----------------------------------------------------------------
+
+/// A `MockMvc`-style entry point: performs `MockRequest`s against a local client
+/// and returns `ResultActions` to assert on.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::testing::*;
+///
+/// let mvc = MockMvc::new();
+/// mvc.perform(get("/http/get"))
+///     .expect_status(200)
+///     .expect_body_contains("http.get");
+///
+/// mvc.perform(handler("http_post_mapping_fn").form(&[("account", "photowey")]))
+///     .expect_ok();
+/// ```
+///
+/// @since 0.3.0
+pub struct MockMvc {
+    client: Client,
+}
+
+impl MockMvc {
+    pub fn new() -> Self {
+        MockMvc::with_client(test_client())
+    }
+
+    pub fn with_client(client: Client) -> Self {
+        MockMvc { client }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn perform(&self, request: MockRequest) -> ResultActions {
+        let (method, uri) = request.resolve();
+        let description = format!("{} {}", method, uri);
+
+        let mut local = self.client.req(method, uri);
+        for (name, value) in request.headers {
+            local.add_header(Header::new(name, value));
+        }
+        if let Some(content_type) = request.content_type {
+            local.add_header(content_type);
+        }
+        if let Some(body) = request.body {
+            local.set_body(body);
+        }
+
+        let response = local.dispatch();
+        let status = response.status().code;
+        let content_type = response.content_type();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|header| (header.name().to_string(), header.value().to_string()))
+            .collect();
+        let body = response.into_string().unwrap_or_default();
+
+        ResultActions {
+            description,
+            status,
+            content_type,
+            headers,
+            body,
+        }
+    }
+}
+
+impl Default for MockMvc {
+    fn default() -> Self {
+        MockMvc::new()
+    }
+}
+
+/// Performs `request` against a fresh `MockMvc` built from the registry.
+///
+/// Use a shared `MockMvc` to send several requests through the same client, e.g. to keep cookies.
+///
+/// @since 0.3.0
+pub fn perform(request: MockRequest) -> ResultActions {
+    MockMvc::new().perform(request)
+}
+
+// ----------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+enum Target {
+    Uri(String),
+    Handler(String),
+}
+
+/// A request to `perform`, built with `get`, `post`, ..., or `handler`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    method: Method,
+    target: Target,
+    path_vars: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    content_type: Option<ContentType>,
+    body: Option<Vec<u8>>,
+}
+
+impl MockRequest {
+    pub fn new<U: Into<String>>(method: Method, uri: U) -> Self {
+        MockRequest {
+            method,
+            target: Target::Uri(uri.into()),
+            path_vars: Vec::new(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            content_type: None,
+            body: None,
+        }
+    }
+
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Sets an `application/x-www-form-urlencoded` body.
+    pub fn form(self, fields: &[(&str, &str)]) -> Self {
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        self.content_type(ContentType::Form).body(body)
+    }
+
+    /// Sets an `application/json` body.
+    pub fn json<B: Into<Vec<u8>>>(self, body: B) -> Self {
+        self.content_type(ContentType::JSON).body(body)
+    }
+
+    /// Replaces the `<name>` (or `<name..>`) segment of the route template.
+    pub fn path_var<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.path_vars.push((name.into(), value.into()));
+        self
+    }
+
+    /// Appends a `name=value` query parameter.
+    pub fn query<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    fn resolve(&self) -> (Method, String) {
+        let (method, template) = match &self.target {
+            Target::Uri(uri) => (self.method, uri.clone()),
+            Target::Handler(function) => resolve_handler(function),
+        };
+
+        let (path, query) = match template.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (template, None),
+        };
+
+        let mut path = path;
+        for (name, value) in &self.path_vars {
+            path = path
+                .replace(&format!("<{}..>", name), value)
+                .replace(&format!("<{}>", name), value);
+        }
+
+        let mut pairs: Vec<String> = query
+            .map(|query| {
+                query
+                    .split('&')
+                    .filter(|pair| !pair.is_empty() && !pair.starts_with('<'))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        pairs.extend(
+            self.query
+                .iter()
+                .map(|(name, value)| format!("{}={}", encode(name), encode(value))),
+        );
+
+        match pairs.is_empty() {
+            true => (method, path),
+            false => (method, format!("{}?{}", path, pairs.join("&"))),
+        }
+    }
+}
+
+pub fn get<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Get, uri)
+}
+
+pub fn post<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Post, uri)
+}
+
+pub fn put<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Put, uri)
+}
+
+pub fn patch<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Patch, uri)
+}
+
+pub fn delete<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Delete, uri)
+}
+
+pub fn head<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Head, uri)
+}
+
+/// @since 0.3.0
+pub fn options<U: Into<String>>(uri: U) -> MockRequest {
+    MockRequest::new(Method::Options, uri)
+}
+
+/// Targets the route registered for the handler `function`, using its method and full path.
+///
+/// Dynamic segments are filled in with `MockRequest::path_var`.
+///
+/// @since 0.3.0
+pub fn handler<F: Into<String>>(function: F) -> MockRequest {
+    let mut request = MockRequest::new(Method::Get, "/");
+    request.target = Target::Handler(function.into());
+    request
+}
+
+// ----------------------------------------------------------------
+
+/// The outcome of a performed request, with chainable expectations.
+///
+/// Each `expect_*` panics with the request and response when unmet.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug)]
+pub struct ResultActions {
+    description: String,
+    status: u16,
+    content_type: Option<ContentType>,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl ResultActions {
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn expect_status(self, status: u16) -> Self {
+        if self.status != status {
+            self.fail(&format!("expected status {}, got {}", status, self.status));
+        }
+        self
+    }
+
+    pub fn expect_ok(self) -> Self {
+        self.expect_status(200)
+    }
+
+    pub fn expect_body(self, body: &str) -> Self {
+        if self.body != body {
+            self.fail(&format!("expected body `{}`", body));
+        }
+        self
+    }
+
+    pub fn expect_body_contains(self, fragment: &str) -> Self {
+        if !self.body.contains(fragment) {
+            self.fail(&format!("expected body to contain `{}`", fragment));
+        }
+        self
+    }
+
+    pub fn expect_header(self, name: &str, value: &str) -> Self {
+        if self.header(name) != Some(value) {
+            self.fail(&format!("expected header `{}: {}`", name, value));
+        }
+        self
+    }
+
+    pub fn expect_content_type(self, content_type: ContentType) -> Self {
+        if self.content_type.as_ref() != Some(&content_type) {
+            self.fail(&format!("expected content type `{}`", content_type));
+        }
+        self
+    }
+
+    fn fail(&self, message: &str) {
+        panic!(
+            "{}: {}\n  status: {}\n  body: {}",
+            self.description, message, self.status, self.body
+        );
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn resolve_handler(function: &str) -> (Method, String) {
+    let mappings: Vec<RouteMapping> = snapshot_route_mappings()
+        .into_iter()
+        .filter(|mapping| mapping.function == function)
+        .collect();
+
+    match mappings.as_slice() {
+        [mapping] => match Method::from_str(&mapping.method) {
+            Ok(method) => (method, mapping.full_path()),
+            Err(_) => panic!("Unsupported HTTP method `{}`", mapping.method),
+        },
+        [] => panic!("No route mapping registered for handler `{}`", function),
+        _ => panic!(
+            "Ambiguous handler `{}`: {} mappings",
+            function,
+            mappings.len()
+        ),
+    }
+}
+
+#[doc(hidden)]
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,468 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/autortr-rocket/testing/fuzz
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rocket::http::{ContentType, Method};
+
+use autortr_rocket_core::{snapshot_route_mappings, RouteMapping, EMPTY};
+
+use super::test_client_with;
+
+// ----------------------------------------------------------------
+
+pub const FUZZ_SEED_ENV: &str = "AUTORTR_FUZZ_SEED";
+pub const FUZZ_ITERATIONS_ENV: &str = "AUTORTR_FUZZ_ITERATIONS";
+
+pub const DEFAULT_ITERATIONS: usize = 8;
+pub const DEFAULT_OVERSIZED_LEN: usize = 2 * 1024 * 1024;
+
+// ----------------------------------------------------------------
+
+/// Smoke-fuzz settings.
+///
+/// `from_env` reads the seed from `AUTORTR_FUZZ_SEED` and the number of requests per route
+/// from `AUTORTR_FUZZ_ITERATIONS`; a random seed is picked when unset and reported on failure.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzConfig {
+    pub seed: u64,
+    pub iterations: usize,
+    pub oversized_len: usize,
+}
+
+impl FuzzConfig {
+    pub fn new(seed: u64) -> Self {
+        FuzzConfig {
+            seed,
+            iterations: DEFAULT_ITERATIONS,
+            oversized_len: DEFAULT_OVERSIZED_LEN,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let seed = std::env::var(FUZZ_SEED_ENV)
+            .ok()
+            .and_then(|seed| seed.trim().parse().ok())
+            .unwrap_or_else(random_seed);
+        let iterations = std::env::var(FUZZ_ITERATIONS_ENV)
+            .ok()
+            .and_then(|iterations| iterations.trim().parse().ok())
+            .unwrap_or(DEFAULT_ITERATIONS);
+
+        FuzzConfig {
+            iterations,
+            ..FuzzConfig::new(seed)
+        }
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn oversized_len(mut self, oversized_len: usize) -> Self {
+        self.oversized_len = oversized_len;
+        self
+    }
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig::from_env()
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The kind of body generated for a request.
+///
+/// @since 0.3.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyKind {
+    None,
+    Empty,
+    Form,
+    Json,
+    Bytes,
+    Oversized,
+}
+
+impl BodyKind {
+    const DATA: [BodyKind; 6] = [
+        BodyKind::None,
+        BodyKind::Empty,
+        BodyKind::Form,
+        BodyKind::Json,
+        BodyKind::Bytes,
+        BodyKind::Oversized,
+    ];
+}
+
+/// A generated request. Its `iteration` and `seed` regenerate it with `replay`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzCase {
+    pub iteration: usize,
+    pub seed: u64,
+    pub function: String,
+    pub method: Method,
+    pub uri: String,
+    pub body_kind: BodyKind,
+    pub content_type: Option<ContentType>,
+    pub body: Vec<u8>,
+}
+
+/// A request answered with a `5xx` status; panicking handlers are answered with `500`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug)]
+pub struct FuzzFailure {
+    pub case: FuzzCase,
+    pub status: u16,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}) {} {} [{:?}, {} bytes] -> {} (case: {}/{})",
+            self.case.function,
+            self.case.method,
+            self.case.uri,
+            self.case.body_kind,
+            self.case.body.len(),
+            self.status,
+            self.case.iteration,
+            self.case.seed
+        )
+    }
+}
+
+/// The outcome of a smoke-fuzz run.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug)]
+pub struct FuzzReport {
+    pub seed: u64,
+    pub cases: usize,
+    /// The generated requests, in sending order.
+    pub requests: Vec<FuzzCase>,
+    pub failures: Vec<FuzzFailure>,
+}
+
+impl FuzzReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Panics with every failing case and the seed to reproduce the run.
+    pub fn assert_no_server_errors(&self) {
+        if self.passed() {
+            return;
+        }
+
+        let mut message = format!(
+            "{} of {} fuzzed requests failed with a server error, reproduce with {}={}:\n",
+            self.failures.len(),
+            self.cases,
+            FUZZ_SEED_ENV,
+            self.seed
+        );
+        for failure in &self.failures {
+            message.push_str(&format!("  - {}\n", failure));
+        }
+
+        panic!("{}", message);
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Sends generated requests to every registered route and reports `5xx` responses.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::testing::fuzz::smoke_fuzz;
+///
+/// #[test]
+/// fn no_server_errors() {
+///     hello_rocket::__trigger_init__();
+///
+///     smoke_fuzz().assert_no_server_errors();
+/// }
+/// ```
+///
+/// @since 0.3.0
+pub fn smoke_fuzz() -> FuzzReport {
+    smoke_fuzz_with(FuzzConfig::from_env(), |_| true)
+}
+
+/// Like `smoke_fuzz`, with explicit settings and for the registered mappings accepted by `filter`.
+///
+/// @since 0.3.0
+pub fn smoke_fuzz_with<F>(config: FuzzConfig, filter: F) -> FuzzReport
+where
+    F: FnMut(&RouteMapping) -> bool,
+{
+    let mut filter = filter;
+    let mut mappings: Vec<RouteMapping> = snapshot_route_mappings()
+        .into_iter()
+        .filter(|mapping| filter(mapping))
+        .collect();
+    mappings.sort_by(|a, b| {
+        (a.full_path(), &a.method, &a.function).cmp(&(b.full_path(), &b.method, &b.function))
+    });
+
+    let mut cases = Vec::new();
+    let mut rng = SplitMix64::new(config.seed);
+    for mapping in &mappings {
+        for iteration in 0..config.iterations {
+            let case_seed = rng.next_u64();
+            cases.push(generate_case(mapping, iteration, case_seed, &config));
+        }
+    }
+
+    let names: Vec<String> = mappings.iter().map(|m| m.function.clone()).collect();
+    let client = test_client_with(|mapping| names.contains(&mapping.function));
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        let mut request = client.req(case.method, case.uri.clone());
+        if let Some(content_type) = &case.content_type {
+            request.add_header(content_type.clone());
+        }
+        if case.body_kind != BodyKind::None {
+            request.set_body(case.body.clone());
+        }
+
+        let status = request.dispatch().status().code;
+        if status >= 500 {
+            failures.push(FuzzFailure {
+                case: case.clone(),
+                status,
+            });
+        }
+    }
+
+    FuzzReport {
+        seed: config.seed,
+        cases: cases.len(),
+        requests: cases,
+        failures,
+    }
+}
+
+/// Regenerates the request of a failing case from its mapping, iteration and case seed.
+///
+/// @since 0.3.0
+pub fn replay(
+    mapping: &RouteMapping,
+    iteration: usize,
+    case_seed: u64,
+    config: &FuzzConfig,
+) -> FuzzCase {
+    generate_case(mapping, iteration, case_seed, config)
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn generate_case(
+    mapping: &RouteMapping,
+    iteration: usize,
+    seed: u64,
+    config: &FuzzConfig,
+) -> FuzzCase {
+    let mut rng = SplitMix64::new(seed);
+    let method = Method::from_str(&mapping.method).unwrap_or(Method::Get);
+    let uri = generate_uri(&mapping.full_path(), &mut rng);
+
+    let body_kind = match mapping.data.as_str() {
+        EMPTY => BodyKind::None,
+        _ => BodyKind::DATA[iteration % BodyKind::DATA.len()],
+    };
+    let (content_type, body) = generate_body(body_kind, &mut rng, config);
+
+    FuzzCase {
+        iteration,
+        seed,
+        function: mapping.function.clone(),
+        method,
+        uri,
+        body_kind,
+        content_type,
+        body,
+    }
+}
+
+#[doc(hidden)]
+fn generate_uri(template: &str, rng: &mut SplitMix64) -> String {
+    let (path, query) = match template.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (template, None),
+    };
+
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match dynamic_name(segment) {
+            Some(name) if name.ends_with("..") => (0..rng.below(3) + 1)
+                .map(|_| generate_segment(rng))
+                .collect::<Vec<_>>()
+                .join("/"),
+            Some(_) => generate_segment(rng),
+            None => segment.to_string(),
+        })
+        .collect();
+    let mut uri = format!("/{}", segments.join("/"));
+
+    if let Some(query) = query {
+        let pairs: Vec<String> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .filter_map(|pair| match dynamic_name(pair) {
+                Some(name) if name.ends_with("..") => None,
+                Some(name) => Some(format!("{}={}", name, generate_segment(rng))),
+                None => Some(pair.to_string()),
+            })
+            .collect();
+        if !pairs.is_empty() {
+            uri = format!("{}?{}", uri, pairs.join("&"));
+        }
+    }
+
+    uri
+}
+
+#[doc(hidden)]
+fn generate_segment(rng: &mut SplitMix64) -> String {
+    match rng.below(9) {
+        0 => "0".to_string(),
+        1 => format!("-{}", rng.below(1_000_000)),
+        2 => rng.below(u32::MAX as u64).to_string(),
+        3 => "18446744073709551616".to_string(),
+        4 => "true".to_string(),
+        5 => "%C3%BC%00".to_string(),
+        6 => "a".repeat(1024),
+        7 => "..".to_string(),
+        _ => alphanumeric(rng, 12),
+    }
+}
+
+#[doc(hidden)]
+fn generate_body(
+    kind: BodyKind,
+    rng: &mut SplitMix64,
+    config: &FuzzConfig,
+) -> (Option<ContentType>, Vec<u8>) {
+    match kind {
+        BodyKind::None | BodyKind::Empty => (None, Vec::new()),
+        BodyKind::Form => {
+            let fields: Vec<String> = (0..rng.below(4))
+                .map(|_| format!("{}={}", alphanumeric(rng, 8), generate_segment(rng)))
+                .collect();
+            (Some(ContentType::Form), fields.join("&").into_bytes())
+        }
+        BodyKind::Json => {
+            let body = match rng.below(5) {
+                0 => "{}".to_string(),
+                1 => "[]".to_string(),
+                2 => "null".to_string(),
+                3 => "{\"".to_string(),
+                _ => format!(
+                    "{{\"{}\": {}, \"{}\": \"{}\"}}",
+                    alphanumeric(rng, 6),
+                    rng.below(1_000),
+                    alphanumeric(rng, 6),
+                    alphanumeric(rng, 16)
+                ),
+            };
+            (Some(ContentType::JSON), body.into_bytes())
+        }
+        BodyKind::Bytes => {
+            let body = (0..rng.below(256)).map(|_| rng.next_u64() as u8).collect();
+            (Some(ContentType::Binary), body)
+        }
+        BodyKind::Oversized => {
+            let content_type = match rng.below(2) {
+                0 => ContentType::Form,
+                _ => ContentType::JSON,
+            };
+            (Some(content_type), vec![b'a'; config.oversized_len])
+        }
+    }
+}
+
+#[doc(hidden)]
+fn dynamic_name(segment: &str) -> Option<&str> {
+    segment.strip_prefix('<')?.strip_suffix('>')
+}
+
+#[doc(hidden)]
+fn alphanumeric(rng: &mut SplitMix64, len: u64) -> String {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    (0..rng.below(len) + 1)
+        .map(|_| CHARS[rng.below(CHARS.len() as u64) as usize] as char)
+        .collect()
+}
+
+#[doc(hidden)]
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+// ----------------------------------------------------------------
+
+/// A small, seedable `SplitMix64` generator: reproducible without extra dependencies.
+#[doc(hidden)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        match bound {
+            0 => 0,
+            _ => self.next_u64() % bound,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/autortr-rocket/testing/snapshot
+
+// ----------------------------------------------------------------
+
+use std::path::Path;
+
+use autortr_rocket_core::{route_table, snapshot_route_mappings, RouteMapping};
+
+// ----------------------------------------------------------------
+
+pub const UPDATE_SNAPSHOTS_ENV: &str = "AUTORTR_UPDATE_SNAPSHOTS";
+
+// ----------------------------------------------------------------
+
+/// Compares the registry's `RouteTable` with the checked-in snapshot at `path`.
+///
+/// Files ending in `.json` hold `RouteTable::to_json`, any other file `RouteTable::to_text`.
+/// On mismatch, panics with a line diff; run with `AUTORTR_UPDATE_SNAPSHOTS=1` to
+/// (re)write the snapshot instead.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::testing::snapshot::assert_route_snapshot;
+///
+/// #[test]
+/// fn route_table() {
+///     hello_rocket::__trigger_init__();
+///
+///     assert_route_snapshot(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/routes.txt"));
+/// }
+/// ```
+///
+/// @since 0.3.0
+pub fn assert_route_snapshot<P: AsRef<Path>>(path: P) {
+    assert_route_snapshot_with(path, |_| true)
+}
+
+/// Like `assert_route_snapshot`, for the registered mappings accepted by `filter`.
+///
+/// @since 0.3.0
+pub fn assert_route_snapshot_with<P, F>(path: P, filter: F)
+where
+    P: AsRef<Path>,
+    F: FnMut(&RouteMapping) -> bool,
+{
+    let path = path.as_ref();
+    let mut filter = filter;
+    let mappings: Vec<RouteMapping> = snapshot_route_mappings()
+        .into_iter()
+        .filter(|mapping| filter(mapping))
+        .collect();
+
+    let table = route_table(&mappings);
+    let actual = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => table.to_json(),
+        _ => table.to_text(),
+    };
+
+    if update_snapshots() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("snapshot directory");
+        }
+        std::fs::write(path, actual).expect("writable snapshot");
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "Missing route snapshot `{}`, run with {}=1 to create it",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV
+        ),
+    };
+
+    if normalize(&expected) != normalize(&actual) {
+        panic!(
+            "Route table differs from snapshot `{}` (run with {}=1 to update it):\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            line_diff(&normalize(&expected), &normalize(&actual))
+        );
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// A line diff of `expected` and `actual`: `-` for removed lines, `+` for added ones.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket::testing::snapshot::line_diff;
+///
+/// assert_eq!(line_diff("a\nb\nc", "a\nc\nd"), "  a\n- b\n  c\n+ d\n");
+/// ```
+///
+/// @since 0.3.0
+pub fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let (n, m) = (expected.len(), actual.len());
+
+    // Longest common subsequence, computed backwards.
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = match expected[i] == actual[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            diff.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+
+    diff
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn update_snapshots() -> bool {
+    matches!(
+        std::env::var(UPDATE_SNAPSHOTS_ENV).as_deref(),
+        Ok("1") | Ok("true") | Ok("yes")
+    )
+}
+
+#[doc(hidden)]
+fn normalize(snapshot: &str) -> String {
+    snapshot.replace("\r\n", "\n").trim_end().to_string()
+}
@@ -25,10 +25,20 @@ extern crate rocket;
 
 use proc_macro::TokenStream;
 
+use proc_macro2::{Group, Ident, TokenStream as TokenStream2, TokenTree};
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, AttributeArgs, ItemFn, Lit, Meta, NestedMeta};
+use syn::{
+    parse_macro_input, Attribute, Expr, Fields, FnArg, GenericArgument, Item, ItemFn, ItemMod,
+    ItemStruct, LitStr, Pat, PathArguments, ReturnType, Type,
+};
 
-use autortr_rocket_core::{DATA, EMPTY, METHOD, NAMESPACE, PATH, ROOT};
+use autortr_codegen::{is_deprecated, Mapping, MappingArgs, PathConstraint};
+use autortr_rocket_core::{
+    join_path, template_parameters, Authority, RateLimit, WebhookScheme, AUTHENTICATED, CORS, DATA,
+    EMPTY, EXCEPT, EXCLUDE, FUNCTIONS, GUARDS, INCLUDE, NAMESPACE, ORDER, PATH_VAR, PRE_AUTHORIZE,
+    QUERY_PARAM, RANK, RATE_LIMIT, RATE_LIMIT_KEY, REQUEST_BODY, REST, ROOT, SCHEME, SECRET, TAGS,
+    VALID, VALIDATE,
+};
 
 // ----------------------------------------------------------------
 
@@ -93,72 +103,24 @@ use autortr_rocket_core::{DATA, EMPTY, METHOD, NAMESPACE, PATH, ROOT};
 ///
 #[proc_macro_attribute]
 pub fn request_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
-    let parsed_args = parse_macro_input!(args as AttributeArgs);
+    let parsed_args = parse_macro_input!(args as MappingArgs);
     let function = parse_macro_input!(item as ItemFn);
 
-    let function_ident = &function.sig.ident;
-    let function_name = function_ident.to_string();
-
-    let (namespace, method, path, data) = match parse_request_mapping_args(parsed_args) {
-        Ok((n, m, p, d)) => (n, m, p, d),
-        Err(_) => panic!("Invalid arguments to `#[request_mapping]`"),
-    };
-
-    let namespace = namespace.unwrap_or_else(|| ROOT.to_string());
-    let data = data.unwrap_or_else(|| EMPTY.to_string());
-
-    let route = match method.as_str() {
-        "get" => match data.as_str() {
-            "_" => quote! { #[rocket::get(#path)] },
-            _ => quote! { #[rocket::get(#path, data = #data)] },
-        },
-        "post" => match data.as_str() {
-            "_" => quote! { #[rocket::post(#path)] },
-            _ => quote! { #[rocket::post(#path, data = #data)] },
-        },
-        "put" => match data.as_str() {
-            "_" => quote! { #[rocket::put(#path)] },
-            _ => quote! { #[rocket::put(#path, data = #data)] },
-        },
-        "patch" => match data.as_str() {
-            "_" => quote! { #[rocket::patch(#path)] },
-            _ => quote! { #[rocket::patch(#path, data = #data)] },
-        },
-        "delete" => match data.as_str() {
-            "_" => quote! { #[rocket::delete(#path)] },
-            _ => quote! { #[rocket::delete(#path, data = #data)] },
-        },
-        "head" => match data.as_str() {
-            "_" => quote! { #[rocket::head(#path)] },
-            _ => quote! { #[rocket::head(#path, data = #data)] },
-        },
-        _ => panic!("Unsupported HTTP method"),
-    };
-
-    let register_fn_name = format_ident!("_register_{}_", function_ident);
-
-    let register_fn = quote! {
-        #[ctor::ctor]
-        fn #register_fn_name() {
-            register_route_mapping(RouteMapping {
-                function: #function_name.to_string(),
-                namespace: #namespace.to_string(),
-                method: #method.to_string(),
-                path: #path.to_string(),
-                data: #data.to_string(),
-                routes: rocket::routes![#function_ident],
-            });
-        }
+    let mapping = match Mapping::request_mapping(&parsed_args) {
+        Some(mapping) => mapping,
+        None => panic!("Invalid arguments to `#[request_mapping]`"),
     };
 
-    let expanded = quote! {
-        #route
-        #function
+    if !matches!(
+        mapping.method.as_str(),
+        "get" | "post" | "put" | "patch" | "delete" | "head"
+    ) {
+        panic!("Unsupported HTTP method");
+    }
 
-        #register_fn
-    };
+    let register_fn_name = format_ident!("_register_{}_", function.sig.ident);
 
-    expanded.into()
+    expand(mapping, &parsed_args, function, register_fn_name)
 }
 
 // ----------------------------------------------------------------
@@ -461,60 +423,614 @@ pub fn head_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
 
 // ----------------------------------------------------------------
 
-#[doc(hidden)]
-fn http_mapping(method: &str, args: TokenStream, item: TokenStream) -> TokenStream {
-    let parsed_args = parse_macro_input!(args as AttributeArgs);
-    let parsed_args_clone = parsed_args.clone();
+/// Declares a REST controller: every mapping in the inline module gets `rest = true`,
+/// and the controller's `namespace`, `authenticated`, `cors`, `rate_limit` and `guards` as with
+/// `#[controller]`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// #[rest_controller(namespace = "/api")]
+/// mod api {
+///     use super::*;
+///
+///     // `{"code":200,"message":"OK","data":[1,2,3]}`
+///     #[get_mapping("/numbers")]
+///     fn numbers_fn() -> Vec<u32> {
+///         vec![1, 2, 3]
+///     }
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn rest_controller(args: TokenStream, item: TokenStream) -> TokenStream {
+    controller_module("rest_controller", true, args, item)
+}
+
+/// Declares a controller: every mapping in the inline module gets the controller's `namespace`,
+/// `authenticated`, `except`, `cors` and `rate_limit` (with its `rate_limit_key`) unless it declares its own,
+/// and the controller's `guards` ahead of its own.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// struct AdminOnly;
+///
+/// #[rocket::async_trait]
+/// impl<'r> FromRequest<'r> for AdminOnly {
+///     // ...
+/// }
+///
+/// #[controller(namespace = "/admin", guards = [AdminOnly])]
+/// mod admin {
+///     use super::*;
+///
+///     // Checks `AdminOnly`, then `ApiKey`.
+///     #[get_mapping(path = "/users", guards = [ApiKey])]
+///     fn users_fn() -> &'static str {
+///         "Hello, admin.users!"
+///     }
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn controller(args: TokenStream, item: TokenStream) -> TokenStream {
+    controller_module("controller", false, args, item)
+}
+
+// ----------------------------------------------------------------
+
+/// Authorizes the requests of a mapped handler with a role/permission expression, checked
+/// before the handler runs. Denied requests get a `403`.
+///
+/// The expression is parsed at compile time, see `Authority`. Its roles and permissions are
+/// evaluated against the `Principal` configured by `#[principal]`. Equivalent to the mapping's
+/// `pre_authorize = ".."` argument.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// #[pre_authorize("has_role('ADMIN') or has_permission('orders:write')")]
+/// #[post_mapping(namespace = "/orders", path = "/")]
+/// fn create_order_fn() -> &'static str {
+///     "Hello, orders.create!"
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn pre_authorize(args: TokenStream, item: TokenStream) -> TokenStream {
+    let expression = parse_macro_input!(args as LitStr);
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    parse_authority(&function, &expression.value());
+
+    let ident = function.sig.ident.clone();
+    let attr = match function
+        .attrs
+        .iter_mut()
+        .find(|attr| is_mapping(&attr.path))
+    {
+        Some(attr) => attr,
+        None => panic!(
+            "`#[pre_authorize]` of `{}` requires a mapping attribute",
+            ident
+        ),
+    };
+    extend_mapping(
+        attr,
+        TokenStream2::new(),
+        quote! { , pre_authorize = #expression },
+    );
+
+    quote! { #function }.into()
+}
+
+/// Makes a `Principal` the one `#[pre_authorize]` expressions are evaluated against.
+/// The type must also be a request guard, resolving the principal of a request.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// #[principal]
+/// struct User {
+///     roles: Vec<String>,
+/// }
+///
+/// impl Principal for User {
+///     fn roles(&self) -> Vec<String> {
+///         self.roles.clone()
+///     }
+///
+///     fn permissions(&self) -> Vec<String> {
+///         Vec::new()
+///     }
+/// }
+///
+/// #[rocket::async_trait]
+/// impl<'r> FromRequest<'r> for User {
+///     // ...
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn principal(_args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemStruct);
+
+    let ident = &item.ident;
+    let register_fn_name =
+        format_ident!("_configure_principal_{}_", ident.to_string().to_lowercase());
+
+    let expanded = quote! {
+        #item
+
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            configure_principal(resolve_principal::<#ident>);
+        }
+    };
+
+    expanded.into()
+}
+
+// ----------------------------------------------------------------
+
+/// Maps a webhook receiver: a `POST` route whose `WebhookPayload` parameter is verified with
+/// the signature `scheme` and `secret` before the handler runs.
+///
+/// Schemes are `github-sha256`, `stripe`, `hmac-sha256` and `api-key`, see `WebhookScheme`.
+/// The `secret` is a `${key}` reference to the Rocket configuration, or the secret itself.
+/// Signatures are compared in constant time; stale timestamps and replayed deliveries are
+/// rejected with a `401`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// // Rocket.toml
+/// //
+/// // [default.hooks.github]
+/// // secret = "..."
+///
+/// #[webhook_mapping(path = "/hooks/github", scheme = "github-sha256", secret = "${hooks.github.secret}")]
+/// fn github_hook_fn(payload: WebhookPayload) -> String {
+///     format!("Received {} bytes", payload.body.len())
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn webhook_mapping(args: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as MappingArgs);
+    let function = parse_macro_input!(item as ItemFn);
+
+    let mut mapping = match Mapping::http_mapping("post", &parsed_args) {
+        Some(mapping) => mapping,
+        None => panic!("Invalid arguments to `#[webhook_mapping]`"),
+    };
+
+    let scheme = match parsed_args.string(SCHEME) {
+        Some(scheme) if WebhookScheme::from_name(&scheme).is_some() => scheme,
+        Some(scheme) => panic!(
+            "`{}` has an unknown webhook scheme `{}`",
+            function.sig.ident, scheme
+        ),
+        None => panic!(
+            "`#[webhook_mapping]` of `{}` requires a `scheme`",
+            function.sig.ident
+        ),
+    };
+    let secret = match parsed_args.string(SECRET) {
+        Some(secret) => secret,
+        None => panic!(
+            "`#[webhook_mapping]` of `{}` requires a `secret`",
+            function.sig.ident
+        ),
+    };
+
+    let (function, spec, payload) = webhook(function, &scheme, &secret);
+    mapping.data = format!("<{}>", payload);
+
+    let register_fn_name = format_ident!("_register_post_{}_", function.sig.ident);
+    let expanded: TokenStream2 = expand(mapping, &parsed_args, function, register_fn_name).into();
+
+    quote! {
+        #spec
+        #expanded
+    }
+    .into()
+}
+
+// ----------------------------------------------------------------
+
+/// Registers an error-mapping function for handlers returning `Result<T, E>` and mapped with
+/// `except = true` (or inside a controller declaring it): their `E` is converted by the
+/// `#[exception_handler]` taking an `E`, optionally scoped to a `namespace` (the most specific one wins).
+///
+/// Errors without a handler respond themselves if they implement `Responder`, and fall back
+/// to a `500` otherwise. The `E` of such handlers must be `Send + 'static`; handlers without
+/// `except` keep their signature and respond as Rocket does.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use rocket::http::Status;
+/// use autortr_rocket::prelude::*;
+///
+/// struct DbError(String);
+///
+/// #[exception_handler]
+/// fn on_db(error: DbError) -> (Status, String) {
+///     (Status::ServiceUnavailable, error.0)
+/// }
+///
+/// #[exception_handler(namespace = "/admin")]
+/// fn on_admin_db(error: DbError) -> (Status, String) {
+///     (Status::InternalServerError, format!("admin: {}", error.0))
+/// }
+///
+/// #[get_mapping(namespace = "/admin", path = "/users", except = true)]
+/// fn users_fn() -> Result<&'static str, DbError> {
+///     Err(DbError("down".to_string()))
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn exception_handler(args: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as MappingArgs);
     let function = parse_macro_input!(item as ItemFn);
 
     let function_ident = &function.sig.ident;
     let function_name = function_ident.to_string();
+    let namespace = parsed_args
+        .string(NAMESPACE)
+        .unwrap_or_else(|| ROOT.to_string());
 
-    let (namespace, path, data) = match parse_http_mapping_named_args(parsed_args) {
-        Ok((n, Some(p), d)) => (n, p, d),
-        Ok((n, None, d)) => {
-            // #[get_mapping("/get")] ...
-            match parse_http_mapping_args(parsed_args_clone) {
-                Ok(p) => (n, p, d),
-                Err(_) => {
-                    let message = format!("Invalid arguments to `#[{}_mapping]`", method);
-                    panic!("{}", message)
-                }
+    let error = match (function.sig.inputs.len(), function.sig.inputs.first()) {
+        (1, Some(FnArg::Typed(argument))) => argument.ty.clone(),
+        _ => panic!(
+            "`#[exception_handler]` `{}` must take exactly one error parameter",
+            function_name
+        ),
+    };
+
+    let thunk = format_ident!("__autortr_exception_{}", function_ident);
+    let register_fn_name = format_ident!("_register_exception_{}_", function_ident);
+
+    let expanded = quote! {
+        #function
+
+        #[doc(hidden)]
+        fn #thunk<'r>(
+            error: Box<dyn std::any::Any + Send>,
+            request: &'r rocket::Request<'_>,
+        ) -> rocket::response::Result<'static> {
+            match error.downcast::<#error>() {
+                Ok(error) => rocket::response::Responder::respond_to(#function_ident(*error), request),
+                Err(_) => Err(rocket::http::Status::InternalServerError),
             }
         }
-        Err(_) => {
+
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            register_exception_handler(ExceptionHandler {
+                function: #function_name.to_string(),
+                namespace: #namespace.to_string(),
+                type_id: std::any::TypeId::of::<#error>(),
+                type_name: std::any::type_name::<#error>(),
+                handler: #thunk,
+            });
+        }
+    };
+
+    expanded.into()
+}
+
+// ----------------------------------------------------------------
+
+/// Registers an `Interceptor` for the routes it matches, run by the router's fairing.
+///
+/// - `order`: pre-handles run by ascending order, post-handles by descending one
+/// - `include`/`exclude`: Ant patterns of the full route paths
+/// - `namespace`, `functions`, `tags`: restrict by `RouteMapping`
+///
+/// Unit structs are used as is, other ones must implement `Default`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use rocket::http::Status;
+/// use rocket::Request;
+/// use autortr_rocket::prelude::*;
+///
+/// #[interceptor(order = 10, include = "/api/**", exclude = "/api/public/**")]
+/// struct ApiKeyInterceptor;
+///
+/// impl Interceptor for ApiKeyInterceptor {
+///     fn pre_handle(&self, request: &Request<'_>, _: &RouteMapping) -> PreHandle {
+///         match request.headers().get_one("X-Api-Key") {
+///             Some(_) => PreHandle::Continue,
+///             None => PreHandle::Respond(InterceptorResponse::new(Status::Unauthorized)),
+///         }
+///     }
+/// }
+/// ```
+///
+/// @since 0.3.0
+#[proc_macro_attribute]
+pub fn interceptor(args: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as MappingArgs);
+    let item = parse_macro_input!(item as ItemStruct);
+
+    let ident = &item.ident;
+    let name = ident.to_string();
+    let order = match parsed_args.expr(ORDER) {
+        Some(order) => quote! { #order },
+        None => quote! { 0 },
+    };
+    let namespace = parsed_args
+        .string(NAMESPACE)
+        .unwrap_or_else(|| ROOT.to_string());
+    let include = parsed_args.strings(INCLUDE).unwrap_or_default();
+    let exclude = parsed_args.strings(EXCLUDE).unwrap_or_default();
+    let functions = parsed_args.strings(FUNCTIONS).unwrap_or_default();
+    let tags = parsed_args.strings(TAGS).unwrap_or_default();
+
+    let instance = match item.fields {
+        Fields::Unit => quote! { #ident },
+        _ => quote! { <#ident as Default>::default() },
+    };
+    let register_fn_name = format_ident!("_register_interceptor_{}_", name.to_lowercase());
+
+    let expanded = quote! {
+        #item
+
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            register_interceptor(InterceptorRegistration {
+                name: #name.to_string(),
+                order: #order,
+                include: vec![#(#include.to_string()),*],
+                exclude: vec![#(#exclude.to_string()),*],
+                namespace: #namespace.to_string(),
+                functions: vec![#(#functions.to_string()),*],
+                tags: vec![#(#tags.to_string()),*],
+                interceptor: std::sync::Arc::new(#instance),
+            });
+        }
+    };
+
+    expanded.into()
+}
+
+// ----------------------------------------------------------------
+
+/// Appends the controller's arguments to every mapping attribute of the inline module.
+#[doc(hidden)]
+fn controller_module(name: &str, rest: bool, args: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as MappingArgs);
+    let mut module = parse_macro_input!(item as ItemMod);
+
+    let items = match module.content.as_mut() {
+        Some((_, items)) => items,
+        None => panic!("`#[{}]` requires an inline module", name),
+    };
+
+    let guards = parsed_args.exprs(GUARDS);
+
+    for item in items.iter_mut() {
+        let function = match item {
+            Item::Fn(function) => function,
+            _ => continue,
+        };
+
+        for attr in function
+            .attrs
+            .iter_mut()
+            .filter(|attr| is_mapping(&attr.path))
+        {
+            let mapping_args: MappingArgs = match attr.parse_args() {
+                Ok(mapping_args) => mapping_args,
+                Err(_) => continue,
+            };
+
+            // The controller's guards come first, checked ahead of the mapping's own.
+            let mut leading = TokenStream2::new();
+            if !guards.is_empty() {
+                leading.extend(quote! { guards = [#(#guards),*], });
+            }
+
+            let mut extra = TokenStream2::new();
+            if rest && mapping_args.expr(REST).is_none() {
+                extra.extend(quote! { , rest = true });
+            }
+            if let (None, Some(except)) = (mapping_args.expr(EXCEPT), parsed_args.expr(EXCEPT)) {
+                extra.extend(quote! { , except = #except });
+            }
+            if let (None, Some(cors)) = (mapping_args.expr(CORS), parsed_args.string(CORS)) {
+                extra.extend(quote! { , cors = #cors });
+            }
+            if let (None, Some(rate_limit)) = (
+                mapping_args.expr(RATE_LIMIT),
+                parsed_args.string(RATE_LIMIT),
+            ) {
+                extra.extend(quote! { , rate_limit = #rate_limit });
+            }
+            let limited =
+                mapping_args.expr(RATE_LIMIT).is_some() || parsed_args.expr(RATE_LIMIT).is_some();
+            if let (true, None, Some(key)) = (
+                limited,
+                mapping_args.expr(RATE_LIMIT_KEY),
+                parsed_args.expr(RATE_LIMIT_KEY),
+            ) {
+                extra.extend(quote! { , rate_limit_key = #key });
+            }
+            if let (None, Some(authenticated)) = (
+                mapping_args.expr(AUTHENTICATED),
+                parsed_args.expr(AUTHENTICATED),
+            ) {
+                extra.extend(quote! { , authenticated = #authenticated });
+            }
+            if let (None, Some(namespace)) =
+                (mapping_args.expr(NAMESPACE), parsed_args.string(NAMESPACE))
+            {
+                extra.extend(quote! { , namespace = #namespace });
+            }
+
+            extend_mapping(attr, leading, extra);
+        }
+    }
+
+    quote! { #module }.into()
+}
+
+/// Adds `leading` and `extra` arguments around those of a mapping attribute.
+#[doc(hidden)]
+fn extend_mapping(attr: &mut Attribute, leading: TokenStream2, extra: TokenStream2) {
+    let mut inner: Vec<TokenTree> = match syn::parse2::<Group>(attr.tokens.clone()) {
+        Ok(group) => group.stream().into_iter().collect(),
+        Err(_) => return,
+    };
+    if matches!(inner.last(), Some(TokenTree::Punct(punct)) if punct.as_char() == ',') {
+        inner.pop();
+    }
+
+    attr.tokens = quote! { (#leading #(#inner)* #extra) };
+}
+
+#[doc(hidden)]
+fn http_mapping(method: &str, args: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed_args = parse_macro_input!(args as MappingArgs);
+    let function = parse_macro_input!(item as ItemFn);
+
+    let mapping = match Mapping::http_mapping(method, &parsed_args) {
+        Some(mapping) => mapping,
+        None => {
             let message = format!("Invalid arguments to `#[{}_mapping]`", method);
             panic!("{}", message)
         }
     };
 
-    let namespace = namespace.unwrap_or_else(|| ROOT.to_string());
-    let data = data.unwrap_or_else(|| EMPTY.to_string());
+    let register_fn_name = format_ident!("_register_{}_{}_", method, function.sig.ident);
 
-    let rkt_method = format_ident!("{}", method);
+    expand(mapping, &parsed_args, function, register_fn_name)
+}
 
-    let route = match data.as_str() {
-        "_" => quote! { #[rocket::#rkt_method(#path)] },
-        _ => quote! { #[rocket::#rkt_method(#path, data = #data)] },
+#[doc(hidden)]
+fn expand(
+    mapping: Mapping,
+    args: &MappingArgs,
+    function: ItemFn,
+    register_fn_name: Ident,
+) -> TokenStream {
+    let (function, pre_authorize) = pre_authorization(function, args);
+    let (function, mapping) = bind(function, mapping, args);
+    let parameters = parameter_types(&function, &mapping.path);
+    let (function, validated) = valid_parameters(function, &mapping, args);
+    require_namespace_parameters(&function, &mapping.dynamic_namespace);
+    let (function, constrained) = constrain(function, &mapping.constraints);
+    let function = match (args.flag(REST), args.flag(EXCEPT)) {
+        (true, _) => rest(function),
+        (false, true) => except(
+            function,
+            &join_path(&mapping.namespace, &mapping.dynamic_namespace),
+        ),
+        (false, false) => function,
     };
+    let function = validate(function, &validated);
+    let (function, authorized, authorities) = authorize(function, pre_authorize.as_deref());
+    let (function, guards) = guard(function, args);
+    let (function, limited, rate_limit_key) = rate_limit(function, args);
+    let function_ident = &function.sig.ident;
+    let function_name = function_ident.to_string();
+
+    let Mapping {
+        namespace,
+        dynamic_namespace,
+        method,
+        path,
+        pattern,
+        data,
+        ..
+    } = mapping;
 
-    let register_fn_name = format_ident!("_register_{}_{}_", method, function_ident);
+    let rkt_method = format_ident!("{}", method);
+    let mut route_args = quote! { #path };
+    if data != EMPTY {
+        route_args.extend(quote! { , data = #data });
+    }
+    if let Some(rank) = args.expr(RANK) {
+        route_args.extend(quote! { , rank = #rank });
+    }
+
+    let route = quote! { #[rocket::#rkt_method(#route_args)] };
+
+    let deprecated = is_deprecated(&function);
+    let cors = match args.string(CORS) {
+        Some(cors) => quote! { Some(#cors.to_string()) },
+        None => quote! { None },
+    };
+    let rate_limit = match args.string(RATE_LIMIT) {
+        Some(rate_limit) => quote! { Some(#rate_limit.to_string()) },
+        None => quote! { None },
+    };
+    let rate_limit_key = match rate_limit_key {
+        Some(key) => quote! { Some(#key.to_string()) },
+        None => quote! { None },
+    };
+    let pre_authorize_tokens = match &pre_authorize {
+        Some(expression) => quote! { Some(#expression.to_string()) },
+        None => quote! { None },
+    };
+    let tags = args.strings(TAGS).unwrap_or_default();
+    let parameters = parameters
+        .iter()
+        .map(|(name, ty)| quote! { (#name.to_string(), #ty.to_string()) });
 
     let register_fn = quote! {
         #[ctor::ctor]
+        #[allow(deprecated)]
         fn #register_fn_name() {
             register_route_mapping(RouteMapping {
                 function: #function_name.to_string(),
                 namespace: #namespace.to_string(),
+                dynamic_namespace: #dynamic_namespace.to_string(),
                 method: #method.to_string(),
                 path: #path.to_string(),
+                pattern: #pattern.to_string(),
+                parameters: vec![#(#parameters),*],
                 data: #data.to_string(),
+                deprecated: #deprecated,
+                tags: vec![#(#tags.to_string()),*],
+                guards: vec![#(#guards.to_string()),*],
+                pre_authorize: #pre_authorize_tokens,
+                authorities: vec![#(#authorities.to_string()),*],
+                cors: #cors,
+                rate_limit: #rate_limit,
+                rate_limit_key: #rate_limit_key,
                 routes: rocket::routes![#function_ident],
             });
         }
     };
 
     let expanded = quote! {
+        #constrained
+        #authorized
+        #limited
+
         #route
         #function
 
@@ -524,86 +1040,763 @@ fn http_mapping(method: &str, args: TokenStream, item: TokenStream) -> TokenStre
     expanded.into()
 }
 
-// ----------------------------------------------------------------
-
+/// The types of the handler parameters bound to the path and query of the template, by name,
+/// e.g. `("id", "u64")`, references without their lifetime.
 #[doc(hidden)]
-fn parse_request_mapping_args(
-    args: AttributeArgs,
-) -> Result<(Option<String>, String, String, Option<String>), ()> {
-    let mut namespace = None;
-    let mut method = None;
-    let mut path = None;
-    let mut data = None;
-
-    for arg in args {
-        match arg {
-            NestedMeta::Meta(Meta::NameValue(nv)) => {
-                if nv.path.is_ident(NAMESPACE) {
-                    if let Lit::Str(n) = nv.lit {
-                        namespace = Some(n.value());
-                    }
-                } else if nv.path.is_ident(METHOD) {
-                    if let Lit::Str(m) = nv.lit {
-                        method = Some(m.value());
-                    }
-                } else if nv.path.is_ident(PATH) {
-                    if let Lit::Str(p) = nv.lit {
-                        path = Some(p.value());
-                    }
-                } else if nv.path.is_ident(DATA) {
-                    if let Lit::Str(d) = nv.lit {
-                        data = Some(d.value());
-                    }
+fn parameter_types(function: &ItemFn, template: &str) -> Vec<(String, String)> {
+    let names = template_parameters(template);
+
+    function
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(argument) => match argument.pat.as_ref() {
+                Pat::Ident(pat) if names.contains(&pat.ident.to_string()) => {
+                    Some((pat.ident.to_string(), type_name(&argument.ty)))
                 }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+#[doc(hidden)]
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Reference(reference) => format!("&{}", type_name(&reference.elem)),
+        ty => quote! { #ty }.to_string().replace(' ', ""),
+    }
+}
+
+/// Places `#[path_var]` and `#[query_param]` parameters into the path template and infers
+/// the `data` binding from the single `#[request_body]` or `FromData` parameter, unless it
+/// is given explicitly. The parameter attributes are stripped from the function.
+#[doc(hidden)]
+fn bind(function: ItemFn, mapping: Mapping, args: &MappingArgs) -> (ItemFn, Mapping) {
+    let mut function = function;
+    let mut mapping = mapping;
+
+    let mut path_vars = Vec::new();
+    let mut query_params = Vec::new();
+    let mut bodies = Vec::new();
+    let mut inferred = Vec::new();
+
+    for input in function.sig.inputs.iter_mut() {
+        let argument = match input {
+            FnArg::Typed(argument) => argument,
+            FnArg::Receiver(_) => continue,
+        };
+
+        let marks: Vec<String> = argument
+            .attrs
+            .iter()
+            .filter_map(|attr| attr.path.get_ident().map(|ident| ident.to_string()))
+            .filter(|name| [PATH_VAR, QUERY_PARAM, REQUEST_BODY].contains(&name.as_str()))
+            .collect();
+        argument.attrs.retain(|attr| {
+            !attr.path.is_ident(PATH_VAR)
+                && !attr.path.is_ident(QUERY_PARAM)
+                && !attr.path.is_ident(REQUEST_BODY)
+        });
+
+        let name = match argument.pat.as_ref() {
+            Pat::Ident(pat) => pat.ident.to_string(),
+            _ if marks.is_empty() => continue,
+            _ => panic!(
+                "`{}` has a `#[{}]` parameter without a plain name",
+                function.sig.ident, marks[0]
+            ),
+        };
+
+        for mark in &marks {
+            match mark.as_str() {
+                PATH_VAR => path_vars.push(name.clone()),
+                QUERY_PARAM => query_params.push(name.clone()),
+                _ => bodies.push(name.clone()),
             }
-            _ => return Err(()),
         }
+        if marks.is_empty() && is_from_data(&argument.ty) {
+            inferred.push(name);
+        }
+    }
+
+    let known = template_parameters(&mapping.path);
+    let (route, query) = match mapping.path.split_once('?') {
+        Some((route, query)) => (route.to_string(), Some(query.to_string())),
+        None => (mapping.path.clone(), None),
+    };
+
+    let mut route = route;
+    for name in path_vars.iter().filter(|name| !known.contains(name)) {
+        route = join_path(&route, &format!("<{}>", name));
+    }
+
+    let mut fields: Vec<String> = query.into_iter().collect();
+    for name in query_params.iter().filter(|name| !known.contains(name)) {
+        fields.push(format!("<{}>", name));
     }
 
-    match (method, path) {
-        (Some(m), Some(p)) => Ok((namespace, m, p, data)),
-        _ => Err(()),
+    mapping.path = match fields.is_empty() {
+        true => route,
+        false => format!("{}?{}", route, fields.join("&")),
+    };
+
+    let body = match (bodies.len(), inferred.len()) {
+        (0, 0) => None,
+        (0, 1) => inferred.pop(),
+        (1, _) => bodies.pop(),
+        (0, _) => panic!(
+            "`{}` has more than one body parameter ({}), mark one with `#[{}]`",
+            function.sig.ident,
+            inferred.join(", "),
+            REQUEST_BODY
+        ),
+        _ => panic!(
+            "`{}` has more than one `#[{}]` parameter",
+            function.sig.ident, REQUEST_BODY
+        ),
+    };
+
+    if let Some(body) = body {
+        let data = format!("<{}>", body);
+        match args.string(DATA) {
+            Some(explicit) if explicit != data && !bodies.is_empty() => panic!(
+                "`data = \"{}\"` conflicts with the `#[{}]` parameter `{}`",
+                explicit, REQUEST_BODY, body
+            ),
+            Some(_) => {}
+            None => mapping.data = data,
+        }
     }
+
+    (function, mapping)
 }
 
-// ----------------------------------------------------------------
+/// Whether the type is one of Rocket's `FromData` guards, optionally wrapped in
+/// `Option` or `Result`.
+#[doc(hidden)]
+fn is_from_data(ty: &Type) -> bool {
+    let segment = match ty {
+        Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => segment,
+            None => return false,
+        },
+        _ => return false,
+    };
 
+    match segment.ident.to_string().as_str() {
+        "Form" | "Json" | "MsgPack" | "Data" | "TempFile" | "Capped" => true,
+        "Option" | "Result" => match &segment.arguments {
+            PathArguments::AngleBracketed(arguments) => match arguments.args.first() {
+                Some(GenericArgument::Type(ty)) => is_from_data(ty),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Strips `#[valid]` from the parameters and returns the names of the bodies to validate:
+/// the marked ones, and the `data` binding with `validate = true`.
 #[doc(hidden)]
-fn parse_http_mapping_named_args(
-    args: AttributeArgs,
-) -> Result<(Option<String>, Option<String>, Option<String>), ()> {
-    let mut namespace = None;
-    let mut path = None;
-    let mut data = None;
-
-    for arg in args {
-        match arg {
-            NestedMeta::Meta(Meta::NameValue(nv)) => {
-                if nv.path.is_ident(NAMESPACE) {
-                    if let Lit::Str(n) = nv.lit {
-                        namespace = Some(n.value());
-                    }
-                } else if nv.path.is_ident(PATH) {
-                    if let Lit::Str(p) = nv.lit {
-                        path = Some(p.value());
-                    }
-                } else if nv.path.is_ident(DATA) {
-                    if let Lit::Str(d) = nv.lit {
-                        data = Some(d.value());
-                    }
+fn valid_parameters(
+    function: ItemFn,
+    mapping: &Mapping,
+    args: &MappingArgs,
+) -> (ItemFn, Vec<Ident>) {
+    let mut function = function;
+    let mut names = Vec::new();
+
+    for input in function.sig.inputs.iter_mut() {
+        if let FnArg::Typed(argument) = input {
+            let marked = argument.attrs.iter().any(|attr| attr.path.is_ident(VALID));
+            argument.attrs.retain(|attr| !attr.path.is_ident(VALID));
+
+            match (marked, argument.pat.as_ref()) {
+                (true, Pat::Ident(pat)) => names.push(pat.ident.clone()),
+                (true, _) => panic!(
+                    "`{}` has a `#[{}]` parameter without a plain name",
+                    function.sig.ident, VALID
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    if args.flag(VALIDATE) {
+        if !mapping.has_data() {
+            panic!(
+                "`validate = true` on `{}` requires a `data` binding",
+                function.sig.ident
+            );
+        }
+
+        let data = mapping.data.trim_start_matches('<').trim_end_matches('>');
+        if !names.iter().any(|name| name == data) {
+            names.push(format_ident!("{}", data));
+        }
+    }
+
+    (function, names)
+}
+
+/// Wraps the `validated` bodies in `Validated` guards: invalid ones answer a `422`
+/// before the handler runs, see `Validation`.
+#[doc(hidden)]
+fn validate(function: ItemFn, validated: &[Ident]) -> ItemFn {
+    if validated.is_empty() {
+        return function;
+    }
+
+    let output = match &function.sig.output {
+        ReturnType::Type(_, ty) => quote! { #ty },
+        ReturnType::Default => quote! { () },
+    };
+
+    let mut function = wrap(
+        function,
+        syn::parse_quote! { -> Validation<#output> },
+        |call| {
+            quote! {
+                #(
+                    let #validated = match #validated.into_result() {
+                        Ok(body) => body,
+                        Err(errors) => return Validation::Invalid(errors),
+                    };
+                )*
+
+                Validation::Valid(#call)
+            }
+        },
+    );
+
+    for input in function.sig.inputs.iter_mut() {
+        if let FnArg::Typed(argument) = input {
+            let validates = matches!(
+                argument.pat.as_ref(),
+                Pat::Ident(pat) if validated.contains(&pat.ident)
+            );
+            if validates {
+                let ty = argument.ty.clone();
+                *argument.ty = syn::parse_quote! { Validated<#ty> };
+            }
+        }
+    }
+
+    function
+}
+
+/// Wraps a `rest` handler: its `T` or `Result<T, E>` is enveloped into a `RestResponse`.
+#[doc(hidden)]
+fn rest(function: ItemFn) -> ItemFn {
+    let envelope = match &function.sig.output {
+        ReturnType::Type(_, ty) if is_result(ty) => quote! { RestResponse::from_result },
+        _ => quote! { RestResponse::ok },
+    };
+
+    wrap(function, syn::parse_quote! { -> RestResponse }, |call| {
+        quote! { #envelope(#call) }
+    })
+}
+
+/// Wraps a handler returning `Result<T, E>`: `E` is converted by the `#[exception_handler]`
+/// registered for the `namespace`, see `ExceptionResponse`.
+#[doc(hidden)]
+fn except(function: ItemFn, namespace: &str) -> ItemFn {
+    let ok = match &function.sig.output {
+        ReturnType::Type(_, ty) => match result_arguments(ty) {
+            Some((ok, _)) => ok,
+            None => return function,
+        },
+        ReturnType::Default => return function,
+    };
+
+    wrap(
+        function,
+        syn::parse_quote! { -> Result<#ok, ExceptionResponse> },
+        |call| {
+            quote! {
+                match #call {
+                    Ok(value) => Ok(value),
+                    Err(error) => Err((&ExceptionProbe::new(error, #namespace)).to_exception()),
+                }
+            }
+        },
+    )
+}
+
+/// Moves the function's body into an inner function and replaces it with `outer(call)`,
+/// where `call` invokes the inner function with the parameters.
+#[doc(hidden)]
+fn wrap<F>(function: ItemFn, output: ReturnType, outer: F) -> ItemFn
+where
+    F: FnOnce(TokenStream2) -> TokenStream2,
+{
+    let mut function = function;
+    let mut inner = function.clone();
+    inner.attrs.clear();
+    inner.vis = syn::Visibility::Inherited;
+    inner.sig.ident = format_ident!("__autortr_inner_{}", function.sig.ident);
+
+    // Patterns such as `_` or `(a, b)` stay on the inner function, the outer one names them.
+    let mut arguments = Vec::new();
+    for (index, input) in function.sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Typed(argument) => match argument.pat.as_mut() {
+                Pat::Ident(pat) => {
+                    pat.mutability = None;
+                    arguments.push(pat.ident.clone());
+                }
+                pat => {
+                    let ident = format_ident!("__autortr_arg_{}", index);
+                    *pat = syn::parse_quote! { #ident };
+                    arguments.push(ident);
                 }
+            },
+            FnArg::Receiver(_) => panic!("`{}` can't take `self`", function.sig.ident),
+        }
+    }
+
+    let inner_ident = &inner.sig.ident;
+    let call = match function.sig.asyncness {
+        Some(_) => quote! { #inner_ident(#(#arguments),*).await },
+        None => quote! { #inner_ident(#(#arguments),*) },
+    };
+    let body = outer(call);
+
+    function.sig.output = output;
+    function.block = Box::new(syn::parse_quote! {{
+        #inner
+
+        #body
+    }});
+
+    function
+}
+
+/// The `T` and `E` of a `Result<T, E>`.
+#[doc(hidden)]
+fn result_arguments(ty: &Type) -> Option<(Type, Type)> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+
+    let arguments = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => &arguments.args,
+        _ => return None,
+    };
+    let mut types = arguments.iter().filter_map(|argument| match argument {
+        GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    });
+
+    match (types.next(), types.next()) {
+        (Some(ok), Some(error)) => Some((ok, error)),
+        _ => None,
+    }
+}
+
+#[doc(hidden)]
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Result")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Whether the attribute is one of the mapping macros.
+#[doc(hidden)]
+fn is_mapping(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .map(|segment| {
+            let name = segment.ident.to_string();
+            name == "request_mapping" || name.ends_with("_mapping")
+        })
+        .unwrap_or(false)
+}
+
+/// Panics unless the handler has a parameter for each dynamic namespace segment.
+#[doc(hidden)]
+fn require_namespace_parameters(function: &ItemFn, dynamic_namespace: &str) {
+    for name in template_parameters(dynamic_namespace) {
+        if name == "_" {
+            continue;
+        }
+
+        let found = function.sig.inputs.iter().any(|input| match input {
+            FnArg::Typed(argument) => {
+                matches!(argument.pat.as_ref(), Pat::Ident(pat) if pat.ident == name)
+            }
+            FnArg::Receiver(_) => false,
+        });
+        if !found {
+            panic!(
+                "`{}` has no `{}` parameter for the namespace segment in `{}`",
+                function.sig.ident, name, dynamic_namespace
+            );
+        }
+    }
+}
+
+/// The `pre_authorize` argument, or the `#[pre_authorize]` attribute below the mapping,
+/// which is stripped.
+#[doc(hidden)]
+fn pre_authorization(function: ItemFn, args: &MappingArgs) -> (ItemFn, Option<String>) {
+    let mut function = function;
+
+    let mut expressions: Vec<String> = args.string(PRE_AUTHORIZE).into_iter().collect();
+    function.attrs.retain(|attr| {
+        let is_pre_authorize = attr
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == PRE_AUTHORIZE)
+            .unwrap_or(false);
+        if is_pre_authorize {
+            match attr.parse_args::<LitStr>() {
+                Ok(expression) => expressions.push(expression.value()),
+                Err(_) => panic!("`#[pre_authorize]` takes an expression string"),
             }
-            _ => {}
         }
+
+        !is_pre_authorize
+    });
+
+    if expressions.len() > 1 {
+        panic!("`{}` has more than one `pre_authorize`", function.sig.ident);
     }
 
-    Ok((namespace, path, data))
+    (function, expressions.pop())
+}
+
+/// Prepends a `PreAuthorized` guard parameter checking the `pre_authorize` expression, with its
+/// hidden `AuthorityRule` type. Returns the rule and the expression's authorities.
+#[doc(hidden)]
+fn authorize(function: ItemFn, expression: Option<&str>) -> (ItemFn, TokenStream2, Vec<String>) {
+    let mut function = function;
+    let expression = match expression {
+        Some(expression) => expression,
+        None => return (function, TokenStream2::new(), Vec::new()),
+    };
+
+    let authorities = parse_authority(&function, expression).authorities();
+    let rule = format_ident!("__autortr_authorize_{}", function.sig.ident);
+    function.sig.inputs.insert(
+        0,
+        syn::parse_quote! { __autortr_authorize: PreAuthorized<#rule> },
+    );
+
+    let rule = quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #rule;
+
+        impl AuthorityRule for #rule {
+            fn authority() -> &'static Authority {
+                static AUTHORITY: std::sync::OnceLock<Authority> = std::sync::OnceLock::new();
+                AUTHORITY.get_or_init(|| Authority::parse(#expression).unwrap())
+            }
+        }
+    };
+
+    (function, rule, authorities)
 }
 
+/// Panics on an invalid `pre_authorize` expression.
 #[doc(hidden)]
-fn parse_http_mapping_args(args: AttributeArgs) -> Result<String, ()> {
-    match args.first() {
-        Some(NestedMeta::Lit(Lit::Str(path))) => Ok(path.value()),
-        _ => Err(()),
+fn parse_authority(function: &ItemFn, expression: &str) -> Authority {
+    match Authority::parse(expression) {
+        Ok(authority) => authority,
+        Err(error) => panic!(
+            "Invalid `pre_authorize` expression of `{}`: {}",
+            function.sig.ident, error
+        ),
     }
 }
+
+/// Replaces the `WebhookPayload` parameter by a `Webhook` data guard, with its hidden
+/// `WebhookSpec` type. Returns the spec and the parameter name.
+#[doc(hidden)]
+fn webhook(function: ItemFn, scheme: &str, secret: &str) -> (ItemFn, TokenStream2, Ident) {
+    let mut function = function;
+    let spec = format_ident!("__autortr_webhook_{}", function.sig.ident);
+    let ident = format_ident!("__autortr_payload");
+
+    let is_payload = |ty: &Type| match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "WebhookPayload")
+            .unwrap_or(false),
+        _ => false,
+    };
+    let mut payloads = function
+        .sig
+        .inputs
+        .iter_mut()
+        .filter_map(|input| match input {
+            FnArg::Typed(argument) if is_payload(&argument.ty) => Some(argument),
+            _ => None,
+        });
+    let argument = match (payloads.next(), payloads.next()) {
+        (Some(argument), None) => argument,
+        _ => panic!(
+            "`{}` must take exactly one `WebhookPayload` parameter",
+            function.sig.ident
+        ),
+    };
+
+    let pat = argument.pat.clone();
+    *argument.pat = syn::parse_quote! { #ident };
+    *argument.ty = syn::parse_quote! { Webhook<#spec> };
+
+    let body = &function.block;
+    function.block = Box::new(syn::parse_quote! {{
+        let #pat: WebhookPayload = #ident.into_inner();
+        #body
+    }});
+
+    let spec_tokens = quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #spec;
+
+        impl WebhookSpec for #spec {
+            fn scheme() -> WebhookScheme {
+                WebhookScheme::from_name(#scheme).unwrap()
+            }
+
+            fn secret() -> &'static str {
+                #secret
+            }
+        }
+    };
+
+    (function, spec_tokens, ident)
+}
+
+/// Prepends one request guard parameter per `guards` type, so that Rocket checks them before
+/// any other parameter, the `Authenticated` one of `authenticated` first unless the handler
+/// already takes an `Authenticated`. Returns the guard type names.
+#[doc(hidden)]
+fn guard(function: ItemFn, args: &MappingArgs) -> (ItemFn, Vec<String>) {
+    let mut function = function;
+    let mut types: Vec<TokenStream2> = Vec::new();
+
+    let authenticated = match args.expr(AUTHENTICATED) {
+        Some(Expr::Path(claims)) => Some(quote! { Authenticated<#claims> }),
+        Some(_) if args.flag(AUTHENTICATED) => Some(quote! { Authenticated }),
+        _ => None,
+    };
+    let mut names = Vec::new();
+    match authenticated {
+        Some(ty) if takes_authenticated(&function) => names.push(ty.to_string().replace(' ', "")),
+        Some(ty) => types.push(ty),
+        None => {}
+    }
+
+    for guard in args.exprs(GUARDS).iter() {
+        match guard {
+            Expr::Path(path) => types.push(quote! { #path }),
+            _ => panic!(
+                "`{}` has a guard which is not a type: `{}`",
+                function.sig.ident,
+                quote! { #guard }
+            ),
+        }
+    }
+
+    let mut inputs = Vec::new();
+    for (index, ty) in types.iter().enumerate() {
+        let ident = format_ident!("__autortr_guard_{}", index);
+
+        names.push(ty.to_string().replace(' ', ""));
+        inputs.push(syn::parse_quote! { #ident: #ty });
+    }
+
+    let rest = std::mem::take(&mut function.sig.inputs);
+    function.sig.inputs = inputs.into_iter().chain(rest).collect();
+
+    (function, names)
+}
+
+/// Whether the handler takes an `Authenticated` parameter, whatever its claims.
+#[doc(hidden)]
+fn takes_authenticated(function: &ItemFn) -> bool {
+    function.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(argument) => match argument.ty.as_ref() {
+            Type::Path(ty) => ty
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Authenticated"),
+            _ => false,
+        },
+        FnArg::Receiver(_) => false,
+    })
+}
+
+/// Inserts a `RateLimited` guard parameter for the `rate_limit` behind the `authenticated`,
+/// `guards` and `pre_authorize` guards, so that rejected requests don't count, with its hidden
+/// `RateLimitSpec` type. Returns the spec and the key name.
+#[doc(hidden)]
+fn rate_limit(function: ItemFn, args: &MappingArgs) -> (ItemFn, TokenStream2, Option<String>) {
+    let mut function = function;
+    let limit = match args.string(RATE_LIMIT) {
+        Some(limit) => limit,
+        None if args.expr(RATE_LIMIT_KEY).is_some() => panic!(
+            "`{}` has a `rate_limit_key` without a `rate_limit`",
+            function.sig.ident
+        ),
+        None => return (function, TokenStream2::new(), None),
+    };
+
+    let RateLimit { requests, period } = match RateLimit::parse(&limit) {
+        Ok(limit) => limit,
+        Err(error) => panic!(
+            "Invalid `rate_limit` of `{}`: {}",
+            function.sig.ident, error
+        ),
+    };
+
+    let (key, name) = match args.expr(RATE_LIMIT_KEY) {
+        None => (quote! { RateLimitByIp }, "ip".to_string()),
+        Some(Expr::Path(path)) => (quote! { #path }, quote! { #path }.to_string().replace(' ', "")),
+        Some(_) => match args.string(RATE_LIMIT_KEY).as_deref() {
+            Some("ip") => (quote! { RateLimitByIp }, "ip".to_string()),
+            Some("api-key") => (quote! { RateLimitByApiKey }, "api-key".to_string()),
+            _ => panic!(
+                "`{}` has an unknown `rate_limit_key`, expected `\"ip\"`, `\"api-key\"` or a `RateLimitKey` type",
+                function.sig.ident
+            ),
+        },
+    };
+
+    let function_name = function.sig.ident.to_string();
+    let spec = format_ident!("__autortr_rate_limit_{}", function.sig.ident);
+    let position = function
+        .sig
+        .inputs
+        .iter()
+        .take_while(|input| is_injected_guard(input))
+        .count();
+    function.sig.inputs.insert(
+        position,
+        syn::parse_quote! { __autortr_rate_limit: RateLimited<#spec> },
+    );
+
+    let spec_tokens = quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #spec;
+
+        impl RateLimitSpec for #spec {
+            fn name() -> &'static str {
+                #function_name
+            }
+
+            fn limit() -> RateLimit {
+                RateLimit::new(#requests, #period)
+            }
+
+            fn key(request: &rocket::Request<'_>) -> Option<String> {
+                <#key as RateLimitKey>::key(request)
+            }
+        }
+    };
+
+    (function, spec_tokens, Some(name))
+}
+
+/// Whether the parameter is one of the guards prepended by `guard` and `authorize`.
+#[doc(hidden)]
+fn is_injected_guard(input: &FnArg) -> bool {
+    match input {
+        FnArg::Typed(argument) => match argument.pat.as_ref() {
+            Pat::Ident(pat) => {
+                let name = pat.ident.to_string();
+                name.starts_with("__autortr_guard_") || name == "__autortr_authorize"
+            }
+            _ => false,
+        },
+        FnArg::Receiver(_) => false,
+    }
+}
+
+/// Wraps each regex-constrained parameter in a `Constrained` guard, generating one
+/// hidden `ParamConstraint` type per constraint.
+#[doc(hidden)]
+fn constrain(function: ItemFn, constraints: &[PathConstraint]) -> (ItemFn, TokenStream2) {
+    let mut function = function;
+    let mut guards = TokenStream2::new();
+    let mut unwraps = Vec::new();
+
+    for constraint in constraints {
+        let guard = format_ident!("__autortr_{}_{}", function.sig.ident, constraint.name);
+        let anchored = constraint.anchored();
+
+        let argument = function
+            .sig
+            .inputs
+            .iter_mut()
+            .find_map(|input| match input {
+                FnArg::Typed(argument) => match argument.pat.as_ref() {
+                    Pat::Ident(pat) if pat.ident == constraint.name => Some(argument),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            });
+        let argument = match argument {
+            Some(argument) => argument,
+            None => panic!(
+                "`{}` has no `{}` parameter for the constrained path variable",
+                function.sig.ident, constraint.name
+            ),
+        };
+
+        let pat = argument.pat.clone();
+        let ty = argument.ty.clone();
+        let ident = format_ident!("{}", constraint.name);
+
+        *argument.pat = syn::parse_quote! { #ident };
+        *argument.ty = syn::parse_quote! { Constrained<#guard, #ty> };
+        unwraps.push(quote! { let #pat: #ty = #ident.into_inner(); });
+
+        guards.extend(quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            struct #guard;
+
+            impl ParamConstraint for #guard {
+                fn pattern() -> &'static PathPattern {
+                    static PATTERN: PathPattern = PathPattern::new(#anchored);
+                    &PATTERN
+                }
+            }
+        });
+    }
+
+    if !unwraps.is_empty() {
+        let body = &function.block;
+        function.block = Box::new(syn::parse_quote! {{
+            #(#unwraps)*
+            #body
+        }});
+    }
+
+    (function, guards)
+}
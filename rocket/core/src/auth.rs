@@ -0,0 +1,361 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/auth
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub use jsonwebtoken::Algorithm as JwtAlgorithm;
+
+// ----------------------------------------------------------------
+
+static JWT_AUTH: RwLock<Option<Arc<JwtAuth>>> = RwLock::new(None);
+
+/// The asymmetric algorithms accepted by default.
+const DEFAULT_ALGORITHMS: [JwtAlgorithm; 9] = [
+    JwtAlgorithm::RS256,
+    JwtAlgorithm::RS384,
+    JwtAlgorithm::RS512,
+    JwtAlgorithm::PS256,
+    JwtAlgorithm::PS384,
+    JwtAlgorithm::PS512,
+    JwtAlgorithm::ES256,
+    JwtAlgorithm::ES384,
+    JwtAlgorithm::EdDSA,
+];
+
+// ----------------------------------------------------------------
+
+/// How `Authenticated` verifies bearer tokens: local verification keys, from a JWKS or PEMs,
+/// and the expected `iss` and `aud`. Nothing is fetched over the network.
+///
+/// Tokens must be signed by one of the keys (the one of their `kid`, if both have one) with
+/// an allowed algorithm, and carry a valid `exp`. A present `nbf` is checked as well, and
+/// `iss`/`aud` are required once configured.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use autortr_rocket::prelude::*;
+///
+/// configure_jwt(
+///     JwtAuth::jwks_file("keys/jwks.json")
+///         .issuer("https://id.example.com")
+///         .audience("orders"),
+/// );
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone)]
+pub struct JwtAuth {
+    keys: Vec<JwtKey>,
+    algorithms: Vec<JwtAlgorithm>,
+    issuers: Vec<String>,
+    audiences: Vec<String>,
+    leeway: u64,
+}
+
+impl JwtAuth {
+    /// The keys of a JWKS document. Panics on an invalid document or key.
+    pub fn jwks(jwks: &str) -> Self {
+        let set: JwkSet = match serde_json::from_str(jwks) {
+            Ok(set) => set,
+            Err(error) => panic!("Invalid JWKS: {}", error),
+        };
+
+        let keys = set
+            .keys
+            .iter()
+            .map(|jwk| match DecodingKey::from_jwk(jwk) {
+                Ok(key) => JwtKey {
+                    kid: jwk.common.key_id.clone(),
+                    algorithm: jwk
+                        .common
+                        .key_algorithm
+                        .and_then(|algorithm| algorithm.to_string().parse().ok()),
+                    key,
+                },
+                Err(error) => panic!(
+                    "Invalid JWK `{}`: {}",
+                    jwk.common.key_id.as_deref().unwrap_or("-"),
+                    error
+                ),
+            })
+            .collect();
+
+        JwtAuth::new(keys)
+    }
+
+    /// The keys of a local JWKS file. Panics if it can't be read.
+    pub fn jwks_file<P: AsRef<Path>>(path: P) -> Self {
+        match std::fs::read_to_string(path.as_ref()) {
+            Ok(jwks) => JwtAuth::jwks(&jwks),
+            Err(error) => panic!("Unreadable JWKS `{}`: {}", path.as_ref().display(), error),
+        }
+    }
+
+    /// An RSA public key. Panics on an invalid PEM.
+    pub fn rsa_pem(pem: &[u8]) -> Self {
+        JwtAuth::pem("RSA", DecodingKey::from_rsa_pem(pem))
+    }
+
+    /// An EC public key. Panics on an invalid PEM.
+    pub fn ec_pem(pem: &[u8]) -> Self {
+        JwtAuth::pem("EC", DecodingKey::from_ec_pem(pem))
+    }
+
+    /// An Ed25519 public key. Panics on an invalid PEM.
+    pub fn ed_pem(pem: &[u8]) -> Self {
+        JwtAuth::pem("Ed25519", DecodingKey::from_ed_pem(pem))
+    }
+
+    /// Adds the keys of `other`, e.g. a PEM next to a JWKS.
+    pub fn and(mut self, other: JwtAuth) -> Self {
+        self.keys.extend(other.keys);
+        self
+    }
+
+    /// The accepted algorithms, the asymmetric ones by default.
+    pub fn algorithms(mut self, algorithms: &[JwtAlgorithm]) -> Self {
+        self.algorithms = algorithms.to_vec();
+        self
+    }
+
+    /// Adds an accepted `iss`, then required.
+    pub fn issuer<I: Into<String>>(mut self, issuer: I) -> Self {
+        self.issuers.push(issuer.into());
+        self
+    }
+
+    /// Adds an accepted `aud`, then required.
+    pub fn audience<A: Into<String>>(mut self, audience: A) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// The clock skew tolerated on `exp` and `nbf`, in seconds, `60` by default.
+    pub fn leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Verifies a token and decodes its claims.
+    pub fn verify<C: DeserializeOwned>(&self, token: &str) -> Result<C, AuthError> {
+        let header = decode_header(token).map_err(|error| AuthError::Invalid(error.to_string()))?;
+        if !self.algorithms.contains(&header.alg) {
+            return Err(AuthError::Invalid(format!(
+                "Algorithm {:?} is not accepted",
+                header.alg
+            )));
+        }
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.leeway;
+        validation.validate_nbf = true;
+
+        let mut required = vec!["exp"];
+        if !self.issuers.is_empty() {
+            validation.set_issuer(&self.issuers);
+            required.push("iss");
+        }
+        match self.audiences.is_empty() {
+            true => validation.validate_aud = false,
+            false => {
+                validation.set_audience(&self.audiences);
+                required.push("aud");
+            }
+        }
+        validation.set_required_spec_claims(&required);
+
+        let candidates = self.keys.iter().filter(|key| {
+            let kid = match (&header.kid, &key.kid) {
+                (Some(token), Some(key)) => token == key,
+                _ => true,
+            };
+            let algorithm = key
+                .algorithm
+                .map(|algorithm| algorithm == header.alg)
+                .unwrap_or(true);
+
+            kid && algorithm
+        });
+
+        let mut error = AuthError::Invalid("No matching key".to_string());
+        for key in candidates {
+            match decode::<C>(token, &key.key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(cause) => error = AuthError::Invalid(cause.to_string()),
+            }
+        }
+
+        Err(error)
+    }
+
+    #[doc(hidden)]
+    fn new(keys: Vec<JwtKey>) -> Self {
+        JwtAuth {
+            keys,
+            algorithms: DEFAULT_ALGORITHMS.to_vec(),
+            issuers: Vec::new(),
+            audiences: Vec::new(),
+            leeway: 60,
+        }
+    }
+
+    #[doc(hidden)]
+    fn pem(kind: &str, key: jsonwebtoken::errors::Result<DecodingKey>) -> Self {
+        match key {
+            Ok(key) => JwtAuth::new(vec![JwtKey {
+                kid: None,
+                algorithm: None,
+                key,
+            }]),
+            Err(error) => panic!("Invalid {} PEM: {}", kind, error),
+        }
+    }
+}
+
+/// Configures how `Authenticated` verifies bearer tokens.
+///
+/// @since 0.3.0
+pub fn configure_jwt(auth: JwtAuth) {
+    *JWT_AUTH.write().unwrap() = Some(Arc::new(auth));
+}
+
+/// The configured verification, if any.
+///
+/// @since 0.3.0
+pub fn jwt_auth() -> Option<Arc<JwtAuth>> {
+    JWT_AUTH.read().unwrap().clone()
+}
+
+// ----------------------------------------------------------------
+
+/// Why a request is not authenticated.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthError {
+    /// No `configure_jwt` yet.
+    Unconfigured,
+    /// No `Authorization: Bearer ..` header.
+    Missing,
+    /// The token is malformed, badly signed, expired, not yet valid or for someone else.
+    Invalid(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::Unconfigured => write!(f, "JWT verification is not configured"),
+            AuthError::Missing => write!(f, "Missing bearer token"),
+            AuthError::Invalid(cause) => write!(f, "Invalid bearer token: {}", cause),
+        }
+    }
+}
+
+/// The registered claims of a token, and the others in `extra`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Option<String>,
+    pub iss: Option<String>,
+    pub exp: Option<u64>,
+    pub nbf: Option<u64>,
+    pub iat: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A request guard verifying the `Authorization: Bearer ..` token with the configured `JwtAuth`,
+/// failing with a `401` otherwise. Dereferences to the token's claims.
+///
+/// Injected by the mappings' `authenticated = true` (or `authenticated = MyClaims`) argument.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct Authenticated<C = Claims>(pub C);
+
+impl<C> Authenticated<C> {
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<C> Deref for Authenticated<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, C: DeserializeOwned + Send> FromRequest<'r> for Authenticated<C> {
+    type Error = AuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let auth = match jwt_auth() {
+            Some(auth) => auth,
+            None => {
+                rocket::warn!("`Authenticated` without a JWT verification, see `configure_jwt`");
+                return Outcome::Error((Status::Unauthorized, AuthError::Unconfigured));
+            }
+        };
+
+        let token = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|authorization| authorization.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+            .map(|(_, token)| token.trim());
+        let token = match token {
+            Some(token) if !token.is_empty() => token,
+            _ => return Outcome::Error((Status::Unauthorized, AuthError::Missing)),
+        };
+
+        match auth.verify(token) {
+            Ok(claims) => Outcome::Success(Authenticated(claims)),
+            Err(error) => Outcome::Error((Status::Unauthorized, error)),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+#[derive(Clone)]
+struct JwtKey {
+    kid: Option<String>,
+    /// The algorithm the JWK is restricted to, if any.
+    algorithm: Option<JwtAlgorithm>,
+    key: DecodingKey,
+}
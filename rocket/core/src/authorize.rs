@@ -0,0 +1,162 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/authorize
+
+// ----------------------------------------------------------------
+
+use std::marker::PhantomData;
+use std::sync::RwLock;
+
+use rocket::futures::future::BoxFuture;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+use autortr_core::{Authorities, Authority};
+
+// ----------------------------------------------------------------
+
+static PRINCIPAL_RESOLVER: RwLock<Option<PrincipalResolver>> = RwLock::new(None);
+
+// ----------------------------------------------------------------
+
+/// The authenticated user of a request, supplying the roles and permissions checked by
+/// `#[pre_authorize]` expressions.
+///
+/// The principal is resolved by its request guard, see `#[principal]` and `configure_principal`.
+///
+/// @since 0.3.0
+pub trait Principal: Send + Sync {
+    fn roles(&self) -> Vec<String>;
+
+    fn permissions(&self) -> Vec<String>;
+}
+
+/// Resolves the `Principal` of a request, `None` if the request is anonymous.
+///
+/// @since 0.3.0
+pub type PrincipalResolver =
+    for<'r> fn(&'r Request<'_>) -> BoxFuture<'r, Option<Box<dyn Principal>>>;
+
+/// Configures how `#[pre_authorize]` resolves the principal, e.g.
+/// `configure_principal(resolve_principal::<User>)`; done by `#[principal]`.
+///
+/// @since 0.3.0
+pub fn configure_principal(resolver: PrincipalResolver) {
+    *PRINCIPAL_RESOLVER.write().unwrap() = Some(resolver);
+}
+
+/// The configured resolver, if any.
+///
+/// @since 0.3.0
+pub fn principal_resolver() -> Option<PrincipalResolver> {
+    *PRINCIPAL_RESOLVER.read().unwrap()
+}
+
+/// Resolves the principal with its `P` request guard; forwards and errors are anonymous.
+///
+/// @since 0.3.0
+pub fn resolve_principal<'r, P>(
+    request: &'r Request<'_>,
+) -> BoxFuture<'r, Option<Box<dyn Principal>>>
+where
+    P: Principal + for<'a> FromRequest<'a> + 'static,
+{
+    Box::pin(async move {
+        match P::from_request(request).await {
+            Outcome::Success(principal) => Some(Box::new(principal) as Box<dyn Principal>),
+            _ => None,
+        }
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// The parsed `#[pre_authorize]` expression of a handler, generated by the macro.
+///
+/// @since 0.3.0
+pub trait AuthorityRule {
+    fn authority() -> &'static Authority;
+}
+
+/// A request guard failing with a `403` unless the request's principal satisfies
+/// the `A` expression. Anonymous requests have no roles nor permissions.
+///
+/// @since 0.3.0
+pub struct PreAuthorized<A>(PhantomData<A>);
+
+#[rocket::async_trait]
+impl<'r, A: AuthorityRule + Send> FromRequest<'r> for PreAuthorized<A> {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let principal = match principal_resolver() {
+            Some(resolve) => resolve(request).await,
+            None => {
+                rocket::warn!(
+                    "`#[pre_authorize]` without a configured principal, see `#[principal]`"
+                );
+                None
+            }
+        };
+
+        let authorities = PrincipalAuthorities::new(principal.as_deref());
+        match A::authority().evaluate(&authorities) {
+            true => Outcome::Success(PreAuthorized(PhantomData)),
+            false => Outcome::Error((Status::Forbidden, ())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+struct PrincipalAuthorities {
+    authenticated: bool,
+    roles: Vec<String>,
+    permissions: Vec<String>,
+}
+
+impl PrincipalAuthorities {
+    fn new(principal: Option<&dyn Principal>) -> Self {
+        PrincipalAuthorities {
+            authenticated: principal.is_some(),
+            roles: principal
+                .map(|principal| principal.roles())
+                .unwrap_or_default(),
+            permissions: principal
+                .map(|principal| principal.permissions())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Authorities for PrincipalAuthorities {
+    fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|candidate| candidate == role)
+    }
+
+    fn has_permission(&self, permission: &str) -> bool {
+        self.permissions
+            .iter()
+            .any(|candidate| candidate == permission)
+    }
+}
@@ -0,0 +1,139 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/constraint
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use rocket::request::FromParam;
+
+// ----------------------------------------------------------------
+
+/// An anchored regex, compiled on first use and shared afterward.
+///
+/// The mapping macros check the regex at build time, so compiling it cannot fail at runtime.
+///
+/// @since 0.3.0
+pub struct PathPattern {
+    source: &'static str,
+    regex: OnceLock<Regex>,
+}
+
+impl PathPattern {
+    pub const fn new(source: &'static str) -> Self {
+        PathPattern {
+            source,
+            regex: OnceLock::new(),
+        }
+    }
+
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex
+            .get_or_init(|| Regex::new(self.source).expect("checked at build time"))
+            .is_match(value)
+    }
+}
+
+/// A regex constraint on a path variable, generated by the mapping macros for `{id:\d+}`.
+///
+/// @since 0.3.0
+pub trait ParamConstraint {
+    fn pattern() -> &'static PathPattern;
+}
+
+/// A path parameter that parses as `T` only if the raw segment matches `C`'s pattern.
+///
+/// On mismatch, `from_param` fails, and Rocket forwards the request to the next matching
+/// route, by rank.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket_core::{Constrained, ParamConstraint, PathPattern};
+/// use rocket::request::FromParam;
+///
+/// struct Digits;
+///
+/// impl ParamConstraint for Digits {
+///     fn pattern() -> &'static PathPattern {
+///         static PATTERN: PathPattern = PathPattern::new(r"^(?:\d+)$");
+///         &PATTERN
+///     }
+/// }
+///
+/// let id = Constrained::<Digits, u64>::from_param("42").unwrap();
+/// assert_eq!(id.into_inner(), 42);
+///
+/// assert!(Constrained::<Digits, u64>::from_param("4x").is_err());
+/// ```
+///
+/// @since 0.3.0
+pub struct Constrained<C, T> {
+    value: T,
+    constraint: PhantomData<C>,
+}
+
+impl<C, T> Constrained<C, T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<C, T> Deref for Constrained<C, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<C, T: fmt::Debug> fmt::Debug for Constrained<C, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<'a, C, T> FromParam<'a> for Constrained<C, T>
+where
+    C: ParamConstraint,
+    T: FromParam<'a>,
+{
+    type Error = &'a str;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        if !C::pattern().is_match(param) {
+            return Err(param);
+        }
+
+        match T::from_param(param) {
+            Ok(value) => Ok(Constrained {
+                value,
+                constraint: PhantomData,
+            }),
+            Err(_) => Err(param),
+        }
+    }
+}
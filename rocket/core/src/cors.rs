@@ -0,0 +1,118 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/cors
+
+// ----------------------------------------------------------------
+
+use serde::Deserialize;
+
+// ----------------------------------------------------------------
+
+/// A named CORS policy, referenced by the mappings' `cors = ".."` argument and defined in
+/// the Rocket configuration under `cors.<name>`:
+///
+/// ```toml
+/// [default.cors.public]
+/// allowed_origins = ["*"]
+/// max_age = 3600
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket_core::CorsPolicy;
+///
+/// let policy = CorsPolicy {
+///     allowed_origins: vec!["https://partner.example.com".to_string()],
+///     allowed_headers: vec!["Content-Type".to_string()],
+///     ..CorsPolicy::default()
+/// };
+///
+/// assert!(policy.allows_origin("https://partner.example.com"));
+/// assert!(!policy.allows_origin("https://elsewhere.example.com"));
+/// assert!(policy.allows_method("DELETE"));
+/// assert!(policy.allows_header("content-type"));
+/// assert_eq!(policy.allow_origin("https://partner.example.com"), "https://partner.example.com");
+///
+/// let any = CorsPolicy {
+///     allowed_origins: vec!["*".to_string()],
+///     allow_credentials: true,
+///     ..CorsPolicy::default()
+/// };
+/// assert!(any.validate().is_err());
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CorsPolicy {
+    /// The allowed origins, `*` for any.
+    pub allowed_origins: Vec<String>,
+    /// The allowed methods, any method of the route if empty.
+    pub allowed_methods: Vec<String>,
+    /// The allowed request headers, `*` for any.
+    pub allowed_headers: Vec<String>,
+    pub exposed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    /// How long preflight responses may be cached, in seconds.
+    pub max_age: Option<u64>,
+}
+
+impl CorsPolicy {
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+
+    /// Whether every origin is allowed, `*`: responses don't vary by `Origin`.
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|allowed| allowed == "*")
+    }
+
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.allowed_methods.is_empty()
+            || self
+                .allowed_methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method))
+    }
+
+    pub fn allows_header(&self, header: &str) -> bool {
+        self.allowed_headers
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(header))
+    }
+
+    /// The `Access-Control-Allow-Origin` of an allowed `origin`: `*` if any origin is allowed,
+    /// the origin itself otherwise.
+    pub fn allow_origin(&self, origin: &str) -> String {
+        match self.allows_any_origin() {
+            true => "*".to_string(),
+            false => origin.to_string(),
+        }
+    }
+
+    /// Fails on a policy allowing credentials from any origin, which would let every site
+    /// make credentialed requests: list the trusted origins instead.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.allows_any_origin() && self.allow_credentials {
+            true => Err("`allowed_origins = [\"*\"]` can't `allow_credentials`".to_string()),
+            false => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/exception
+
+// ----------------------------------------------------------------
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+
+use rocket::http::Status;
+use rocket::response::{self, Responder};
+use rocket::Request;
+
+use autortr_core::{namespace_contains, Registry};
+
+// ----------------------------------------------------------------
+
+/// Responds with a type-erased error.
+///
+/// @since 0.3.0
+pub type ExceptionFn =
+    for<'r, 'i> fn(Box<dyn Any + Send>, &'r Request<'i>) -> response::Result<'static>;
+
+/// An `#[exception_handler]`: maps the errors of type `type_id` raised by handlers under
+/// `namespace` to a response.
+///
+/// @since 0.3.0
+#[derive(Clone)]
+pub struct ExceptionHandler {
+    pub function: String,
+    /// `/` applies to every handler.
+    pub namespace: String,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub handler: ExceptionFn,
+}
+
+impl ExceptionHandler {
+    /// Whether the handler applies to routes declared under `namespace`.
+    pub fn applies_to(&self, namespace: &str) -> bool {
+        namespace_contains(&self.namespace, namespace)
+    }
+}
+
+// ----------------------------------------------------------------
+
+static EXCEPTION_HANDLERS: Registry<ExceptionHandler> = Registry::new();
+
+// ----------------------------------------------------------------
+
+/// @since 0.3.0
+pub fn register_exception_handler(handler: ExceptionHandler) {
+    EXCEPTION_HANDLERS.register(handler);
+}
+
+/// Returns every exception handler registered so far.
+///
+/// @since 0.3.0
+pub fn snapshot_exception_handlers() -> Vec<ExceptionHandler> {
+    EXCEPTION_HANDLERS.snapshot()
+}
+
+/// The exception handler of the most specific namespace for errors of `type_id`
+/// raised under `namespace`.
+///
+/// @since 0.3.0
+pub fn find_exception_handler(type_id: TypeId, namespace: &str) -> Option<ExceptionHandler> {
+    snapshot_exception_handlers()
+        .into_iter()
+        .filter(|handler| handler.type_id == type_id && handler.applies_to(namespace))
+        .max_by_key(|handler| handler.namespace.trim_end_matches('/').len())
+}
+
+// ----------------------------------------------------------------
+
+/// The error of a handler returning `Result<T, E>`, converted by the matching
+/// `#[exception_handler]` if any.
+///
+/// Otherwise `E` responds itself if it is a `Responder`, or falls back to a `500`.
+///
+/// @since 0.3.0
+pub struct ExceptionResponse {
+    error: Box<dyn Any + Send>,
+    type_id: TypeId,
+    namespace: &'static str,
+    fallback: Option<ExceptionFn>,
+}
+
+impl<'r> Responder<'r, 'static> for ExceptionResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        if let Some(handler) = find_exception_handler(self.type_id, self.namespace) {
+            return (handler.handler)(self.error, request);
+        }
+
+        match self.fallback {
+            Some(fallback) => fallback(self.error, request),
+            None => (
+                Status::InternalServerError,
+                Status::InternalServerError.reason_lossy(),
+            )
+                .respond_to(request),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Wraps a handler's error, so that the generated code picks `RespondingException`
+/// for `Responder` errors and `UnmappedException` for any other.
+///
+/// @since 0.3.0
+pub struct ExceptionProbe<E> {
+    error: RefCell<Option<E>>,
+    namespace: &'static str,
+}
+
+impl<E> ExceptionProbe<E> {
+    pub fn new(error: E, namespace: &'static str) -> Self {
+        ExceptionProbe {
+            error: RefCell::new(Some(error)),
+            namespace,
+        }
+    }
+
+    #[doc(hidden)]
+    fn take(&self) -> E {
+        self.error.borrow_mut().take().expect("taken once")
+    }
+}
+
+/// @since 0.3.0
+pub trait RespondingException {
+    fn to_exception(&self) -> ExceptionResponse;
+}
+
+impl<E> RespondingException for ExceptionProbe<E>
+where
+    E: Any + Send + for<'r> Responder<'r, 'static>,
+{
+    fn to_exception(&self) -> ExceptionResponse {
+        ExceptionResponse {
+            error: Box::new(self.take()),
+            type_id: TypeId::of::<E>(),
+            namespace: self.namespace,
+            fallback: Some(respond_with::<E>),
+        }
+    }
+}
+
+/// @since 0.3.0
+pub trait UnmappedException {
+    fn to_exception(&self) -> ExceptionResponse;
+}
+
+impl<E: Any + Send> UnmappedException for &ExceptionProbe<E> {
+    fn to_exception(&self) -> ExceptionResponse {
+        ExceptionResponse {
+            error: Box::new(self.take()),
+            type_id: TypeId::of::<E>(),
+            namespace: self.namespace,
+            fallback: None,
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Downcasts a type-erased error and responds with it.
+///
+/// @since 0.3.0
+pub fn respond_with<'r, E>(
+    error: Box<dyn Any + Send>,
+    request: &'r Request<'_>,
+) -> response::Result<'static>
+where
+    E: Any + for<'a> Responder<'a, 'static>,
+{
+    match error.downcast::<E>() {
+        Ok(error) => error.respond_to(request),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
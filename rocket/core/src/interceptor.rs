@@ -0,0 +1,155 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/interceptor
+
+// ----------------------------------------------------------------
+
+use std::sync::Arc;
+
+use rocket::http::{ContentType, Status};
+use rocket::{Request, Response};
+
+use autortr_core::{ant_matches, namespace_contains, Registry};
+
+use crate::RouteMapping;
+
+// ----------------------------------------------------------------
+
+/// The response of a pre-handle interceptor short-circuiting the request.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterceptorResponse {
+    pub status: Status,
+    pub content_type: ContentType,
+    pub body: String,
+}
+
+impl InterceptorResponse {
+    pub fn new(status: Status) -> Self {
+        InterceptorResponse {
+            status,
+            content_type: ContentType::Plain,
+            body: status.reason_lossy().to_string(),
+        }
+    }
+
+    pub fn content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    pub fn body<B: Into<String>>(mut self, body: B) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
+/// Whether the request goes on after a pre-handle interceptor.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum PreHandle {
+    Continue,
+    Respond(InterceptorResponse),
+}
+
+/// Logic applied around the handlers of the matching routes, see `#[interceptor]`.
+///
+/// Pre-handles run in ascending `order`, post-handles and after-completions in descending one.
+/// If a pre-handle responds, no handler or post-handle runs, and only the interceptors whose
+/// pre-handle ran complete.
+///
+/// @since 0.3.0
+pub trait Interceptor: Send + Sync {
+    fn pre_handle(&self, _request: &Request<'_>, _mapping: &RouteMapping) -> PreHandle {
+        PreHandle::Continue
+    }
+
+    fn post_handle(
+        &self,
+        _request: &Request<'_>,
+        _mapping: &RouteMapping,
+        _response: &mut Response<'_>,
+    ) {
+    }
+
+    fn after_completion(&self, _request: &Request<'_>, _mapping: &RouteMapping, _status: Status) {}
+}
+
+// ----------------------------------------------------------------
+
+/// An interceptor with the routes it applies to.
+///
+/// @since 0.3.0
+#[derive(Clone)]
+pub struct InterceptorRegistration {
+    pub name: String,
+    pub order: i32,
+    /// Ant patterns of the full route paths, every route if empty.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Restricts to the routes declared under a namespace, `/` for all.
+    pub namespace: String,
+    /// Restricts to the given handlers, if any.
+    pub functions: Vec<String>,
+    /// Restricts to the routes with one of the given tags, if any.
+    pub tags: Vec<String>,
+    pub interceptor: Arc<dyn Interceptor>,
+}
+
+impl InterceptorRegistration {
+    /// Whether the interceptor applies to the route mapping.
+    pub fn matches(&self, mapping: &RouteMapping) -> bool {
+        let full_path = mapping.full_path();
+
+        (self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| ant_matches(pattern, &full_path)))
+            && !self
+                .exclude
+                .iter()
+                .any(|pattern| ant_matches(pattern, &full_path))
+            && namespace_contains(&self.namespace, &mapping.declared_namespace())
+            && (self.functions.is_empty() || self.functions.contains(&mapping.function))
+            && (self.tags.is_empty() || self.tags.iter().any(|tag| mapping.tags.contains(tag)))
+    }
+}
+
+// ----------------------------------------------------------------
+
+static INTERCEPTORS: Registry<InterceptorRegistration> = Registry::new();
+
+// ----------------------------------------------------------------
+
+/// @since 0.3.0
+pub fn register_interceptor(interceptor: InterceptorRegistration) {
+    INTERCEPTORS.register(interceptor);
+}
+
+/// Returns every interceptor registered so far, by ascending `order`.
+///
+/// @since 0.3.0
+pub fn snapshot_interceptors() -> Vec<InterceptorRegistration> {
+    let mut interceptors = INTERCEPTORS.snapshot();
+    interceptors.sort_by_key(|interceptor| interceptor.order);
+
+    interceptors
+}
@@ -18,65 +18,253 @@
 
 // ----------------------------------------------------------------
 
-use std::sync::Mutex;
+#[cfg(feature = "auth")]
+mod auth;
+mod authorize;
+mod constraint;
+mod cors;
+mod exception;
+mod interceptor;
+mod rate_limit;
+#[cfg(feature = "redis")]
+mod rate_limit_redis;
+mod rest;
+#[cfg(feature = "validate")]
+mod validate;
+mod webhook;
 
-use lazy_static::lazy_static;
-use rocket::Route;
-
-pub const GET: &str = "get";
-pub const POST: &str = "post";
-pub const PUT: &str = "put";
-pub const PATCH: &str = "patch";
-pub const DELETE: &str = "delete";
-pub const HEAD: &str = "head";
+#[cfg(feature = "auth")]
+pub use auth::*;
+pub use authorize::*;
+pub use autortr_core::*;
+pub use constraint::*;
+pub use cors::*;
+pub use exception::*;
+pub use interceptor::*;
+pub use rate_limit::*;
+#[cfg(feature = "redis")]
+pub use rate_limit_redis::*;
+pub use rest::*;
+#[cfg(feature = "validate")]
+pub use validate::*;
+pub use webhook::*;
 
 // ----------------------------------------------------------------
 
-pub const NAMESPACE: &str = "namespace";
-pub const METHOD: &str = "method";
-pub const PATH: &str = "path";
-pub const DATA: &str = "data";
-
-// ----------------------------------------------------------------
+use std::collections::BTreeMap;
 
-pub const ROOT: &str = "/";
-pub const EMPTY: &str = "_";
+use rocket::{Build, Catcher, Rocket, Route};
 
 // ----------------------------------------------------------------
 
 #[derive(Clone)]
 pub struct RouteMapping {
     pub function: String,
+    /// The static mount base of the declared namespace.
     pub namespace: String,
+    /// The dynamic rest of the declared namespace, e.g. `/<tenant>`; empty if none.
+    pub dynamic_namespace: String,
     pub method: String,
+    /// The route path, with the `dynamic_namespace` folded in.
     pub path: String,
+    /// The path as written on the mapping, e.g. a Spring-style `/users/{id}`.
+    pub pattern: String,
+    /// The types of the handler's path and query parameters, by name, e.g. `("id", "u64")`.
+    pub parameters: Vec<(String, String)>,
     pub data: String,
+    /// Whether the handler is marked `#[deprecated]`.
+    pub deprecated: bool,
+    /// The `tags` of the mapping, e.g. to select interceptors.
+    pub tags: Vec<String>,
+    /// The request guard types of the mapping and its controller, e.g. `AdminOnly`.
+    pub guards: Vec<String>,
+    /// The `#[pre_authorize]` expression, if any.
+    pub pre_authorize: Option<String>,
+    /// The roles and permissions of the `pre_authorize` expression, see `Authority::authorities`.
+    pub authorities: Vec<String>,
+    /// The name of the mapping's CORS policy, see `CorsPolicy`.
+    pub cors: Option<String>,
+    /// The `rate_limit` of the mapping, as declared, e.g. `100/minute`.
+    pub rate_limit: Option<String>,
+    /// The key of the `rate_limit`: `ip`, `api-key` or a `RateLimitKey` type.
+    pub rate_limit_key: Option<String>,
     pub routes: Vec<Route>,
 }
 
+impl RouteMapping {
+    /// The `namespace` joined with the `path`, as mounted by the router.
+    ///
+    /// @since 0.3.0
+    pub fn full_path(&self) -> String {
+        join_path(&self.namespace, &self.path)
+    }
+
+    /// The namespace as declared, e.g. `/tenants/<tenant>`.
+    ///
+    /// @since 0.3.0
+    pub fn declared_namespace(&self) -> String {
+        join_path(&self.namespace, &self.dynamic_namespace)
+    }
+
+    /// The framework-neutral descriptors of the mapped `Route`s, with their parameter types,
+    /// and the `guards`, `pre_authorize`, `authorities`, `cors` policy and `rate_limit` as metadata.
+    ///
+    /// @since 0.3.0
+    pub fn descriptors(&self) -> Vec<RouteDescriptor> {
+        self.routes
+            .iter()
+            .map(|route| RouteDescriptor {
+                handler: self.function.clone(),
+                method: route.method.as_str().to_lowercase(),
+                namespace: self.namespace.clone(),
+                path: self.path.clone(),
+                parameters: self.parameters.iter().cloned().collect(),
+                body: match self.data.as_str() {
+                    EMPTY => None,
+                    data => Some(data.to_string()),
+                },
+                rank: Some(route.rank),
+                format: route.format.as_ref().map(|format| format.to_string()),
+                deprecated: self.deprecated,
+                metadata: self.metadata(),
+            })
+            .collect()
+    }
+
+    #[doc(hidden)]
+    fn metadata(&self) -> BTreeMap<String, String> {
+        let mut metadata = BTreeMap::new();
+        if !self.guards.is_empty() {
+            metadata.insert(GUARDS.to_string(), self.guards.join(", "));
+        }
+        if let Some(pre_authorize) = &self.pre_authorize {
+            metadata.insert(PRE_AUTHORIZE.to_string(), pre_authorize.clone());
+            metadata.insert(AUTHORITIES.to_string(), self.authorities.join(", "));
+        }
+        if let Some(cors) = &self.cors {
+            metadata.insert(CORS.to_string(), cors.clone());
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            metadata.insert(RATE_LIMIT.to_string(), rate_limit.clone());
+        }
+        if let Some(rate_limit_key) = &self.rate_limit_key {
+            metadata.insert(RATE_LIMIT_KEY.to_string(), rate_limit_key.clone());
+        }
+
+        metadata
+    }
+}
+
 // ----------------------------------------------------------------
 
-lazy_static! {
-    static ref ROUTE_MAPPINGS: Mutex<Vec<RouteMapping>> = Mutex::new(Vec::new());
+/// The Rocket `Backend`: mounts the `Route`s of each namespace, and registers the catchers
+/// at their own base.
+///
+/// @since 0.3.0
+pub struct RocketBackend;
+
+impl Backend for RocketBackend {
+    const NAME: &'static str = "Rocket";
+
+    type App = Rocket<Build>;
+    type Mapping = RouteMapping;
+    type Catcher = Catcher;
+    type Output = Rocket<Build>;
+
+    fn descriptor(mapping: &RouteMapping) -> RouteDescriptor {
+        RouteDescriptor::new(
+            &mapping.function,
+            &mapping.method,
+            &mapping.namespace,
+            &mapping.path,
+        )
+    }
+
+    fn catcher_namespace(catcher: &Catcher) -> String {
+        catcher.base.to_string()
+    }
+
+    fn app() -> Rocket<Build> {
+        rocket::build()
+    }
+
+    fn mount(app: Rocket<Build>, namespace: &str, mappings: Vec<RouteMapping>) -> Rocket<Build> {
+        let routes: Vec<Route> = mappings
+            .into_iter()
+            .flat_map(|mapping| mapping.routes)
+            .collect();
+
+        app.mount(namespace, routes)
+    }
+
+    fn mount_catchers(
+        app: Rocket<Build>,
+        _namespace: &str,
+        catchers: Vec<Catcher>,
+    ) -> Rocket<Build> {
+        // The catchers already carry their base.
+        app.register(ROOT, catchers)
+    }
+
+    fn finish(app: Rocket<Build>) -> Rocket<Build> {
+        app
+    }
 }
 
 // ----------------------------------------------------------------
 
+static ROUTE_MAPPINGS: Registry<RouteMapping> = Registry::new();
+
+// ----------------------------------------------------------------
+
 pub fn register_route_mapping(mapping: RouteMapping) {
-    let mut mappings = ROUTE_MAPPINGS.lock().unwrap();
-    mappings.push(mapping);
+    ROUTE_MAPPINGS.register(mapping);
 }
 
 // ----------------------------------------------------------------
 
 pub fn try_acquire_route_mappings() -> Vec<RouteMapping> {
-    let mappings = ROUTE_MAPPINGS.lock().unwrap();
-    mappings.clone()
+    ROUTE_MAPPINGS.acquire()
 }
 
 // ----------------------------------------------------------------
 
 pub fn clean_route_mappings() {
-    let mut mappings = ROUTE_MAPPINGS.lock().unwrap();
-    mappings.clear();
+    ROUTE_MAPPINGS.clean();
+}
+
+// ----------------------------------------------------------------
+
+/// Returns every route mapping registered so far.
+///
+/// Unlike `try_acquire_route_mappings`, the snapshot is not affected by `clean_route_mappings`,
+/// so it can be used to build any number of isolated `Rocket` instances, e.g. in tests.
+///
+/// @since 0.3.0
+pub fn snapshot_route_mappings() -> Vec<RouteMapping> {
+    ROUTE_MAPPINGS.snapshot()
+}
+
+/// Returns the sorted `RouteTable` of the given route mappings.
+///
+/// @since 0.3.0
+pub fn route_table(mappings: &[RouteMapping]) -> RouteTable {
+    RouteTable::from_descriptors(&route_descriptors(mappings))
+}
+
+/// Returns the sorted `RouteTable` of every registered route mapping.
+///
+/// @since 0.3.0
+pub fn snapshot_route_table() -> RouteTable {
+    route_table(&snapshot_route_mappings())
+}
+
+/// Returns the framework-neutral descriptors of the given route mappings.
+///
+/// @since 0.3.0
+pub fn route_descriptors(mappings: &[RouteMapping]) -> Vec<RouteDescriptor> {
+    mappings
+        .iter()
+        .flat_map(RouteMapping::descriptors)
+        .collect()
 }
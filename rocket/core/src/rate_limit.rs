@@ -0,0 +1,393 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/rate_limit
+
+// ----------------------------------------------------------------
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use rocket::futures::future::BoxFuture;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+use autortr_core::RateLimit;
+
+// ----------------------------------------------------------------
+
+/// The default number of keys a `MemoryRateLimitStore` holds.
+///
+/// @since 0.3.0
+pub const RATE_LIMIT_CAPACITY: usize = 10_000;
+
+/// How often a `MemoryRateLimitStore` drops its refilled buckets.
+#[doc(hidden)]
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+static RATE_LIMIT_STORE: RwLock<Option<Arc<dyn RateLimitStore>>> = RwLock::new(None);
+
+// ----------------------------------------------------------------
+
+/// The outcome of a rate-limited request, reported in the `RateLimit-*` headers.
+///
+/// @since 0.3.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub limit: RateLimit,
+    pub allowed: bool,
+    /// The requests left, `RateLimit-Remaining`.
+    pub remaining: u64,
+    /// The seconds until the limit is fully available again, `RateLimit-Reset`.
+    pub reset: u64,
+    /// The seconds until the next request is allowed, `Retry-After`; `0` if allowed.
+    pub retry_after: u64,
+}
+
+/// A failure of a `RateLimitStore`, e.g. an unreachable Redis server.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitError(pub String);
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limit store failure: {}", self.0)
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+/// Counts the requests of the rate-limited mappings, per key.
+///
+/// @since 0.3.0
+pub trait RateLimitStore: Send + Sync {
+    /// Takes one request off the `limit` of the `key`.
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        limit: RateLimit,
+    ) -> BoxFuture<'a, Result<RateLimitDecision, RateLimitError>>;
+}
+
+/// Configures the store of `rate_limit` mappings; a `MemoryRateLimitStore` by default.
+///
+/// @since 0.3.0
+pub fn configure_rate_limit_store<S: RateLimitStore + 'static>(store: S) {
+    *RATE_LIMIT_STORE.write().unwrap() = Some(Arc::new(store));
+}
+
+/// The configured store.
+///
+/// @since 0.3.0
+pub fn rate_limit_store() -> Arc<dyn RateLimitStore> {
+    if let Some(store) = RATE_LIMIT_STORE.read().unwrap().as_ref() {
+        return store.clone();
+    }
+
+    RATE_LIMIT_STORE
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| Arc::new(MemoryRateLimitStore::new()))
+        .clone()
+}
+
+// ----------------------------------------------------------------
+
+/// An in-memory token bucket per key: the bucket holds up to `requests` tokens and refills
+/// at `requests` per `period`, so bursts are allowed up to the limit.
+///
+/// The store holds at most `capacity` keys, `RATE_LIMIT_CAPACITY` by default: once full, the
+/// oldest key is forgotten, and its next request starts over with a full bucket. Refilled
+/// buckets, which count as much as no bucket, are dropped every minute.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, Instant};
+///
+/// use autortr_rocket_core::{MemoryRateLimitStore, RateLimit};
+///
+/// let store = MemoryRateLimitStore::new();
+/// let limit = RateLimit::parse("2/minute").unwrap();
+/// let now = Instant::now();
+///
+/// assert!(store.take("client", limit, now).allowed);
+/// assert_eq!(store.take("client", limit, now).remaining, 0);
+///
+/// let decision = store.take("client", limit, now);
+/// assert!(!decision.allowed);
+/// assert_eq!(decision.retry_after, 30);
+///
+/// assert!(store.take("client", limit, now + Duration::from_secs(30)).allowed);
+/// assert!(store.take("another", limit, now).allowed);
+/// ```
+///
+/// Bounded by its capacity:
+///
+/// ```rust
+/// use std::time::{Duration, Instant};
+///
+/// use autortr_rocket_core::{MemoryRateLimitStore, RateLimit};
+///
+/// let store = MemoryRateLimitStore::with_capacity(2);
+/// let limit = RateLimit::parse("1/minute").unwrap();
+/// let now = Instant::now();
+///
+/// assert!(store.take("first", limit, now).allowed);
+/// assert!(store.take("second", limit, now).allowed);
+/// assert!(store.take("third", limit, now).allowed);
+/// assert_eq!(store.len(), 2);
+///
+/// // `first` was forgotten, `second` wasn't
+/// assert!(!store.take("second", limit, now).allowed);
+/// assert!(store.take("first", limit, now).allowed);
+///
+/// // a minute later, every bucket is refilled and swept
+/// assert!(store.take("fourth", limit, now + Duration::from_secs(60)).allowed);
+/// assert_eq!(store.len(), 1);
+/// ```
+///
+/// @since 0.3.0
+pub struct MemoryRateLimitStore {
+    capacity: usize,
+    buckets: Mutex<Buckets>,
+}
+
+impl Default for MemoryRateLimitStore {
+    fn default() -> Self {
+        MemoryRateLimitStore::with_capacity(RATE_LIMIT_CAPACITY)
+    }
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding at most `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        MemoryRateLimitStore {
+            capacity: capacity.max(1),
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    /// The number of keys held.
+    pub fn len(&self) -> usize {
+        self.buckets.lock().unwrap().buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes one token off the bucket of the `key`, as of `now`.
+    pub fn take(&self, key: &str, limit: RateLimit, now: Instant) -> RateLimitDecision {
+        let capacity = limit.requests as f64;
+        let rate = capacity / limit.period as f64;
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets {
+            buckets,
+            order,
+            swept,
+        } = &mut *buckets;
+
+        // Full buckets are dropped: a key without one starts over with a full bucket.
+        let sweep = match *swept {
+            Some(swept) => now >= swept + SWEEP_INTERVAL,
+            None => true,
+        };
+        if sweep {
+            buckets.retain(|_, bucket| bucket.level(now) < bucket.capacity);
+            order.retain(|key| buckets.contains_key(key));
+            *swept = Some(now);
+        }
+
+        if !buckets.contains_key(key) {
+            while buckets.len() >= self.capacity {
+                match order.pop_front() {
+                    Some(oldest) => buckets.remove(&oldest),
+                    None => break,
+                };
+            }
+            order.push_back(key.to_string());
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            capacity,
+            rate,
+            updated: now,
+        });
+        bucket.capacity = capacity;
+        bucket.rate = rate;
+        bucket.tokens = bucket.level(now);
+        bucket.updated = bucket.updated.max(now);
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        RateLimitDecision {
+            limit,
+            allowed,
+            remaining: bucket.tokens.floor() as u64,
+            reset: ((capacity - bucket.tokens) / rate).ceil() as u64,
+            retry_after: match allowed {
+                true => 0,
+                false => ((1.0 - bucket.tokens) / rate).ceil() as u64,
+            },
+        }
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        limit: RateLimit,
+    ) -> BoxFuture<'a, Result<RateLimitDecision, RateLimitError>> {
+        Box::pin(async move { Ok(self.take(key, limit, Instant::now())) })
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Extracts the key a request is counted under, e.g. its client IP; `None` falls back to
+/// the client IP.
+///
+/// @since 0.3.0
+pub trait RateLimitKey {
+    fn key(request: &Request<'_>) -> Option<String>;
+}
+
+/// `rate_limit_key = "ip"`, the default: the client IP.
+///
+/// @since 0.3.0
+pub struct RateLimitByIp;
+
+impl RateLimitKey for RateLimitByIp {
+    fn key(request: &Request<'_>) -> Option<String> {
+        request.client_ip().map(|ip| ip.to_string())
+    }
+}
+
+/// `rate_limit_key = "api-key"`: the `X-Api-Key` header.
+///
+/// The header is taken as is: unless a guard of the mapping (e.g. in `guards`, which run ahead
+/// of the rate limit) validates the key, every made-up key gets a bucket of its own.
+///
+/// @since 0.3.0
+pub struct RateLimitByApiKey;
+
+impl RateLimitKey for RateLimitByApiKey {
+    fn key(request: &Request<'_>) -> Option<String> {
+        request
+            .headers()
+            .get_one("X-Api-Key")
+            .map(|key| format!("api-key:{}", key))
+    }
+}
+
+/// The `rate_limit` of a handler, generated by the macro.
+///
+/// @since 0.3.0
+pub trait RateLimitSpec {
+    /// The handler, scoping its counters.
+    fn name() -> &'static str;
+
+    fn limit() -> RateLimit;
+
+    fn key(request: &Request<'_>) -> Option<String>;
+}
+
+/// A request guard failing with a `429` once the request's key exceeds the `S` limit.
+///
+/// The decision is recorded for the router's fairing, which adds the `RateLimit-*` and
+/// `Retry-After` headers. Store failures are logged and let the request through.
+///
+/// @since 0.3.0
+pub struct RateLimited<S>(PhantomData<S>);
+
+#[rocket::async_trait]
+impl<'r, S: RateLimitSpec + Send> FromRequest<'r> for RateLimited<S> {
+    type Error = RateLimitDecision;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let key = S::key(request)
+            .or_else(|| RateLimitByIp::key(request))
+            .unwrap_or_else(|| "anonymous".to_string());
+        let key = format!("{}:{}", S::name(), key);
+
+        let decision = match rate_limit_store().acquire(&key, S::limit()).await {
+            Ok(decision) => decision,
+            Err(error) => {
+                rocket::warn!("`{}` is not rate limited: {}", S::name(), error);
+                return Outcome::Success(RateLimited(PhantomData));
+            }
+        };
+
+        request.local_cache(|| RecordedRateLimit(Some(decision)));
+        match decision.allowed {
+            true => Outcome::Success(RateLimited(PhantomData)),
+            false => Outcome::Error((Status::TooManyRequests, decision)),
+        }
+    }
+}
+
+/// The decision recorded by the `RateLimited` guard of the request, if any.
+///
+/// @since 0.3.0
+pub fn recorded_rate_limit(request: &Request<'_>) -> Option<RateLimitDecision> {
+    request.local_cache(|| RecordedRateLimit(None)).0
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    /// Tokens per second.
+    rate: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn level(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * self.rate).min(self.capacity)
+    }
+}
+
+/// The buckets of a `MemoryRateLimitStore`, with their keys from the oldest.
+#[doc(hidden)]
+#[derive(Default)]
+struct Buckets {
+    buckets: HashMap<String, Bucket>,
+    order: VecDeque<String>,
+    swept: Option<Instant>,
+}
+
+#[doc(hidden)]
+struct RecordedRateLimit(Option<RateLimitDecision>);
@@ -0,0 +1,156 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/rate_limit_redis
+
+// ----------------------------------------------------------------
+
+use rocket::futures::future::BoxFuture;
+use rocket::tokio::sync::Mutex;
+
+use redis::aio::MultiplexedConnection;
+use redis::{Client, RedisError};
+
+use autortr_core::RateLimit;
+
+use crate::{RateLimitDecision, RateLimitError, RateLimitStore};
+
+// ----------------------------------------------------------------
+
+/// The default prefix of the Redis keys.
+///
+/// @since 0.3.0
+pub const REDIS_RATE_LIMIT_PREFIX: &str = "autortr:rate_limit:";
+
+// ----------------------------------------------------------------
+
+/// A fixed-window counter per key in Redis, or any server speaking its protocol, shared by
+/// every instance of the application.
+///
+/// Each window is a key set with `SET .. PX <period> NX`, counted with `INCR` and timed with
+/// `PTTL`, in one `MULTI`/`EXEC` transaction.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use autortr_rocket_core::{configure_rate_limit_store, RedisRateLimitStore};
+///
+/// let store = RedisRateLimitStore::open("redis://127.0.0.1:6379/").unwrap();
+/// configure_rate_limit_store(store.prefix("orders:"));
+/// ```
+///
+/// @since 0.3.0
+pub struct RedisRateLimitStore {
+    client: Client,
+    prefix: String,
+    connection: Mutex<Option<MultiplexedConnection>>,
+}
+
+impl RedisRateLimitStore {
+    /// A store for the `redis://` URL; connects on the first request.
+    pub fn open(url: &str) -> Result<Self, RateLimitError> {
+        let client = Client::open(url).map_err(redis_error)?;
+
+        Ok(RedisRateLimitStore {
+            client,
+            prefix: REDIS_RATE_LIMIT_PREFIX.to_string(),
+            connection: Mutex::new(None),
+        })
+    }
+
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    #[doc(hidden)]
+    async fn connection(&self) -> Result<MultiplexedConnection, RateLimitError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+
+        let opened = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(redis_error)?;
+
+        Ok(connection.insert(opened).clone())
+    }
+}
+
+impl RateLimitStore for RedisRateLimitStore {
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        limit: RateLimit,
+    ) -> BoxFuture<'a, Result<RateLimitDecision, RateLimitError>> {
+        Box::pin(async move {
+            let key = format!("{}{}", self.prefix, key);
+            let window = match limit.period.checked_mul(1000) {
+                Some(window) => window,
+                None => return Err(RateLimitError(format!("window too long: {}", limit))),
+            };
+            let mut connection = self.connection().await?;
+
+            let counted: Result<(u64, i64), RedisError> = redis::pipe()
+                .atomic()
+                .cmd("SET")
+                .arg(&key)
+                .arg(0)
+                .arg("PX")
+                .arg(window)
+                .arg("NX")
+                .ignore()
+                .cmd("INCR")
+                .arg(&key)
+                .cmd("PTTL")
+                .arg(&key)
+                .query_async(&mut connection)
+                .await;
+            let (count, ttl) = match counted {
+                Ok(counted) => counted,
+                Err(error) => {
+                    // Reconnects on the next request.
+                    *self.connection.lock().await = None;
+                    return Err(redis_error(error));
+                }
+            };
+
+            let reset = match ttl {
+                ttl if ttl > 0 => (ttl as u64).div_ceil(1000),
+                _ => limit.period,
+            };
+            let allowed = count <= limit.requests;
+
+            Ok(RateLimitDecision {
+                limit,
+                allowed,
+                remaining: limit.requests.saturating_sub(count),
+                reset,
+                retry_after: if allowed { 0 } else { reset },
+            })
+        })
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn redis_error(error: RedisError) -> RateLimitError {
+    RateLimitError(error.to_string())
+}
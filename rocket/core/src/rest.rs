@@ -0,0 +1,271 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/rest
+
+// ----------------------------------------------------------------
+
+use std::io::Cursor;
+use std::sync::RwLock;
+
+use rocket::http::{ContentType, Status};
+use rocket::response::{self, Responder};
+use rocket::{Request, Response};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// ----------------------------------------------------------------
+
+static REST_ENVELOPE: RwLock<Option<RestEnvelope>> = RwLock::new(None);
+
+// ----------------------------------------------------------------
+
+/// The JSON envelope of `rest` handlers: `{"code": .., "message": .., "data": ..}`.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestEnvelope {
+    pub code_field: String,
+    pub message_field: String,
+    pub data_field: String,
+    /// The `code` of successful responses.
+    pub success_code: i64,
+    /// The `message` of successful responses.
+    pub success_message: String,
+}
+
+impl Default for RestEnvelope {
+    fn default() -> Self {
+        RestEnvelope {
+            code_field: "code".to_string(),
+            message_field: "message".to_string(),
+            data_field: "data".to_string(),
+            success_code: 200,
+            success_message: "OK".to_string(),
+        }
+    }
+}
+
+impl RestEnvelope {
+    /// Wraps `data` into the envelope.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use autortr_rocket_core::RestEnvelope;
+    ///
+    /// let envelope = RestEnvelope {
+    ///     success_code: 0,
+    ///     data_field: "result".to_string(),
+    ///     ..RestEnvelope::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     envelope.wrap(0, "OK", serde_json::json!(42)).to_string(),
+    ///     r#"{"code":0,"message":"OK","result":42}"#
+    /// );
+    /// ```
+    pub fn wrap(&self, code: i64, message: &str, data: Value) -> Value {
+        let mut envelope = Map::new();
+        envelope.insert(self.code_field.clone(), Value::from(code));
+        envelope.insert(self.message_field.clone(), Value::from(message));
+        envelope.insert(self.data_field.clone(), data);
+
+        Value::Object(envelope)
+    }
+}
+
+/// Replaces the envelope of all `rest` handlers.
+///
+/// @since 0.3.0
+pub fn configure_rest_envelope(envelope: RestEnvelope) {
+    *REST_ENVELOPE.write().unwrap() = Some(envelope);
+}
+
+/// The configured envelope, `RestEnvelope::default()` if none.
+///
+/// @since 0.3.0
+pub fn rest_envelope() -> RestEnvelope {
+    REST_ENVELOPE.read().unwrap().clone().unwrap_or_default()
+}
+
+// ----------------------------------------------------------------
+
+/// The status and envelope of a failed `rest` handler.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestError {
+    pub status: Status,
+    /// The envelope `code`, the status code unless set.
+    pub code: i64,
+    pub message: String,
+    pub data: Value,
+}
+
+impl RestError {
+    pub fn new<M: Into<String>>(status: Status, message: M) -> Self {
+        RestError {
+            status,
+            code: status.code as i64,
+            message: message.into(),
+            data: Value::Null,
+        }
+    }
+
+    pub fn code(mut self, code: i64) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+/// Converts the `E` of a `rest` handler's `Result<T, E>` into a status and an error body.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Status;
+/// use autortr_rocket_core::{IntoRestError, RestError};
+///
+/// enum AccountError {
+///     NotFound(u64),
+/// }
+///
+/// impl IntoRestError for AccountError {
+///     fn into_rest_error(self) -> RestError {
+///         match self {
+///             AccountError::NotFound(id) => {
+///                 RestError::new(Status::NotFound, format!("account {} not found", id))
+///             }
+///         }
+///     }
+/// }
+///
+/// let error = AccountError::NotFound(7).into_rest_error();
+///
+/// assert_eq!(error.status, Status::NotFound);
+/// assert_eq!(error.code, 404);
+/// ```
+///
+/// @since 0.3.0
+pub trait IntoRestError {
+    fn into_rest_error(self) -> RestError;
+}
+
+impl IntoRestError for RestError {
+    fn into_rest_error(self) -> RestError {
+        self
+    }
+}
+
+impl IntoRestError for Status {
+    fn into_rest_error(self) -> RestError {
+        RestError::new(self, self.reason_lossy())
+    }
+}
+
+impl IntoRestError for String {
+    fn into_rest_error(self) -> RestError {
+        RestError::new(Status::InternalServerError, self)
+    }
+}
+
+impl IntoRestError for &str {
+    fn into_rest_error(self) -> RestError {
+        RestError::new(Status::InternalServerError, self)
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The enveloped JSON response of a `rest` handler.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestResponse {
+    pub status: Status,
+    pub body: Value,
+}
+
+impl RestResponse {
+    /// `200 OK` with `value` as the envelope `data`.
+    ///
+    /// If `value` can't be serialized, the serde error is logged and the client gets a
+    /// `500 Internal Server Error` envelope without its detail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use rocket::http::Status;
+    /// use autortr_rocket_core::RestResponse;
+    ///
+    /// // JSON object keys must be strings
+    /// let value = HashMap::from([((1, 2), "pair")]);
+    /// let response = RestResponse::ok(value);
+    ///
+    /// assert_eq!(response.status, Status::InternalServerError);
+    /// assert_eq!(response.body["message"], "Internal Server Error");
+    /// ```
+    pub fn ok<T: Serialize>(value: T) -> Self {
+        let envelope = rest_envelope();
+
+        match serde_json::to_value(value) {
+            Ok(data) => RestResponse {
+                status: Status::Ok,
+                body: envelope.wrap(envelope.success_code, &envelope.success_message, data),
+            },
+            Err(error) => {
+                rocket::error!("Failed to serialize a `rest` response: {}", error);
+                RestResponse::error(Status::InternalServerError)
+            }
+        }
+    }
+
+    pub fn error<E: IntoRestError>(error: E) -> Self {
+        let error = error.into_rest_error();
+
+        RestResponse {
+            status: error.status,
+            body: rest_envelope().wrap(error.code, &error.message, error.data),
+        }
+    }
+
+    pub fn from_result<T: Serialize, E: IntoRestError>(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => RestResponse::ok(value),
+            Err(error) => RestResponse::error(error),
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for RestResponse {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let body = self.body.to_string();
+
+        Response::build()
+            .status(self.status)
+            .header(ContentType::JSON)
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
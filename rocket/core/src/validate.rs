@@ -0,0 +1,231 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/validate
+
+// ----------------------------------------------------------------
+
+use std::io::Cursor;
+use std::ops::Deref;
+use std::sync::RwLock;
+
+use rocket::data::{Data, FromData, Outcome};
+use rocket::http::{ContentType, Status};
+use rocket::response::{self, Responder};
+use rocket::{Request, Response};
+use serde_json::{Map, Value};
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+// ----------------------------------------------------------------
+
+/// Formats the body of a failed validation.
+///
+/// @since 0.3.0
+pub type ValidationFormat = fn(&ValidationErrors) -> Value;
+
+static VALIDATION_FORMAT: RwLock<Option<ValidationFormat>> = RwLock::new(None);
+
+// ----------------------------------------------------------------
+
+/// Replaces the body format of every `422` validation failure.
+///
+/// # Example
+///
+/// ```rust
+/// use serde_json::{json, Value};
+/// use validator::ValidationErrors;
+/// use autortr_rocket_core::{configure_validation_format, validation_format};
+///
+/// fn fields(errors: &ValidationErrors) -> Value {
+///     let mut fields: Vec<String> = errors.field_errors().keys().map(|f| f.to_string()).collect();
+///     fields.sort();
+///
+///     json!({ "code": 42200, "fields": fields })
+/// }
+///
+/// configure_validation_format(fields);
+///
+/// assert_eq!(validation_format()(&ValidationErrors::new())["code"], 42200);
+/// ```
+///
+/// @since 0.3.0
+pub fn configure_validation_format(format: ValidationFormat) {
+    *VALIDATION_FORMAT.write().unwrap() = Some(format);
+}
+
+/// The configured format, `default_validation_format` if none.
+///
+/// @since 0.3.0
+pub fn validation_format() -> ValidationFormat {
+    VALIDATION_FORMAT
+        .read()
+        .unwrap()
+        .unwrap_or(default_validation_format)
+}
+
+/// `{"status": 422, "title": "Unprocessable Entity", "errors": [{"field", "code", "message"}]}`,
+/// nested fields are dotted and list items indexed, e.g. `address.zip` or `items[0].name`.
+///
+/// # Example
+///
+/// ```rust
+/// use validator::{ValidationError, ValidationErrors};
+/// use autortr_rocket_core::default_validation_format;
+///
+/// let mut errors = ValidationErrors::new();
+/// errors.add("account", ValidationError::new("length"));
+///
+/// assert_eq!(
+///     default_validation_format(&errors).to_string(),
+///     r#"{"errors":[{"code":"length","field":"account","message":null}],"status":422,"title":"Unprocessable Entity"}"#
+/// );
+/// ```
+///
+/// @since 0.3.0
+pub fn default_validation_format(errors: &ValidationErrors) -> Value {
+    let mut fields = Vec::new();
+    flatten("", errors, &mut fields);
+    fields.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let errors: Vec<Value> = fields
+        .into_iter()
+        .map(|(field, code, message)| {
+            let mut error = Map::new();
+            error.insert("field".to_string(), Value::from(field));
+            error.insert("code".to_string(), Value::from(code));
+            error.insert(
+                "message".to_string(),
+                message.map(Value::from).unwrap_or(Value::Null),
+            );
+
+            Value::Object(error)
+        })
+        .collect();
+
+    let mut body = Map::new();
+    body.insert(
+        "status".to_string(),
+        Value::from(Status::UnprocessableEntity.code),
+    );
+    body.insert(
+        "title".to_string(),
+        Value::from(Status::UnprocessableEntity.reason_lossy()),
+    );
+    body.insert("errors".to_string(), Value::from(errors));
+
+    Value::Object(body)
+}
+
+// ----------------------------------------------------------------
+
+/// A data guard validating the body of `D` (e.g. `Form<T>` or `Json<T>`) with `Validate`.
+///
+/// Invalid bodies don't fail the guard, so that the generated handler can answer with
+/// a `422` in the configured format.
+///
+/// @since 0.3.0
+pub struct Validated<D> {
+    result: Result<D, ValidationErrors>,
+}
+
+impl<D> Validated<D> {
+    pub fn into_result(self) -> Result<D, ValidationErrors> {
+        self.result
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, D> FromData<'r> for Validated<D>
+where
+    D: FromData<'r> + Deref,
+    D::Target: Validate,
+{
+    type Error = D::Error;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        match D::from_data(request, data).await {
+            Outcome::Success(body) => Outcome::Success(Validated {
+                result: match body.validate() {
+                    Ok(()) => Ok(body),
+                    Err(errors) => Err(errors),
+                },
+            }),
+            Outcome::Error(error) => Outcome::Error(error),
+            Outcome::Forward(forward) => Outcome::Forward(forward),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The response of a handler with validated bodies.
+///
+/// @since 0.3.0
+pub enum Validation<R> {
+    Valid(R),
+    Invalid(ValidationErrors),
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Validation<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self {
+            Validation::Valid(responder) => responder.respond_to(request),
+            Validation::Invalid(errors) => {
+                let body = validation_format()(&errors).to_string();
+
+                Response::build()
+                    .status(Status::UnprocessableEntity)
+                    .header(ContentType::JSON)
+                    .sized_body(body.len(), Cursor::new(body))
+                    .ok()
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn flatten(
+    prefix: &str,
+    errors: &ValidationErrors,
+    fields: &mut Vec<(String, String, Option<String>)>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = match prefix.is_empty() {
+            true => field.to_string(),
+            false => format!("{}.{}", prefix, field),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    fields.push((
+                        path.clone(),
+                        error.code.to_string(),
+                        error.message.as_ref().map(|message| message.to_string()),
+                    ));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => flatten(&path, errors, fields),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    flatten(&format!("{}[{}]", path, index), errors, fields);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,408 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/core/webhook
+
+// ----------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rocket::data::{self, FromData, ToByteUnit};
+use rocket::http::Status;
+use rocket::{Data, Request};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+// ----------------------------------------------------------------
+
+/// How old a signed timestamp may be, in seconds; deliveries are remembered twice as long
+/// to reject replays.
+///
+/// @since 0.3.0
+pub const WEBHOOK_TOLERANCE: u64 = 300;
+
+static DELIVERIES: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+// ----------------------------------------------------------------
+
+/// The signature scheme of a `#[webhook_mapping]`.
+///
+/// @since 0.3.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebhookScheme {
+    /// `github-sha256`: `X-Hub-Signature-256: sha256=<hex>` over the body, with the required
+    /// `X-GitHub-Delivery` header; replayed delivery ids and signatures are rejected.
+    ///
+    /// GitHub signs no timestamp: a delivery is only remembered for `2 * WEBHOOK_TOLERANCE`,
+    /// after which the same delivery is accepted again. Handlers needing a stronger guarantee
+    /// deduplicate the `delivery` ids themselves.
+    GithubSha256,
+    /// `stripe`: `Stripe-Signature: t=<timestamp>,v1=<hex>` over `<timestamp>.<body>`.
+    Stripe,
+    /// `hmac-sha256`: `X-Webhook-Signature: <hex>` over `<timestamp>.<body>`, with the
+    /// `X-Webhook-Timestamp` header.
+    HmacSha256,
+    /// `api-key`: the `X-Api-Key` header is the secret itself.
+    ApiKey,
+}
+
+impl WebhookScheme {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "github-sha256" => Some(WebhookScheme::GithubSha256),
+            "stripe" => Some(WebhookScheme::Stripe),
+            "hmac-sha256" => Some(WebhookScheme::HmacSha256),
+            "api-key" => Some(WebhookScheme::ApiKey),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookScheme::GithubSha256 => "github-sha256",
+            WebhookScheme::Stripe => "stripe",
+            WebhookScheme::HmacSha256 => "hmac-sha256",
+            WebhookScheme::ApiKey => "api-key",
+        }
+    }
+}
+
+/// The scheme and secret of a `#[webhook_mapping]`, generated by the macro.
+///
+/// @since 0.3.0
+pub trait WebhookSpec {
+    fn scheme() -> WebhookScheme;
+
+    /// The secret, or a `${key}` reference to the Rocket configuration, e.g.
+    /// `${hooks.github.secret}`.
+    fn secret() -> &'static str;
+}
+
+/// The verified delivery of a webhook.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookPayload {
+    pub scheme: WebhookScheme,
+    /// The delivery id, e.g. `X-GitHub-Delivery`, if any.
+    pub delivery: Option<String>,
+    /// The signed timestamp, if the scheme has one.
+    pub timestamp: Option<u64>,
+    /// The raw body, as signed.
+    pub body: Vec<u8>,
+}
+
+impl WebhookPayload {
+    pub fn text(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.body)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+/// Why a webhook delivery is rejected.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WebhookError {
+    /// The secret reference is not in the Rocket configuration: `500`.
+    Unconfigured(String),
+    /// The body exceeds the `webhook` limit (`1 MiB` by default): `413`.
+    TooLarge,
+    /// A required header is missing: `401`.
+    Missing(&'static str),
+    /// A header can't be parsed: `401`.
+    Malformed(&'static str),
+    /// The signature doesn't match: `401`.
+    Mismatch,
+    /// The signed timestamp is out of the `WEBHOOK_TOLERANCE`: `401`.
+    Stale,
+    /// The delivery was already received: `401`.
+    Replayed,
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::Unconfigured(key) => write!(f, "Unconfigured webhook secret `{}`", key),
+            WebhookError::TooLarge => write!(f, "Webhook payload too large"),
+            WebhookError::Missing(header) => write!(f, "Missing `{}`", header),
+            WebhookError::Malformed(header) => write!(f, "Malformed `{}`", header),
+            WebhookError::Mismatch => write!(f, "Webhook signature mismatch"),
+            WebhookError::Stale => write!(f, "Stale webhook timestamp"),
+            WebhookError::Replayed => write!(f, "Replayed webhook delivery"),
+        }
+    }
+}
+
+impl WebhookError {
+    pub fn status(&self) -> Status {
+        match self {
+            WebhookError::Unconfigured(_) => Status::InternalServerError,
+            WebhookError::TooLarge => Status::PayloadTooLarge,
+            _ => Status::Unauthorized,
+        }
+    }
+}
+
+/// A data guard verifying a webhook delivery with the scheme and secret of `W`.
+///
+/// @since 0.3.0
+pub struct Webhook<W> {
+    payload: WebhookPayload,
+    spec: PhantomData<W>,
+}
+
+impl<W> Webhook<W> {
+    pub fn into_inner(self) -> WebhookPayload {
+        self.payload
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, W: WebhookSpec> FromData<'r> for Webhook<W> {
+    type Error = WebhookError;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let fail = |error: WebhookError| {
+            rocket::warn!("Rejected webhook: {}", error);
+            data::Outcome::Error((error.status(), error))
+        };
+
+        let secret = match resolve_secret(request, W::secret()) {
+            Ok(secret) => secret,
+            Err(error) => return fail(error),
+        };
+
+        let limit = request
+            .limits()
+            .get("webhook")
+            .unwrap_or_else(|| 1.mebibytes());
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => return fail(WebhookError::TooLarge),
+            Err(_) => return fail(WebhookError::Malformed("body")),
+        };
+
+        match verify(W::scheme(), &secret, request, body) {
+            Ok(payload) => data::Outcome::Success(Webhook {
+                payload,
+                spec: PhantomData,
+            }),
+            Err(error) => fail(error),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Resolves a `${key}` secret from the Rocket configuration.
+#[doc(hidden)]
+fn resolve_secret(request: &Request<'_>, secret: &str) -> Result<String, WebhookError> {
+    match secret
+        .strip_prefix("${")
+        .and_then(|secret| secret.strip_suffix('}'))
+    {
+        Some(key) => request
+            .rocket()
+            .figment()
+            .extract_inner::<String>(key)
+            .map_err(|_| WebhookError::Unconfigured(key.to_string())),
+        None => Ok(secret.to_string()),
+    }
+}
+
+#[doc(hidden)]
+fn verify(
+    scheme: WebhookScheme,
+    secret: &str,
+    request: &Request<'_>,
+    body: Vec<u8>,
+) -> Result<WebhookPayload, WebhookError> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .get_one(name)
+            .ok_or(WebhookError::Missing(name))
+    };
+
+    let mut payload = WebhookPayload {
+        scheme,
+        delivery: None,
+        timestamp: None,
+        body,
+    };
+
+    match scheme {
+        WebhookScheme::GithubSha256 => {
+            const SIGNATURE: &str = "X-Hub-Signature-256";
+            let signature = header(SIGNATURE)?
+                .strip_prefix("sha256=")
+                .and_then(decode_hex)
+                .ok_or(WebhookError::Malformed(SIGNATURE))?;
+            verify_hmac(secret, &[&payload.body], &signature)?;
+
+            // The delivery header isn't signed: the signature is remembered too, so that
+            // changing or dropping the id doesn't replay a delivery.
+            let delivery = header("X-GitHub-Delivery")?;
+            remember(&[
+                format!("{}:delivery:{}", scheme.name(), delivery),
+                format!("{}:signature:{}", scheme.name(), encode_hex(&signature)),
+            ])?;
+            payload.delivery = Some(delivery.to_string());
+        }
+        WebhookScheme::Stripe => {
+            const SIGNATURE: &str = "Stripe-Signature";
+            let fields: Vec<(&str, &str)> = header(SIGNATURE)?
+                .split(',')
+                .filter_map(|field| field.trim().split_once('='))
+                .collect();
+            let timestamp = fields
+                .iter()
+                .find(|(name, _)| *name == "t")
+                .and_then(|(_, value)| value.parse::<u64>().ok())
+                .ok_or(WebhookError::Malformed(SIGNATURE))?;
+            let signatures: Vec<Vec<u8>> = fields
+                .iter()
+                .filter(|(name, _)| *name == "v1")
+                .filter_map(|(_, value)| decode_hex(value))
+                .collect();
+
+            check_timestamp(timestamp)?;
+            let prefix = format!("{}.", timestamp);
+            let signature = signatures
+                .iter()
+                .find(|signature| {
+                    verify_hmac(secret, &[prefix.as_bytes(), &payload.body], signature).is_ok()
+                })
+                .ok_or(WebhookError::Mismatch)?;
+
+            payload.timestamp = Some(timestamp);
+            remember(&[format!(
+                "{}:{}:{}",
+                scheme.name(),
+                timestamp,
+                encode_hex(signature)
+            )])?;
+        }
+        WebhookScheme::HmacSha256 => {
+            const SIGNATURE: &str = "X-Webhook-Signature";
+            const TIMESTAMP: &str = "X-Webhook-Timestamp";
+            let signature = header(SIGNATURE)?;
+            let signature = decode_hex(signature.strip_prefix("sha256=").unwrap_or(signature))
+                .ok_or(WebhookError::Malformed(SIGNATURE))?;
+            let timestamp = header(TIMESTAMP)?
+                .parse::<u64>()
+                .map_err(|_| WebhookError::Malformed(TIMESTAMP))?;
+
+            check_timestamp(timestamp)?;
+            let prefix = format!("{}.", timestamp);
+            verify_hmac(secret, &[prefix.as_bytes(), &payload.body], &signature)?;
+
+            payload.timestamp = Some(timestamp);
+            remember(&[format!(
+                "{}:{}:{}",
+                scheme.name(),
+                timestamp,
+                encode_hex(&signature)
+            )])?;
+        }
+        WebhookScheme::ApiKey => {
+            let key = header("X-Api-Key")?;
+            if !bool::from(key.as_bytes().ct_eq(secret.as_bytes())) {
+                return Err(WebhookError::Mismatch);
+            }
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Compares the HMAC-SHA256 of the `parts` with the `signature` in constant time.
+#[doc(hidden)]
+fn verify_hmac(secret: &str, parts: &[&[u8]], signature: &[u8]) -> Result<(), WebhookError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    for part in parts {
+        mac.update(part);
+    }
+
+    mac.verify_slice(signature)
+        .map_err(|_| WebhookError::Mismatch)
+}
+
+#[doc(hidden)]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[doc(hidden)]
+fn check_timestamp(timestamp: u64) -> Result<(), WebhookError> {
+    match now().abs_diff(timestamp) <= WEBHOOK_TOLERANCE {
+        true => Ok(()),
+        false => Err(WebhookError::Stale),
+    }
+}
+
+/// Remembers the keys of a delivery while its timestamp could be accepted, failing if any
+/// of them was already received.
+#[doc(hidden)]
+fn remember(keys: &[String]) -> Result<(), WebhookError> {
+    let now = now();
+    let mut deliveries = DELIVERIES.lock().unwrap();
+    let deliveries = deliveries.get_or_insert_with(HashMap::new);
+
+    deliveries.retain(|_, expiry| *expiry > now);
+    if keys.iter().any(|key| deliveries.contains_key(key)) {
+        return Err(WebhookError::Replayed);
+    }
+    for key in keys {
+        deliveries.insert(key.clone(), now + 2 * WEBHOOK_TOLERANCE);
+    }
+
+    Ok(())
+}
+
+#[doc(hidden)]
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((hex_digit(*high)? << 4) | hex_digit(*low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+#[doc(hidden)]
+fn hex_digit(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|digit| digit as u8)
+}
+
+#[doc(hidden)]
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
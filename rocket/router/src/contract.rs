@@ -0,0 +1,607 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/router/contract
+
+// ----------------------------------------------------------------
+
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::{ContentType, Method, Status};
+use rocket::route::{BoxFuture, Outcome};
+use rocket::{Build, Config, Data, Request, Response, Rocket, Route};
+use serde_json::{json, Value};
+
+use autortr_rocket_core::{
+    openapi_document, route_descriptors, snapshot_route_mappings, ADDITIONAL_QUERY,
+    TRAILING_SEGMENTS,
+};
+
+// ----------------------------------------------------------------
+
+/// The base of the internal route answering rejected requests.
+pub const CONTRACT_BASE: &str = crate::INTERNAL_BASE;
+
+/// The path of the internal route answering rejected requests, under `CONTRACT_BASE`.
+pub const CONTRACT_PATH: &str = "/contract";
+
+/// The size of Rocket's peek buffer, see `Data::peek`: request bodies checked against their
+/// schema are shorter.
+pub const CONTRACT_PEEK_LIMIT: usize = 512;
+
+// ----------------------------------------------------------------
+
+/// What the `ContractFairing` does with violations, which are always logged and reported.
+///
+/// @since 0.3.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractMode {
+    /// Rejected requests answer a `400` before any handler runs, rejected responses
+    /// are replaced by a `500`.
+    Reject,
+    Log,
+}
+
+/// A request or response that doesn't match the OpenAPI contract.
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractViolation {
+    pub method: String,
+    pub path: String,
+    /// The `operationId` of the matched operation.
+    pub operation: String,
+    pub message: String,
+}
+
+/// The violations seen by a `ContractFairing`, shared with the attached fairing.
+///
+/// @since 0.3.0
+#[derive(Clone, Default)]
+pub struct ContractReport {
+    violations: Arc<Mutex<Vec<ContractViolation>>>,
+}
+
+impl ContractReport {
+    pub fn violations(&self) -> Vec<ContractViolation> {
+        self.violations.lock().unwrap().clone()
+    }
+
+    #[doc(hidden)]
+    fn record(&self, violations: &[ContractViolation]) {
+        for violation in violations {
+            rocket::warn!(
+                "Contract violation: {} {} ({}): {}",
+                violation.method,
+                violation.path,
+                violation.operation,
+                violation.message
+            );
+        }
+        self.violations
+            .lock()
+            .unwrap()
+            .extend(violations.iter().cloned());
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Validates requests, and responses outside of the release profile, against an OpenAPI
+/// document: unknown query parameters, parameter and JSON body schema mismatches, and
+/// undocumented status codes.
+///
+/// A request body documented as JSON is a violation if it comes with an undocumented content
+/// type. JSON bodies longer than the `peek_limit` can't be read ahead of the handler: they are
+/// reported as too large to check, and rejected in `ContractMode::Reject`.
+///
+/// # Examples
+///
+/// ```rust
+/// use autortr_rocket_router::contract::{ContractFairing, ContractMode};
+///
+/// let fairing = ContractFairing::from_registry().mode(ContractMode::Log);
+/// let report = fairing.report();
+///
+/// let app = rocket::build().attach(fairing);
+/// assert!(report.violations().is_empty());
+/// ```
+///
+/// @since 0.3.0
+pub struct ContractFairing {
+    document: Value,
+    mode: ContractMode,
+    responses: Option<bool>,
+    peek_limit: usize,
+    report: ContractReport,
+}
+
+impl ContractFairing {
+    pub fn new(document: Value) -> Self {
+        ContractFairing {
+            document,
+            mode: ContractMode::Reject,
+            responses: None,
+            peek_limit: CONTRACT_PEEK_LIMIT - 1,
+            report: ContractReport::default(),
+        }
+    }
+
+    /// Validates against the `openapi_document` of every registered route mapping.
+    pub fn from_registry() -> Self {
+        let descriptors = route_descriptors(&snapshot_route_mappings());
+
+        ContractFairing::new(openapi_document("autortr", "0.1.0", &descriptors))
+    }
+
+    pub fn mode(mut self, mode: ContractMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Whether responses are validated, by default outside of the release profile.
+    pub fn validate_responses(mut self, responses: bool) -> Self {
+        self.responses = Some(responses);
+        self
+    }
+
+    /// The longest request body checked, by default (and at most) `CONTRACT_PEEK_LIMIT - 1`.
+    pub fn peek_limit(mut self, limit: usize) -> Self {
+        self.peek_limit = limit.min(CONTRACT_PEEK_LIMIT - 1);
+        self
+    }
+
+    pub fn report(&self) -> ContractReport {
+        self.report.clone()
+    }
+
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    #[doc(hidden)]
+    fn violations(
+        &self,
+        request: &Request<'_>,
+        operation: &Operation<'_>,
+        messages: Vec<String>,
+    ) -> Vec<ContractViolation> {
+        messages
+            .into_iter()
+            .map(|message| ContractViolation {
+                method: request.method().as_str().to_string(),
+                path: request.uri().path().to_string(),
+                operation: operation.id.to_string(),
+                message,
+            })
+            .collect()
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for ContractFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "autortr contract",
+            kind: Kind::Ignite | Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        Ok(rocket.mount(
+            CONTRACT_BASE,
+            vec![Route::new(Method::Get, CONTRACT_PATH, reject_request)],
+        ))
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, data: &mut Data<'_>) {
+        let operation = match find_operation(&self.document, request) {
+            Some(operation) => operation,
+            None => return,
+        };
+
+        let mut messages = check_parameters(&operation, request);
+        if let Some(schema) = operation.body_schema() {
+            let json = request.content_type().map(|ct| ct.is_json()) == Some(true);
+            if !json && !operation.accepts(request.content_type()) {
+                messages.push(match request.content_type() {
+                    Some(content_type) => format!(
+                        "body content type `{}` is not documented",
+                        content_type.media_type()
+                    ),
+                    None => "body content type is missing".to_string(),
+                });
+            } else if json {
+                // A full peek buffer may be a prefix, even of an already buffered body.
+                let peeked = data.peek(CONTRACT_PEEK_LIMIT).await.to_vec();
+                let complete = data.peek_complete() && peeked.len() < CONTRACT_PEEK_LIMIT;
+                match complete && peeked.len() <= self.peek_limit {
+                    true => match serde_json::from_slice::<Value>(&peeked) {
+                        Ok(body) => messages.extend(check_schema(&body, schema, "body")),
+                        Err(error) => messages.push(format!("body is not valid JSON: {}", error)),
+                    },
+                    false => messages.push(format!(
+                        "body too large to check: longer than {} bytes",
+                        self.peek_limit
+                    )),
+                }
+            }
+        }
+
+        if messages.is_empty() {
+            return;
+        }
+
+        let violations = self.violations(request, &operation, messages);
+        self.report.record(&violations);
+
+        if self.mode == ContractMode::Reject {
+            request.local_cache(|| RejectedRequest(violations));
+            request.set_method(Method::Get);
+            request.set_uri(contract_uri());
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let enabled = self
+            .responses
+            .unwrap_or_else(|| request.rocket().config().profile != Config::RELEASE_PROFILE);
+        if !enabled
+            || !request
+                .local_cache(|| RejectedRequest(Vec::new()))
+                .0
+                .is_empty()
+        {
+            return;
+        }
+
+        let operation = match find_operation(&self.document, request) {
+            Some(operation) => operation,
+            None => return,
+        };
+
+        let mut messages = Vec::new();
+        match operation.response(response.status()) {
+            None => messages.push(format!(
+                "status {} is not documented",
+                response.status().code
+            )),
+            Some(documented) => {
+                let schema = documented.pointer("/content/application~1json/schema");
+                let json = response.content_type().map(|ct| ct.is_json()) == Some(true);
+                if let (Some(schema), true) = (schema, json) {
+                    let body = response.body_mut().to_string().await.unwrap_or_default();
+                    match serde_json::from_str::<Value>(&body) {
+                        Ok(value) => messages.extend(check_schema(&value, schema, "response")),
+                        Err(error) => {
+                            messages.push(format!("response is not valid JSON: {}", error))
+                        }
+                    }
+                    response.set_sized_body(body.len(), Cursor::new(body));
+                }
+            }
+        }
+
+        if messages.is_empty() {
+            return;
+        }
+
+        let violations = self.violations(request, &operation, messages);
+        self.report.record(&violations);
+
+        if self.mode == ContractMode::Reject {
+            let body = rejection(Status::InternalServerError, &violations).to_string();
+
+            response.set_status(Status::InternalServerError);
+            response.set_header(ContentType::JSON);
+            response.set_sized_body(body.len(), Cursor::new(body));
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The violations of a request rewritten to the internal route.
+#[doc(hidden)]
+struct RejectedRequest(Vec<ContractViolation>);
+
+/// An operation of the document matching a request, with its path parameter values.
+#[doc(hidden)]
+struct Operation<'d> {
+    id: &'d str,
+    operation: &'d Value,
+    path_values: Vec<(String, String)>,
+}
+
+impl<'d> Operation<'d> {
+    fn parameters(&self, location: &str) -> Vec<&'d Value> {
+        self.operation["parameters"]
+            .as_array()
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .filter(|parameter| parameter["in"] == location)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn body_schema(&self) -> Option<&'d Value> {
+        self.operation
+            .pointer("/requestBody/content/application~1json/schema")
+    }
+
+    /// Whether the request body documents a media range matching `content_type`.
+    fn accepts(&self, content_type: Option<&ContentType>) -> bool {
+        let content_type = match content_type {
+            Some(content_type) => content_type,
+            None => return false,
+        };
+        let content = match self.operation.pointer("/requestBody/content") {
+            Some(Value::Object(content)) => content,
+            _ => return false,
+        };
+
+        content.keys().any(|range| match range.split_once('/') {
+            Some(("*", "*")) => true,
+            Some((top, "*")) => content_type.top().as_str().eq_ignore_ascii_case(top),
+            _ => ContentType::parse_flexible(range)
+                .map(|range| range.media_type() == content_type.media_type())
+                .unwrap_or(false),
+        })
+    }
+
+    fn response(&self, status: Status) -> Option<&'d Value> {
+        let responses = self.operation["responses"].as_object()?;
+        let code = status.code.to_string();
+        let range = format!("{}XX", status.code / 100);
+
+        responses
+            .get(&code)
+            .or_else(|| responses.get(&range))
+            .or_else(|| responses.get("default"))
+    }
+}
+
+/// The internal route answering rejected requests.
+#[doc(hidden)]
+fn contract_uri() -> Origin<'static> {
+    Origin::parse_owned(format!("{}{}", CONTRACT_BASE, CONTRACT_PATH)).unwrap()
+}
+
+#[doc(hidden)]
+fn reject_request<'r>(request: &'r Request<'_>, data: Data<'r>) -> BoxFuture<'r> {
+    Box::pin(async move {
+        let violations = &request.local_cache(|| RejectedRequest(Vec::new())).0;
+        if violations.is_empty() {
+            return Outcome::forward(data, Status::NotFound);
+        }
+
+        let body = rejection(Status::BadRequest, violations).to_string();
+        Outcome::from(
+            request,
+            rocket::response::status::Custom(Status::BadRequest, (ContentType::JSON, body)),
+        )
+    })
+}
+
+#[doc(hidden)]
+fn rejection(status: Status, violations: &[ContractViolation]) -> Value {
+    let messages: Vec<&str> = violations
+        .iter()
+        .map(|violation| violation.message.as_str())
+        .collect();
+
+    json!({
+        "status": status.code,
+        "title": status.reason_lossy(),
+        "violations": messages,
+    })
+}
+
+/// The operation of the request's method whose path matches with the most static segments.
+#[doc(hidden)]
+fn find_operation<'d>(document: &'d Value, request: &Request<'_>) -> Option<Operation<'d>> {
+    let method = request.method().as_str().to_lowercase();
+    let segments: Vec<&str> = request.uri().path().segments().collect();
+
+    let mut best: Option<(usize, Operation<'d>)> = None;
+    for (template, item) in document["paths"].as_object()? {
+        let operation = match item.get(&method) {
+            Some(operation) => operation,
+            None => continue,
+        };
+        let trailing = operation["parameters"]
+            .as_array()
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .any(|parameter| parameter[TRAILING_SEGMENTS] == true)
+            })
+            .unwrap_or(false);
+
+        if let Some((score, path_values)) = match_template(template, &segments, trailing) {
+            if best.as_ref().map(|(best, _)| score > *best).unwrap_or(true) {
+                let id = operation["operationId"].as_str().unwrap_or_default();
+                best = Some((
+                    score,
+                    Operation {
+                        id,
+                        operation,
+                        path_values,
+                    },
+                ));
+            }
+        }
+    }
+
+    best.map(|(_, operation)| operation)
+}
+
+/// The number of static segments and the path values, if the template matches.
+#[doc(hidden)]
+fn match_template(
+    template: &str,
+    segments: &[&str],
+    trailing: bool,
+) -> Option<(usize, Vec<(String, String)>)> {
+    let parts: Vec<&str> = template
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    let (mut score, mut values) = (0, Vec::new());
+
+    for (index, part) in parts.iter().enumerate() {
+        let name = part
+            .strip_prefix('{')
+            .and_then(|part| part.strip_suffix('}'));
+        let last = index + 1 == parts.len();
+
+        match name {
+            Some(name) if last && trailing => {
+                let rest = segments.get(index..).unwrap_or_default().join("/");
+                values.push((name.to_string(), rest));
+                return Some((score, values));
+            }
+            Some(name) => values.push((name.to_string(), segments.get(index)?.to_string())),
+            None if segments.get(index) == Some(part) => score += 1,
+            None => return None,
+        }
+    }
+
+    match parts.len() == segments.len() {
+        true => Some((score, values)),
+        false => None,
+    }
+}
+
+#[doc(hidden)]
+fn check_parameters(operation: &Operation<'_>, request: &Request<'_>) -> Vec<String> {
+    let mut messages = Vec::new();
+
+    for (name, value) in &operation.path_values {
+        let parameter = operation
+            .parameters("path")
+            .into_iter()
+            .find(|parameter| parameter["name"] == name.as_str());
+        if let Some(schema) = parameter.map(|parameter| &parameter["schema"]) {
+            messages.extend(check_schema(
+                &coerce(value, schema),
+                schema,
+                &format!("path parameter `{}`", name),
+            ));
+        }
+    }
+
+    let query = operation.parameters("query");
+    let additional = operation.operation[ADDITIONAL_QUERY] == true;
+    for field in request.query_fields() {
+        // `user.name` and `user[name]` belong to `user`.
+        let key = field.name.key_lossy().as_str();
+        let parameter = query.iter().find(|parameter| parameter["name"] == key);
+
+        match parameter {
+            Some(parameter) if field.name.as_name().as_str() == key => {
+                messages.extend(check_schema(
+                    &coerce(field.value, &parameter["schema"]),
+                    &parameter["schema"],
+                    &format!("query parameter `{}`", key),
+                ))
+            }
+            Some(_) => {}
+            None if additional => {}
+            None => messages.push(format!(
+                "unknown query parameter `{}`",
+                field.name.as_name()
+            )),
+        }
+    }
+
+    messages
+}
+
+/// The parameter value as the JSON type of its schema, if it parses.
+#[doc(hidden)]
+fn coerce(value: &str, schema: &Value) -> Value {
+    let parsed = match schema["type"].as_str() {
+        Some("integer") => match value.parse::<i64>() {
+            Ok(value) => Some(Value::from(value)),
+            Err(_) => value.parse::<u64>().ok().map(Value::from),
+        },
+        Some("number") => value.parse::<f64>().ok().map(Value::from),
+        Some("boolean") => value.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| Value::from(value))
+}
+
+/// Checks `value` against a JSON schema subset: `type`, `enum`, `required`,
+/// `properties` and `items`.
+#[doc(hidden)]
+fn check_schema(value: &Value, schema: &Value, at: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+
+    if let Some(expected) = schema["type"].as_str() {
+        let matches = match expected {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            messages.push(format!("{} is not of type `{}`", at, expected));
+            return messages;
+        }
+    }
+
+    if let Some(values) = schema["enum"].as_array() {
+        if !values.contains(value) {
+            messages.push(format!("{} is not one of {}", at, schema["enum"]));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for name in schema["required"].as_array().into_iter().flatten() {
+            let name = name.as_str().unwrap_or_default();
+            if !object.contains_key(name) {
+                messages.push(format!("{} misses the required `{}`", at, name));
+            }
+        }
+        if let Some(properties) = schema["properties"].as_object() {
+            for (name, property) in properties {
+                if let Some(value) = object.get(name) {
+                    messages.extend(check_schema(value, property, &format!("{}.{}", at, name)));
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(schema)) = (value.as_array(), schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            messages.extend(check_schema(item, schema, &format!("{}[{}]", at, index)));
+        }
+    }
+
+    messages
+}
@@ -0,0 +1,351 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/router/cors
+
+// ----------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::route::{Handler, Outcome};
+use rocket::{Build, Data, Request, Response, Rocket, Route};
+
+use autortr_rocket_core::{path_shape, CorsPolicy, RouteMapping};
+
+// ----------------------------------------------------------------
+
+/// The name of the generated preflight routes.
+///
+/// @since 0.3.0
+pub const PREFLIGHT: &str = "cors_preflight";
+
+// ----------------------------------------------------------------
+
+/// Applies the named CORS policies of the mappings, attached by the router whenever a mapping
+/// has a `cors` policy.
+///
+/// Policies are read from the Rocket configuration (`cors.<name>`) at ignition, which fails on
+/// an unknown or invalid policy, e.g. one allowing credentials from any origin. Responses to
+/// allowed origins get the `Access-Control-*` headers of their route's policy, and `Vary: Origin`
+/// unless it allows any origin; preflights are answered by the `preflight_routes`.
+///
+/// @since 0.3.0
+pub struct CorsFairing {
+    mappings: Vec<RouteMapping>,
+}
+
+impl CorsFairing {
+    /// Every mapping, for the `Allow` header of their paths, including those without a policy.
+    pub fn new(mappings: Vec<RouteMapping>) -> Self {
+        CorsFairing { mappings }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for CorsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "autortr CORS",
+            kind: Kind::Ignite | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        let policies: HashMap<String, CorsPolicy> = match rocket.figment().extract_inner("cors") {
+            Ok(policies) => policies,
+            Err(error) if error.missing() => HashMap::new(),
+            Err(error) => {
+                rocket::error!("Invalid `cors` configuration: {}", error);
+                return Err(rocket);
+            }
+        };
+
+        for (name, policy) in &policies {
+            if let Err(error) = policy.validate() {
+                rocket::error!("Invalid CORS policy `cors.{}`: {}", name, error);
+                return Err(rocket);
+            }
+        }
+
+        let mut mappings = Vec::new();
+        for mapping in &self.mappings {
+            let policy = mapping.cors.clone();
+            match &policy {
+                Some(policy) if !policies.contains_key(policy) => {
+                    rocket::error!(
+                        "`{}` has the CORS policy `{}`, which is not configured under `cors.{}`",
+                        mapping.function,
+                        policy,
+                        policy
+                    );
+                    return Err(rocket);
+                }
+                _ => {}
+            }
+
+            mappings.push(CorsMapping {
+                function: mapping.function.clone(),
+                shape: shape_of(&mapping.full_path()),
+                methods: mapping.routes.iter().map(|route| route.method).collect(),
+                policy,
+            });
+        }
+
+        Ok(rocket.manage(CorsState { policies, mappings }))
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if request.method() == Method::Options {
+            return;
+        }
+
+        let (state, route) = match (request.rocket().state::<CorsState>(), request.route()) {
+            (Some(state), Some(route)) => (state, route),
+            _ => return,
+        };
+
+        let shape = shape_of(route.uri.path());
+        let mapping = state.mappings.iter().find(|mapping| {
+            route.name.as_deref() == Some(mapping.function.as_str())
+                && mapping.shape == shape
+                && mapping.methods.contains(&route.method)
+        });
+        let policy = match mapping.and_then(|mapping| state.policy(mapping)) {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        // Whether or not the origin is allowed, caches must not share the response across origins.
+        if !policy.allows_any_origin() {
+            response.adjoin_header(Header::new("Vary", "Origin"));
+        }
+
+        let origin = match request.headers().get_one("Origin") {
+            Some(origin) => origin,
+            None => return,
+        };
+        if policy.allows_origin(origin) && policy.allows_method(route.method.as_str()) {
+            response.set_header(Header::new(
+                "Access-Control-Allow-Origin",
+                policy.allow_origin(origin),
+            ));
+            if policy.allow_credentials {
+                response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            }
+            if !policy.exposed_headers.is_empty() {
+                response.set_header(Header::new(
+                    "Access-Control-Expose-Headers",
+                    policy.exposed_headers.join(", "),
+                ));
+            }
+        }
+    }
+}
+
+/// One `OPTIONS` route per distinct full path of the mappings with a `cors` policy, unless
+/// the path already has an `OPTIONS` mapping.
+///
+/// A preflight is answered with a `204` and the `Access-Control-*` headers of the policy of the
+/// requested method's mapping, or a `403` if the policy denies it. `OPTIONS` requests which are
+/// not preflights get the path's methods as `Allow`.
+///
+/// @since 0.3.0
+pub fn preflight_routes(mappings: &[RouteMapping]) -> Vec<Route> {
+    let mut shapes: Vec<String> = mappings
+        .iter()
+        .flat_map(|mapping| mapping.routes.iter().map(move |route| (mapping, route)))
+        .filter(|(_, route)| route.method == Method::Options)
+        .map(|(mapping, _)| shape_of(&mapping.full_path()))
+        .collect();
+
+    let mut routes = Vec::new();
+    for mapping in mappings.iter().filter(|mapping| mapping.cors.is_some()) {
+        let path = mapping.full_path();
+        let path = path.split('?').next().unwrap_or_default();
+        let shape = path_shape(path);
+        if shapes.contains(&shape) {
+            continue;
+        }
+
+        let mut route = Route::new(
+            Method::Options,
+            path,
+            Preflight {
+                shape: shape.clone(),
+            },
+        );
+        route.name = Some(PREFLIGHT.into());
+
+        routes.push(route);
+        shapes.push(shape);
+    }
+
+    routes
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+struct CorsState {
+    policies: HashMap<String, CorsPolicy>,
+    mappings: Vec<CorsMapping>,
+}
+
+impl CorsState {
+    fn policy(&self, mapping: &CorsMapping) -> Option<&CorsPolicy> {
+        self.policies.get(mapping.policy.as_deref()?)
+    }
+}
+
+#[doc(hidden)]
+struct CorsMapping {
+    function: String,
+    shape: String,
+    methods: Vec<Method>,
+    /// `None` for the mappings without a policy, only listed in `Allow`.
+    policy: Option<String>,
+}
+
+#[doc(hidden)]
+fn shape_of(full_path: &str) -> String {
+    path_shape(full_path.split('?').next().unwrap_or_default())
+}
+
+#[doc(hidden)]
+#[derive(Clone)]
+struct Preflight {
+    shape: String,
+}
+
+#[rocket::async_trait]
+impl Handler for Preflight {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let state = match request.rocket().state::<CorsState>() {
+            Some(state) => state,
+            None => return Outcome::forward(data, Status::NotFound),
+        };
+        let mappings: Vec<&CorsMapping> = state
+            .mappings
+            .iter()
+            .filter(|mapping| mapping.shape == self.shape)
+            .collect();
+
+        let headers = request.headers();
+        let (origin, requested) = match (
+            headers.get_one("Origin"),
+            headers.get_one("Access-Control-Request-Method"),
+        ) {
+            (Some(origin), Some(requested)) => (origin, requested),
+            _ => {
+                let allow = methods(mappings.iter().copied(), |_| true);
+                return Outcome::Success(
+                    Response::build()
+                        .status(Status::NoContent)
+                        .header(Header::new("Allow", allow.join(", ")))
+                        .finalize(),
+                );
+            }
+        };
+
+        let method = match requested.parse::<Method>() {
+            Ok(Method::Head) => Method::Get,
+            Ok(method) => method,
+            Err(_) => return Outcome::Error(Status::Forbidden),
+        };
+        let mapping = mappings
+            .iter()
+            .find(|mapping| mapping.methods.contains(&method));
+        let (mapping, policy) =
+            match mapping.and_then(|mapping| Some((mapping, state.policy(mapping)?))) {
+                Some(found) => found,
+                None => return Outcome::Error(Status::Forbidden),
+            };
+
+        let requested_headers: Vec<&str> = headers
+            .get_one("Access-Control-Request-Headers")
+            .map(|requested| {
+                requested
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|header| !header.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if !policy.allows_origin(origin)
+            || !policy.allows_method(method.as_str())
+            || !requested_headers
+                .iter()
+                .all(|header| policy.allows_header(header))
+        {
+            return Outcome::Error(Status::Forbidden);
+        }
+
+        let allowed = methods(
+            mappings
+                .iter()
+                .copied()
+                .filter(|candidate| candidate.policy == mapping.policy),
+            |method| policy.allows_method(method),
+        );
+
+        let mut response = Response::build();
+        response
+            .status(Status::NoContent)
+            .header(Header::new(
+                "Access-Control-Allow-Origin",
+                policy.allow_origin(origin),
+            ))
+            .header(Header::new("Vary", "Origin"))
+            .header(Header::new(
+                "Access-Control-Allow-Methods",
+                allowed.join(", "),
+            ));
+        if !requested_headers.is_empty() {
+            response.header(Header::new(
+                "Access-Control-Allow-Headers",
+                requested_headers.join(", "),
+            ));
+        }
+        if policy.allow_credentials {
+            response.header(Header::new("Access-Control-Allow-Credentials", "true"));
+        }
+        if let Some(max_age) = policy.max_age {
+            response.header(Header::new("Access-Control-Max-Age", max_age.to_string()));
+        }
+
+        Outcome::Success(response.finalize())
+    }
+}
+
+/// The distinct methods of the mappings, in order.
+#[doc(hidden)]
+fn methods<'a, M, F>(mappings: M, allows: F) -> Vec<&'static str>
+where
+    M: Iterator<Item = &'a CorsMapping>,
+    F: Fn(&str) -> bool,
+{
+    let mut methods = Vec::new();
+    for method in mappings.flat_map(|mapping| mapping.methods.iter()) {
+        if allows(method.as_str()) && !methods.contains(&method.as_str()) {
+            methods.push(method.as_str());
+        }
+    }
+
+    methods
+}
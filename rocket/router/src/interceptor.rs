@@ -0,0 +1,232 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/router/interceptor
+
+// ----------------------------------------------------------------
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::Method;
+use rocket::response::status::Custom;
+use rocket::route::{BoxFuture, Outcome};
+use rocket::{Build, Data, Request, Response, Rocket, Route};
+
+use autortr_rocket_core::{
+    path_matches, snapshot_interceptors, snapshot_route_mappings, InterceptorRegistration,
+    InterceptorResponse, PreHandle, RouteMapping,
+};
+
+use crate::INTERNAL_BASE;
+
+// ----------------------------------------------------------------
+
+/// The path of the internal route answering short-circuited requests, under `INTERNAL_BASE`.
+pub const INTERCEPTED_PATH: &str = "/intercepted";
+
+// ----------------------------------------------------------------
+
+/// Runs the matching interceptors around each request, attached by the router whenever
+/// interceptors are registered.
+///
+/// The route mapping of a request is the one of the lowest ranked route matching its method
+/// and path, as Rocket would try first.
+///
+/// Requests already rewritten to an internal route by another autortr fairing, e.g. rejected
+/// by a `ContractFairing` attached ahead, are not intercepted. Requests rewritten after the
+/// pre-handles ran, e.g. by a `ContractFairing` attached behind, skip the post-handles: their
+/// handler didn't run, the after-completions still do.
+///
+/// @since 0.3.0
+pub struct InterceptorFairing {
+    interceptors: Vec<InterceptorRegistration>,
+    mappings: Vec<RouteMapping>,
+}
+
+impl InterceptorFairing {
+    /// `interceptors` by ascending `order`, see `snapshot_interceptors`.
+    pub fn new(interceptors: Vec<InterceptorRegistration>, mappings: Vec<RouteMapping>) -> Self {
+        InterceptorFairing {
+            interceptors,
+            mappings,
+        }
+    }
+
+    pub fn from_registry() -> Self {
+        InterceptorFairing::new(snapshot_interceptors(), snapshot_route_mappings())
+    }
+
+    /// The index of the mapping whose route would handle the request, if any.
+    #[doc(hidden)]
+    fn find_mapping(&self, request: &Request<'_>) -> Option<usize> {
+        let path = request.uri().path().to_string();
+        let find = |method: Method| {
+            self.mappings
+                .iter()
+                .enumerate()
+                .filter(|(_, mapping)| path_matches(&mapping.full_path(), &path))
+                .flat_map(|(index, mapping)| {
+                    mapping
+                        .routes
+                        .iter()
+                        .filter(move |route| route.method == method)
+                        .map(move |route| (route.rank, index))
+                })
+                .min()
+                .map(|(_, index)| index)
+        };
+
+        // Rocket answers `HEAD` with the `GET` routes, unless there is a `HEAD` one.
+        match request.method() {
+            Method::Head => find(Method::Head).or_else(|| find(Method::Get)),
+            method => find(method),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for InterceptorFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "autortr interceptors",
+            kind: Kind::Ignite | Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        Ok(rocket.mount(
+            INTERNAL_BASE,
+            vec![Route::new(
+                Method::Get,
+                INTERCEPTED_PATH,
+                respond_intercepted,
+            )],
+        ))
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        if is_internal(request) {
+            return;
+        }
+
+        let index = match self.find_mapping(request) {
+            Some(index) => index,
+            None => return,
+        };
+        let mapping = &self.mappings[index];
+
+        let matched: Vec<usize> = (0..self.interceptors.len())
+            .filter(|&interceptor| self.interceptors[interceptor].matches(mapping))
+            .collect();
+        if matched.is_empty() {
+            return;
+        }
+
+        let (mut ran, mut response) = (Vec::new(), None);
+        for &interceptor in &matched {
+            ran.push(interceptor);
+            let registration = &self.interceptors[interceptor];
+            if let PreHandle::Respond(respond) =
+                registration.interceptor.pre_handle(request, mapping)
+            {
+                response = Some(respond);
+                break;
+            }
+        }
+
+        let short_circuited = response.is_some();
+        request.local_cache(|| Intercepted {
+            mapping: Some(index),
+            matched,
+            ran,
+            response,
+        });
+
+        if short_circuited {
+            request.set_method(Method::Get);
+            request.set_uri(
+                Origin::parse_owned(format!("{}{}", INTERNAL_BASE, INTERCEPTED_PATH)).unwrap(),
+            );
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let intercepted = request.local_cache(Intercepted::default);
+        let mapping = match intercepted.mapping {
+            Some(index) => &self.mappings[index],
+            None => return,
+        };
+
+        // Not short-circuited here, but rewritten to an internal route by another fairing.
+        let handled = intercepted.response.is_none() && !is_internal(request);
+        if handled {
+            for &interceptor in intercepted.matched.iter().rev() {
+                let registration = &self.interceptors[interceptor];
+                registration
+                    .interceptor
+                    .post_handle(request, mapping, response);
+            }
+        }
+
+        for &interceptor in intercepted.ran.iter().rev() {
+            let registration = &self.interceptors[interceptor];
+            registration
+                .interceptor
+                .after_completion(request, mapping, response.status());
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// The interceptors applied to a request.
+#[doc(hidden)]
+#[derive(Default)]
+struct Intercepted {
+    mapping: Option<usize>,
+    matched: Vec<usize>,
+    /// The interceptors whose pre-handle ran.
+    ran: Vec<usize>,
+    /// The response of the short-circuiting pre-handle.
+    response: Option<InterceptorResponse>,
+}
+
+/// Whether the request was rewritten to an internal route.
+#[doc(hidden)]
+fn is_internal(request: &Request<'_>) -> bool {
+    request
+        .uri()
+        .path()
+        .as_str()
+        .strip_prefix(INTERNAL_BASE)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[doc(hidden)]
+fn respond_intercepted<'r>(request: &'r Request<'_>, data: Data<'r>) -> BoxFuture<'r> {
+    Box::pin(async move {
+        match &request.local_cache(Intercepted::default).response {
+            Some(response) => Outcome::from(
+                request,
+                Custom(
+                    response.status,
+                    (response.content_type.clone(), response.body.clone()),
+                ),
+            ),
+            None => Outcome::forward(data, rocket::http::Status::NotFound),
+        }
+    })
+}
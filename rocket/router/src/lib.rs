@@ -18,9 +18,33 @@
 
 // ----------------------------------------------------------------
 
+#[cfg(feature = "contract")]
+pub mod contract;
+pub mod cors;
+pub mod interceptor;
+#[cfg(feature = "problem")]
+pub mod problem;
+pub mod rate_limit;
+
+// ----------------------------------------------------------------
+
 use rocket::{Build, Rocket};
 
-use autortr_rocket_core::{clean_route_mappings, try_acquire_route_mappings};
+use autortr_rocket_core::{
+    clean_route_mappings, snapshot_interceptors, try_acquire_route_mappings, RocketBackend,
+    RouteMapping, ROOT,
+};
+
+use crate::cors::CorsFairing;
+use crate::interceptor::InterceptorFairing;
+use crate::rate_limit::RateLimitFairing;
+
+// ----------------------------------------------------------------
+
+/// The base of the internal routes mounted by the router's fairings.
+///
+/// @since 0.3.0
+pub const INTERNAL_BASE: &str = "/__autortr";
 
 // ----------------------------------------------------------------
 
@@ -60,13 +84,61 @@ pub fn app() -> Rocket<Build> {
 fn __trigger_init__() {}
 
 fn build() -> Rocket<Build> {
-    let mut app = rocket::build();
-    let mappings = try_acquire_route_mappings();
-    for mapping in mappings {
-        app = app.mount(mapping.namespace, mapping.routes.clone());
-    }
+    let app = build_with(try_acquire_route_mappings());
 
     clean_route_mappings();
 
     app
 }
+
+/// Constructs a Rocket application from the given route mappings.
+///
+/// Unlike `app`, the registry is left untouched, which allows building several
+/// instances from `snapshot_route_mappings`, e.g. one per test.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket_core::snapshot_route_mappings;
+/// use autortr_rocket_router::build_with;
+///
+/// let mappings = snapshot_route_mappings()
+///     .into_iter()
+///     .filter(|mapping| mapping.namespace == "/rocket")
+///     .collect();
+///
+/// let app = build_with(mappings);
+/// ```
+///
+/// Mappings and catchers are mounted by `RocketBackend`. Registered interceptors are run by
+/// an attached `interceptor::InterceptorFairing`.
+/// Mappings with a `cors` policy get a `cors::CorsFairing` and their `cors::preflight_routes`,
+/// and those with a `rate_limit` a `rate_limit::RateLimitFairing`.
+/// With the `problem` feature, `problem::problem_catchers` are registered at `/`.
+///
+/// @since 0.3.0
+pub fn build_with(mappings: Vec<RouteMapping>) -> Rocket<Build> {
+    #[cfg(feature = "problem")]
+    let catchers = problem::problem_catchers();
+    #[cfg(not(feature = "problem"))]
+    let catchers = Vec::new();
+
+    let mut app = autortr_rocket_core::build::<RocketBackend>(mappings.clone(), catchers);
+
+    let interceptors = snapshot_interceptors();
+    if !interceptors.is_empty() {
+        app = app.attach(InterceptorFairing::new(interceptors, mappings.clone()));
+    }
+
+    if mappings.iter().any(|mapping| mapping.cors.is_some()) {
+        app = app
+            .attach(CorsFairing::new(mappings.clone()))
+            .mount(ROOT, cors::preflight_routes(&mappings));
+    }
+
+    if mappings.iter().any(|mapping| mapping.rate_limit.is_some()) {
+        app = app.attach(RateLimitFairing);
+    }
+
+    app
+}
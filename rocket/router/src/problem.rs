@@ -0,0 +1,185 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/router/problem
+
+// ----------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use rocket::catcher::BoxFuture;
+use rocket::http::{ContentType, Status};
+use rocket::response::status::Custom;
+use rocket::response::Responder;
+use rocket::{Catcher, Request};
+use serde_json::{Map, Value};
+
+use autortr_rocket_core::{namespace_contains, snapshot_route_mappings, Registry, RouteMapping};
+
+// ----------------------------------------------------------------
+
+pub const ABOUT_BLANK: &str = "about:blank";
+
+// ----------------------------------------------------------------
+
+/// Overrides the problem details of the errors raised under a namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use autortr_rocket_router::problem::{register_problem_namespace, ProblemNamespace};
+///
+/// register_problem_namespace(
+///     ProblemNamespace::new("/rocket")
+///         .type_uri("https://errors.example.com/rocket/{status}")
+///         .extension("service", "rocket"),
+/// );
+/// ```
+///
+/// @since 0.3.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProblemNamespace {
+    pub namespace: String,
+    /// The problem `type`, `{status}` is replaced by the status code.
+    pub type_uri: Option<String>,
+    /// Extension members added to every problem.
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl ProblemNamespace {
+    pub fn new<N: Into<String>>(namespace: N) -> Self {
+        ProblemNamespace {
+            namespace: namespace.into(),
+            type_uri: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+
+    pub fn type_uri<U: Into<String>>(mut self, type_uri: U) -> Self {
+        self.type_uri = Some(type_uri.into());
+        self
+    }
+
+    pub fn extension<N: Into<String>, V: Into<Value>>(mut self, name: N, value: V) -> Self {
+        self.extensions.insert(name.into(), value.into());
+        self
+    }
+}
+
+// ----------------------------------------------------------------
+
+static PROBLEM_NAMESPACES: Registry<ProblemNamespace> = Registry::new();
+
+// ----------------------------------------------------------------
+
+/// @since 0.3.0
+pub fn register_problem_namespace(namespace: ProblemNamespace) {
+    PROBLEM_NAMESPACES.register(namespace);
+}
+
+/// @since 0.3.0
+pub fn snapshot_problem_namespaces() -> Vec<ProblemNamespace> {
+    PROBLEM_NAMESPACES.snapshot()
+}
+
+// ----------------------------------------------------------------
+
+/// The default catcher emitting `application/problem+json` for every `4xx`/`5xx` status,
+/// registered at `/` by the router.
+///
+/// @since 0.3.0
+pub fn problem_catchers() -> Vec<Catcher> {
+    let mut catcher = Catcher::new(None, catch_problem);
+    catcher.name = Some("autortr_problem".into());
+
+    vec![catcher]
+}
+
+/// The RFC 9457 problem details of `status` for the request.
+///
+/// Besides `type`, `title`, `status` and `instance`, the problem names the matched
+/// `RouteMapping`'s `function` and full `path` when available, followed by the extensions of
+/// the most specific `ProblemNamespace`.
+///
+/// @since 0.3.0
+pub fn problem(status: Status, request: &Request<'_>) -> Value {
+    let mapping = matched_mapping(request);
+    let namespace = match &mapping {
+        Some(mapping) => mapping.declared_namespace(),
+        None => request.uri().path().to_string(),
+    };
+    let overrides = snapshot_problem_namespaces()
+        .into_iter()
+        .filter(|candidate| namespace_contains(&candidate.namespace, &namespace))
+        .max_by_key(|candidate| candidate.namespace.trim_end_matches('/').len());
+
+    let type_uri = overrides
+        .as_ref()
+        .and_then(|overrides| overrides.type_uri.as_ref())
+        .map(|type_uri| type_uri.replace("{status}", &status.code.to_string()))
+        .unwrap_or_else(|| ABOUT_BLANK.to_string());
+
+    let mut problem = Map::new();
+    problem.insert("type".to_string(), Value::from(type_uri));
+    problem.insert("title".to_string(), Value::from(status.reason_lossy()));
+    problem.insert("status".to_string(), Value::from(status.code));
+    problem.insert(
+        "instance".to_string(),
+        Value::from(request.uri().path().to_string()),
+    );
+
+    if let Some(mapping) = mapping {
+        problem.insert(
+            "function".to_string(),
+            Value::from(mapping.function.clone()),
+        );
+        problem.insert("path".to_string(), Value::from(mapping.full_path()));
+    }
+
+    if let Some(overrides) = overrides {
+        for (name, value) in overrides.extensions {
+            problem.entry(name).or_insert(value);
+        }
+    }
+
+    Value::Object(problem)
+}
+
+// ----------------------------------------------------------------
+
+#[doc(hidden)]
+fn catch_problem<'r>(status: Status, request: &'r Request<'_>) -> BoxFuture<'r> {
+    let body = problem(status, request).to_string();
+    let content_type = ContentType::new("application", "problem+json");
+
+    Box::pin(async move { Custom(status, (content_type, body)).respond_to(request) })
+}
+
+/// The registered mapping of the route that matched the request, if any.
+#[doc(hidden)]
+fn matched_mapping(request: &Request<'_>) -> Option<RouteMapping> {
+    let route = request.route()?;
+    let name = route.name.as_deref()?;
+
+    snapshot_route_mappings().into_iter().find(|mapping| {
+        mapping.function == name
+            && mapping.full_path().split('?').next() == Some(route.uri.path())
+            && mapping
+                .routes
+                .iter()
+                .any(|candidate| candidate.method == route.method)
+    })
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// rocket/router/rate_limit
+
+// ----------------------------------------------------------------
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Request, Response};
+
+use autortr_rocket_core::recorded_rate_limit;
+
+// ----------------------------------------------------------------
+
+/// Adds the `RateLimit-Limit`, `RateLimit-Remaining`, `RateLimit-Reset` and `RateLimit-Policy`
+/// headers to the responses of rate-limited requests, and `Retry-After` to their `429`s.
+/// Attached by the router whenever a mapping has a `rate_limit`.
+///
+/// @since 0.3.0
+pub struct RateLimitFairing;
+
+#[rocket::async_trait]
+impl Fairing for RateLimitFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "autortr rate limit",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let decision = match recorded_rate_limit(request) {
+            Some(decision) => decision,
+            None => return,
+        };
+
+        let limit = decision.limit;
+        response.set_header(Header::new("RateLimit-Limit", limit.requests.to_string()));
+        response.set_header(Header::new(
+            "RateLimit-Remaining",
+            decision.remaining.to_string(),
+        ));
+        response.set_header(Header::new("RateLimit-Reset", decision.reset.to_string()));
+        response.set_header(Header::new("RateLimit-Policy", limit.policy()));
+        if !decision.allowed {
+            response.set_header(Header::new("Retry-After", decision.retry_after.to_string()));
+        }
+    }
+}
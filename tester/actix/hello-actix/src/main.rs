@@ -0,0 +1,36 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/actix/hello-actix/main
+
+// ----------------------------------------------------------------
+
+use actix_web::{App, HttpServer};
+
+use autortr_actix::prelude::*;
+use hello_actix::__trigger_init__;
+
+// ----------------------------------------------------------------
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    __trigger_init__();
+
+    HttpServer::new(|| App::new().configure(configure))
+        .bind(("127.0.0.1", 8000))?
+        .run()
+        .await
+}
@@ -0,0 +1,157 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/actix/hello-actix/tests/app
+
+// ----------------------------------------------------------------
+
+use actix_web::http::{Method, StatusCode};
+use actix_web::{test, App};
+
+use autortr_actix::prelude::*;
+
+// ----------------------------------------------------------------
+
+async fn send(method: Method, uri: &str, form: Option<&str>) -> (StatusCode, String) {
+    hello_actix::__trigger_init__();
+    let app = test::init_service(
+        App::new().configure(|cfg| configure_with(cfg, snapshot_route_mappings())),
+    )
+    .await;
+
+    let request = test::TestRequest::default().method(method).uri(uri);
+    let request = match form {
+        Some(form) => request
+            .insert_header(("content-type", "application/x-www-form-urlencoded"))
+            .set_payload(form.to_string()),
+        None => request,
+    };
+
+    let response = test::call_service(&app, request.to_request()).await;
+    let status = response.status();
+    let body = test::read_body(response).await;
+
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+// ----------------------------------------------------------------
+
+#[actix_web::test]
+async fn request_mapping_routes() {
+    let cases = [
+        (Method::GET, "/get", "Hello, get!"),
+        (Method::POST, "/post", "Hello, post!"),
+        (Method::PUT, "/put", "Hello, put!"),
+        (Method::PATCH, "/patch", "Hello, patch!"),
+        (Method::DELETE, "/delete", "Hello, delete!"),
+        (Method::GET, "/actix/namespace", "Hello, namespace!"),
+        (Method::GET, "/http/get", "Hello, http.get mapping!"),
+        (
+            Method::GET,
+            "/path/get",
+            "Hello, http.default.path.get mapping!",
+        ),
+        (
+            Method::POST,
+            "/path/post",
+            "Hello, http.default.path.post mapping!",
+        ),
+    ];
+
+    for (method, uri, expected) in cases {
+        assert_eq!(
+            send(method.clone(), uri, None).await,
+            (StatusCode::OK, expected.to_string()),
+            "{} {}",
+            method,
+            uri
+        );
+    }
+
+    assert_eq!(send(Method::HEAD, "/head", None).await.0, StatusCode::OK);
+    assert_eq!(
+        send(Method::HEAD, "/http/head", None).await.0,
+        StatusCode::OK
+    );
+}
+
+#[actix_web::test]
+async fn form_data_routes() {
+    let cases = [
+        (Method::POST, "/actix/data", "Hello, data!"),
+        (Method::POST, "/http/post", "Hello, http.post mapping!"),
+        (Method::PUT, "/http/put", "Hello, http.put mapping!"),
+        (Method::PATCH, "/http/patch", "Hello, http.patch mapping!"),
+        (
+            Method::DELETE,
+            "/http/delete",
+            "Hello, http.delete mapping!",
+        ),
+    ];
+
+    for (method, uri, expected) in cases {
+        assert_eq!(
+            send(method.clone(), uri, Some("account=photowey")).await,
+            (StatusCode::OK, expected.to_string()),
+            "{} {}",
+            method,
+            uri
+        );
+    }
+}
+
+#[actix_web::test]
+async fn path_parameter_routes() {
+    assert_eq!(
+        send(Method::GET, "/users/42", None).await,
+        (StatusCode::OK, "Hello, user 42!".to_string())
+    );
+    assert_eq!(
+        send(Method::GET, "/files/a/b.txt", None).await,
+        (StatusCode::OK, "Hello, file a/b.txt!".to_string())
+    );
+    assert_eq!(
+        send(Method::GET, "/users/x", None).await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        send(Method::GET, "/missing", None).await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        send(Method::POST, "/get", None).await.0,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+}
+
+#[actix_web::test]
+async fn registered_paths_are_converted() {
+    hello_actix::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let user = mappings.iter().find(|m| m.function == "user_fn").unwrap();
+    let file = mappings.iter().find(|m| m.function == "file_fn").unwrap();
+
+    assert_eq!(
+        (user.path.as_str(), user.route_path.as_str()),
+        ("/<id>", "/{id}")
+    );
+    assert_eq!(
+        (file.path.as_str(), file.route_path.as_str()),
+        ("/<path..>", "/{path:.*}")
+    );
+    assert!(detect_conflicts(&route_descriptors(&mappings)).is_empty());
+}
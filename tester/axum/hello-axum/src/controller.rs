@@ -0,0 +1,145 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/axum/hello-axum/controller
+
+// ----------------------------------------------------------------
+
+use axum::extract::{Form, Path};
+use serde::Deserialize;
+
+use autortr_axum::prelude::*;
+
+// ----------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    account: String,
+}
+
+// ---------------------------------------------------------------- #[request_mapping]
+
+#[request_mapping(method = "get", path = "/get")]
+async fn get_fn() -> &'static str {
+    "Hello, get!"
+}
+
+#[request_mapping(method = "post", path = "/post")]
+async fn post_fn() -> &'static str {
+    "Hello, post!"
+}
+
+#[request_mapping(method = "put", path = "/put")]
+async fn puf_fn() -> &'static str {
+    "Hello, put!"
+}
+
+#[request_mapping(method = "patch", path = "/patch")]
+async fn patch_fn() -> &'static str {
+    "Hello, patch!"
+}
+
+#[request_mapping(method = "delete", path = "/delete")]
+async fn delete_fn() -> &'static str {
+    "Hello, delete!"
+}
+
+// ----------------------------------------------------------------
+
+#[request_mapping(namespace = "/axum", method = "get", path = "/namespace")]
+async fn namespace_fn() -> &'static str {
+    "Hello, namespace!"
+}
+
+// ----------------------------------------------------------------
+
+#[request_mapping(namespace = "/axum", method = "post", path = "/data", data = "<form>")]
+async fn data_fn(Form(form): Form<Account>) -> &'static str {
+    println!("data_fn: form.account: {}", form.account);
+
+    "Hello, data!"
+}
+
+// ----------------------------------------------------------------
+
+#[request_mapping(method = "head", path = "/head")]
+async fn head_fn() {}
+
+// ---------------------------------------------------------------- #[${http_method}_mapping]
+
+#[get_mapping(namespace = "/http", path = "/get")]
+async fn http_get_mapping_fn() -> &'static str {
+    "Hello, http.get mapping!"
+}
+
+#[post_mapping(namespace = "/http", path = "/post", data = "<form>")]
+async fn http_post_mapping_fn(Form(form): Form<Account>) -> &'static str {
+    println!("http_post_mapping_fn: form.account: {}", form.account);
+
+    "Hello, http.post mapping!"
+}
+
+#[put_mapping(namespace = "/http", path = "/put", data = "<form>")]
+async fn http_put_mapping_fn(Form(form): Form<Account>) -> &'static str {
+    println!("http_put_mapping_fn: form.account: {}", form.account);
+
+    "Hello, http.put mapping!"
+}
+
+#[patch_mapping(namespace = "/http", path = "/patch", data = "<form>")]
+async fn http_patch_mapping_fn(Form(form): Form<Account>) -> &'static str {
+    println!("http_patch_mapping_fn: form.account: {}", form.account);
+
+    "Hello, http.patch mapping!"
+}
+
+#[delete_mapping(namespace = "/http", path = "/delete", data = "<form>")]
+async fn http_delete_mapping_fn(Form(form): Form<Account>) -> &'static str {
+    println!("http_delete_mapping_fn: form.account: {}", form.account);
+
+    "Hello, http.delete mapping!"
+}
+
+#[head_mapping(namespace = "/http", path = "/head")]
+async fn http_head_mapping_fn() {}
+
+// ---------------------------------------------------------------- #[${http_method}_mapping("/path")]
+
+#[get_mapping("/path/get")]
+async fn http_default_path_get_mapping_fn() -> &'static str {
+    "Hello, http.default.path.get mapping!"
+}
+
+#[post_mapping("/path/post")]
+async fn http_default_path_post_mapping_fn() -> &'static str {
+    "Hello, http.default.path.post mapping!"
+}
+
+// ---------------------------------------------------------------- <param> templates
+
+#[get_mapping(namespace = "/users", path = "/<id>")]
+async fn user_fn(Path(id): Path<u64>) -> String {
+    format!("Hello, user {}!", id)
+}
+
+#[get_mapping(namespace = "/files", path = "/<path..>")]
+async fn file_fn(Path(path): Path<String>) -> String {
+    format!("Hello, file {}!", path)
+}
+
+// ----------------------------------------------------------------
+
+pub fn __trigger_init__() {}
@@ -0,0 +1,37 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/poem/hello-poem/main
+
+// ----------------------------------------------------------------
+
+use poem::listener::TcpListener;
+use poem::Server;
+
+use autortr_poem::prelude::*;
+use hello_poem::__trigger_init__;
+
+// ----------------------------------------------------------------
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    __trigger_init__();
+    let app = app();
+
+    Server::new(TcpListener::bind("127.0.0.1:8000"))
+        .run(app)
+        .await
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/poem/hello-poem/tests/app
+
+// ----------------------------------------------------------------
+
+use poem::http::{Method, StatusCode};
+use poem::test::TestClient;
+
+use autortr_poem::prelude::*;
+
+// ----------------------------------------------------------------
+
+async fn send(method: Method, uri: &str, form: Option<&str>) -> (StatusCode, String) {
+    hello_poem::__trigger_init__();
+    let client = TestClient::new(build_with(snapshot_route_mappings(), snapshot_catchers()));
+
+    let request = client.request(method, uri);
+    let request = match form {
+        Some(form) => request
+            .content_type("application/x-www-form-urlencoded")
+            .body(form.to_string()),
+        None => request,
+    };
+
+    let response = request.send().await;
+    let status = response.0.status();
+    let body = response.0.into_body().into_string().await.unwrap();
+
+    (status, body)
+}
+
+// ----------------------------------------------------------------
+
+#[tokio::test]
+async fn request_mapping_routes() {
+    let cases = [
+        (Method::GET, "/get", "Hello, get!"),
+        (Method::POST, "/post", "Hello, post!"),
+        (Method::PUT, "/put", "Hello, put!"),
+        (Method::PATCH, "/patch", "Hello, patch!"),
+        (Method::DELETE, "/delete", "Hello, delete!"),
+        (Method::GET, "/poem/namespace", "Hello, namespace!"),
+        (Method::GET, "/http/get", "Hello, http.get mapping!"),
+        (
+            Method::GET,
+            "/path/get",
+            "Hello, http.default.path.get mapping!",
+        ),
+        (
+            Method::POST,
+            "/path/post",
+            "Hello, http.default.path.post mapping!",
+        ),
+    ];
+
+    for (method, uri, expected) in cases {
+        assert_eq!(
+            send(method.clone(), uri, None).await,
+            (StatusCode::OK, expected.to_string()),
+            "{} {}",
+            method,
+            uri
+        );
+    }
+
+    assert_eq!(send(Method::HEAD, "/head", None).await.0, StatusCode::OK);
+    assert_eq!(
+        send(Method::HEAD, "/http/head", None).await.0,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn form_data_routes() {
+    let cases = [
+        (Method::POST, "/poem/data", "Hello, data!"),
+        (Method::POST, "/http/post", "Hello, http.post mapping!"),
+        (Method::PUT, "/http/put", "Hello, http.put mapping!"),
+        (Method::PATCH, "/http/patch", "Hello, http.patch mapping!"),
+        (
+            Method::DELETE,
+            "/http/delete",
+            "Hello, http.delete mapping!",
+        ),
+    ];
+
+    for (method, uri, expected) in cases {
+        assert_eq!(
+            send(method.clone(), uri, Some("account=photowey")).await,
+            (StatusCode::OK, expected.to_string()),
+            "{} {}",
+            method,
+            uri
+        );
+    }
+}
+
+#[tokio::test]
+async fn path_parameter_routes() {
+    assert_eq!(
+        send(Method::GET, "/users/42", None).await,
+        (StatusCode::OK, "Hello, user 42!".to_string())
+    );
+    assert_eq!(
+        send(Method::GET, "/files/a/b.txt", None).await,
+        (StatusCode::OK, "Hello, file a/b.txt!".to_string())
+    );
+    assert_eq!(
+        send(Method::GET, "/users/x", None).await.0,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn namespace_catchers() {
+    // The root catcher applies to the whole app.
+    assert_eq!(
+        send(Method::GET, "/missing", None).await,
+        (StatusCode::NOT_FOUND, "Nothing here!".to_string())
+    );
+    // The `/poem` catcher wins within its namespace.
+    assert_eq!(
+        send(Method::GET, "/poem/missing", None).await,
+        (StatusCode::NOT_FOUND, "poem: 404".to_string())
+    );
+    assert_eq!(
+        send(Method::POST, "/poem/data", Some("unknown=1")).await.1,
+        "poem: 400"
+    );
+}
+
+#[test]
+fn registered_paths_are_converted() {
+    hello_poem::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let user = mappings.iter().find(|m| m.function == "user_fn").unwrap();
+    let file = mappings.iter().find(|m| m.function == "file_fn").unwrap();
+
+    assert_eq!(
+        (user.path.as_str(), user.route_path.as_str()),
+        ("/<id>", "/:id")
+    );
+    assert_eq!(
+        (file.path.as_str(), file.route_path.as_str()),
+        ("/<path..>", "/*path")
+    );
+    assert!(detect_conflicts(&route_descriptors(&mappings)).is_empty());
+}
@@ -18,8 +18,14 @@
 
 // ----------------------------------------------------------------
 
+use std::path::PathBuf;
+
 use rocket::form::Form;
-use rocket::FromForm;
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::{FromForm, Request, Response};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 use autortr_rocket::prelude::*;
 
@@ -159,6 +165,505 @@ fn http_default_path_head_mapping_fn() -> &'static str {
     "Hello, http.default.path.head mapping!"
 }
 
+// ---------------------------------------------------------------- v0.3.0 Spring-style patterns
+
+#[get_mapping(namespace = "/spring", path = "/users/{id}")]
+fn spring_user_fn(id: u64) -> String {
+    format!("Hello, spring.user {}!", id)
+}
+
+#[get_mapping(namespace = "/spring", path = "/users/*/posts")]
+fn spring_posts_fn() -> &'static str {
+    "Hello, spring.posts!"
+}
+
+#[get_mapping(namespace = "/spring", path = "/static/**")]
+fn spring_static_fn(path: PathBuf) -> String {
+    format!("Hello, spring.static {}!", path.display())
+}
+
+// ---------------------------------------------------------------- v0.3.0 Regex-constrained path variables
+
+#[get_mapping(namespace = "/orders", path = r"/{id:\d+}")]
+fn order_fn(id: u64) -> String {
+    format!("Hello, order {}!", id)
+}
+
+// Non-numeric ids are forwarded here.
+#[get_mapping(namespace = "/orders", path = "/<code>", rank = 2)]
+fn order_code_fn(code: &str) -> String {
+    format!("Hello, order code {}!", code)
+}
+
+#[get_mapping(namespace = "/files", path = r"/{name:[a-z0-9_-]+\.pdf}")]
+fn pdf_fn(name: &str) -> String {
+    format!("Hello, pdf {}!", name)
+}
+
+// ---------------------------------------------------------------- v0.3.0 Dynamic namespace segments
+
+#[get_mapping(namespace = "/tenants/<tenant>", path = "/users/<id>")]
+fn tenant_user_fn(tenant: &str, id: u64) -> String {
+    format!("Hello, {}.user {}!", tenant, id)
+}
+
+#[get_mapping(namespace = "/tenants/{tenant}/admin", path = "/")]
+fn tenant_admin_fn(tenant: &str) -> String {
+    format!("Hello, {}.admin!", tenant)
+}
+
+// ---------------------------------------------------------------- v0.3.0 Signature-inferred bindings
+
+// `data = "<form>"` is inferred from `Form<Account>`.
+#[post_mapping(namespace = "/infer", path = "/post")]
+fn infer_post_fn(form: Form<Account>) -> String {
+    format!("Hello, infer.post {}!", form.account)
+}
+
+// `/users/<id>?<verbose>`, `data = "<account>"`
+#[put_mapping(namespace = "/infer", path = "/users")]
+fn infer_put_fn(
+    #[path_var] id: u64,
+    #[query_param] verbose: Option<bool>,
+    #[request_body] account: Form<Account>,
+) -> String {
+    match verbose {
+        Some(true) => format!("Hello, infer.put {} {}!", id, account.account),
+        _ => format!("Hello, infer.put {}!", id),
+    }
+}
+
+// ---------------------------------------------------------------- v0.3.0 REST envelope
+
+#[derive(Serialize)]
+struct Profile {
+    id: u64,
+    name: String,
+}
+
+enum ProfileError {
+    NotFound(u64),
+}
+
+impl IntoRestError for ProfileError {
+    fn into_rest_error(self) -> RestError {
+        match self {
+            ProfileError::NotFound(id) => {
+                RestError::new(Status::NotFound, format!("profile {} not found", id)).code(40401)
+            }
+        }
+    }
+}
+
+#[get_mapping(namespace = "/rest", path = "/profiles/<id>", rest = true)]
+fn rest_profile_fn(id: u64) -> Result<Profile, ProfileError> {
+    match id {
+        0 => Err(ProfileError::NotFound(id)),
+        _ => Ok(Profile {
+            id,
+            name: "rocket".to_string(),
+        }),
+    }
+}
+
+#[rest_controller(namespace = "/rest")]
+mod rest_api {
+    use super::*;
+
+    #[get_mapping("/numbers")]
+    fn rest_numbers_fn() -> Vec<u32> {
+        vec![1, 2, 3]
+    }
+
+    #[post_mapping("/echo")]
+    async fn rest_echo_fn(form: Form<Account>) -> String {
+        form.account.clone()
+    }
+}
+
+// ---------------------------------------------------------------- v0.3.0 Exception handlers
+
+pub struct LookupError(String);
+
+#[exception_handler]
+fn on_lookup(error: LookupError) -> (Status, String) {
+    (Status::NotFound, format!("lookup: {}", error.0))
+}
+
+#[exception_handler(namespace = "/errors/admin")]
+fn on_admin_lookup(error: LookupError) -> (Status, String) {
+    (Status::Forbidden, format!("admin lookup: {}", error.0))
+}
+
+#[get_mapping(namespace = "/errors", path = "/lookup/<name>", except = true)]
+fn errors_lookup_fn(name: &str) -> Result<&'static str, LookupError> {
+    match name {
+        "rocket" => Ok("Hello, rocket!"),
+        _ => Err(LookupError(name.to_string())),
+    }
+}
+
+#[get_mapping(namespace = "/errors/admin", path = "/lookup/<name>", except = true)]
+async fn errors_admin_lookup_fn(name: &str) -> Result<&'static str, LookupError> {
+    Err(LookupError(name.to_string()))
+}
+
+// Responders without a handler respond themselves.
+#[get_mapping(namespace = "/errors", path = "/status", except = true)]
+fn errors_status_fn() -> Result<&'static str, Status> {
+    Err(Status::Conflict)
+}
+
+// ---------------------------------------------------------------- v0.3.0 Body validation
+
+#[derive(Debug, FromForm, Validate)]
+struct Signup {
+    #[validate(length(min = 3, message = "too short"))]
+    account: String,
+    #[validate(email)]
+    email: String,
+}
+
+#[post_mapping(namespace = "/valid", path = "/signup", validate = true)]
+fn valid_signup_fn(form: Form<Signup>) -> String {
+    format!("Hello, valid.signup {}!", form.account)
+}
+
+#[put_mapping(namespace = "/valid", path = "/signup")]
+async fn valid_update_fn(#[valid] form: Form<Signup>) -> String {
+    format!("Hello, valid.update {}!", form.email)
+}
+
+// ---------------------------------------------------------------- v0.3.0 Interceptors
+
+#[interceptor(order = 10, include = "/api/**", exclude = "/api/public/**")]
+struct ApiKeyInterceptor;
+
+impl Interceptor for ApiKeyInterceptor {
+    fn pre_handle(&self, request: &Request<'_>, _: &RouteMapping) -> PreHandle {
+        match request.headers().get_one("X-Api-Key") {
+            Some(_) => PreHandle::Continue,
+            None => PreHandle::Respond(InterceptorResponse::new(Status::Unauthorized)),
+        }
+    }
+
+    fn post_handle(&self, _: &Request<'_>, _: &RouteMapping, response: &mut Response<'_>) {
+        response.adjoin_header(Header::new("X-Interceptors", "api-key"));
+    }
+}
+
+#[interceptor(order = 20, tags = ["audit"])]
+struct AuditInterceptor;
+
+impl Interceptor for AuditInterceptor {
+    fn post_handle(&self, _: &Request<'_>, mapping: &RouteMapping, response: &mut Response<'_>) {
+        response.adjoin_header(Header::new("X-Interceptors", "audit"));
+        response.set_header(Header::new("X-Audit", mapping.function.clone()));
+    }
+}
+
+#[get_mapping(namespace = "/api", path = "/items", tags = ["audit"])]
+fn api_items_fn() -> &'static str {
+    "Hello, api.items!"
+}
+
+#[get_mapping(namespace = "/api/public", path = "/ping")]
+fn api_public_ping_fn() -> &'static str {
+    "Hello, api.public.ping!"
+}
+
+// ---------------------------------------------------------------- v0.3.0 Request guards
+
+pub struct ApiKey;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKey {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one("X-Api-Key") {
+            Some(_) => Outcome::Success(ApiKey),
+            None => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+pub struct AdminOnly;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminOnly {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one("X-Role") {
+            Some("admin") => Outcome::Success(AdminOnly),
+            _ => Outcome::Error((Status::Forbidden, ())),
+        }
+    }
+}
+
+#[get_mapping(namespace = "/guarded", path = "/key", guards = [ApiKey])]
+fn guarded_key_fn() -> &'static str {
+    "Hello, guarded.key!"
+}
+
+#[controller(namespace = "/guarded/admin", guards = [ApiKey])]
+mod guarded_admin {
+    use super::*;
+
+    #[get_mapping("/users")]
+    fn guarded_admin_users_fn() -> &'static str {
+        "Hello, guarded.admin.users!"
+    }
+
+    #[delete_mapping(path = "/users/<id>", guards = [AdminOnly])]
+    fn guarded_admin_delete_fn(id: u32) -> String {
+        format!("Hello, guarded.admin.delete {}!", id)
+    }
+}
+
+#[rest_controller(namespace = "/guarded/rest", guards = [AdminOnly])]
+mod guarded_rest {
+    use super::*;
+
+    #[get_mapping("/numbers")]
+    fn guarded_rest_numbers_fn() -> Vec<u32> {
+        vec![1, 2, 3]
+    }
+}
+
+// ---------------------------------------------------------------- v0.3.0 Pre-authorization
+
+#[principal]
+pub struct HeaderUser {
+    roles: Vec<String>,
+    permissions: Vec<String>,
+}
+
+impl Principal for HeaderUser {
+    fn roles(&self) -> Vec<String> {
+        self.roles.clone()
+    }
+
+    fn permissions(&self) -> Vec<String> {
+        self.permissions.clone()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HeaderUser {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let list = |name: &str| -> Vec<String> {
+            request
+                .headers()
+                .get_one(name)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(|item| item.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        match request.headers().get_one("X-User") {
+            Some(_) => Outcome::Success(HeaderUser {
+                roles: list("X-Roles"),
+                permissions: list("X-Permissions"),
+            }),
+            None => Outcome::Forward(Status::Unauthorized),
+        }
+    }
+}
+
+#[pre_authorize("has_role('ADMIN') or has_permission('orders:write')")]
+#[post_mapping(namespace = "/secured", path = "/orders")]
+fn secured_orders_fn() -> &'static str {
+    "Hello, secured.orders!"
+}
+
+#[get_mapping(namespace = "/secured", path = "/reports")]
+#[pre_authorize("is_authenticated()")]
+fn secured_reports_fn() -> &'static str {
+    "Hello, secured.reports!"
+}
+
+#[delete_mapping(
+    namespace = "/secured",
+    path = "/orders/<id>",
+    pre_authorize = "has_role('ADMIN') and not has_permission('orders:readonly')"
+)]
+fn secured_delete_fn(id: u32) -> String {
+    format!("Hello, secured.delete {}!", id)
+}
+
+// ---------------------------------------------------------------- v0.3.0 JWT authentication
+
+// The JWT verification is configured by `tests/authentication.rs`, with a key pair generated
+// for each run: no signing key is published with the tester.
+
+#[derive(Deserialize)]
+pub struct JwtProfile {
+    sub: String,
+    name: String,
+}
+
+#[get_mapping(namespace = "/jwt", path = "/ping", authenticated = true)]
+fn jwt_ping_fn() -> &'static str {
+    "Hello, jwt.ping!"
+}
+
+#[controller(namespace = "/jwt", authenticated = JwtProfile)]
+mod jwt {
+    use super::*;
+
+    // Takes the controller's `Authenticated` guard itself, which isn't injected again.
+    #[get_mapping("/me")]
+    fn jwt_me_fn(profile: Authenticated<JwtProfile>) -> String {
+        format!("Hello, jwt.me {} ({})!", profile.name, profile.sub)
+    }
+}
+
+// ---------------------------------------------------------------- v0.3.0 Webhooks
+
+#[derive(Deserialize)]
+struct GithubEvent {
+    action: String,
+}
+
+#[webhook_mapping(
+    path = "/hooks/github",
+    scheme = "github-sha256",
+    secret = "${hooks.github.secret}"
+)]
+fn github_hook_fn(payload: WebhookPayload) -> Result<String, Status> {
+    let event: GithubEvent = payload.json().map_err(|_| Status::BadRequest)?;
+    Ok(format!("Hello, hooks.github {}!", event.action))
+}
+
+#[webhook_mapping(
+    path = "/hooks/stripe",
+    scheme = "stripe",
+    secret = "${hooks.stripe.secret}"
+)]
+fn stripe_hook_fn(payload: WebhookPayload) -> String {
+    format!(
+        "Hello, hooks.stripe {}!",
+        payload.timestamp.unwrap_or_default()
+    )
+}
+
+#[webhook_mapping(
+    namespace = "/hooks",
+    path = "/partner",
+    scheme = "hmac-sha256",
+    secret = "${hooks.partner.secret}"
+)]
+async fn partner_hook_fn(payload: WebhookPayload) -> String {
+    format!(
+        "Hello, hooks.partner {}!",
+        payload.text().unwrap_or_default()
+    )
+}
+
+#[webhook_mapping(
+    path = "/hooks/legacy",
+    scheme = "api-key",
+    secret = "${hooks.legacy.secret}"
+)]
+fn legacy_hook_fn(payload: WebhookPayload) -> String {
+    format!("Hello, hooks.legacy {}!", payload.body.len())
+}
+
+// ---------------------------------------------------------------- v0.3.0 CORS
+
+#[get_mapping(namespace = "/cors", path = "/catalog", cors = "public")]
+fn cors_catalog_fn() -> &'static str {
+    "Hello, cors.catalog!"
+}
+
+#[controller(namespace = "/cors/partner", cors = "partner")]
+mod cors_partner {
+    use super::*;
+
+    #[get_mapping("/orders/<id>")]
+    fn cors_partner_order_fn(id: u32) -> String {
+        format!("Hello, cors.partner.order {}!", id)
+    }
+
+    #[delete_mapping("/orders/<id>")]
+    fn cors_partner_delete_fn(id: u32) -> String {
+        format!("Hello, cors.partner.delete {}!", id)
+    }
+
+    #[post_mapping("/orders")]
+    fn cors_partner_create_fn() -> &'static str {
+        "Hello, cors.partner.create!"
+    }
+}
+
+// Without a policy: not for browsers, still listed in `Allow`.
+#[put_mapping(namespace = "/cors/partner", path = "/orders/<id>")]
+fn cors_partner_update_fn(id: u32) -> String {
+    format!("Hello, cors.partner.update {}!", id)
+}
+
+// ---------------------------------------------------------------- v0.3.0 Rate limiting
+
+pub struct TenantKey;
+
+impl RateLimitKey for TenantKey {
+    fn key(request: &Request<'_>) -> Option<String> {
+        request
+            .headers()
+            .get_one("X-Tenant")
+            .map(|tenant| format!("tenant:{}", tenant))
+    }
+}
+
+#[get_mapping(namespace = "/limits", path = "/burst", rate_limit = "3/minute")]
+fn limits_burst_fn() -> &'static str {
+    "Hello, limits.burst!"
+}
+
+#[get_mapping(
+    namespace = "/limits",
+    path = "/keys",
+    rate_limit = "2/minute",
+    rate_limit_key = "api-key"
+)]
+fn limits_keys_fn() -> &'static str {
+    "Hello, limits.keys!"
+}
+
+// Requests rejected by `AdminOnly` don't count.
+#[get_mapping(
+    namespace = "/limits",
+    path = "/admin",
+    guards = [AdminOnly],
+    rate_limit = "1/minute"
+)]
+fn limits_admin_fn() -> &'static str {
+    "Hello, limits.admin!"
+}
+
+#[controller(namespace = "/limits/tenants", rate_limit = "2/hour", rate_limit_key = TenantKey)]
+mod limits_tenants {
+    use super::*;
+
+    #[get_mapping("/reports")]
+    fn limits_tenant_reports_fn() -> &'static str {
+        "Hello, limits.tenants.reports!"
+    }
+
+    #[get_mapping(path = "/exports", rate_limit = "1/day")]
+    fn limits_tenant_exports_fn() -> &'static str {
+        "Hello, limits.tenants.exports!"
+    }
+}
+
 // ----------------------------------------------------------------
 
 pub fn __trigger_init__() {}
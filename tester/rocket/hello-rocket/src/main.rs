@@ -44,7 +44,7 @@ fn configure() -> AdHoc {
 // ----------------------------------------------------------------
 
 #[rocket::main]
-async fn main() -> Result<(), rocket::Error> {
+async fn main() -> Result<(), Box<rocket::Error>> {
     __trigger_init__();
     let app = app();
     let _ = app.attach(configure()).launch().await?;
@@ -0,0 +1,179 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/authentication
+
+// ----------------------------------------------------------------
+
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use p256::pkcs8::{EncodePrivateKey, LineEnding};
+use rand_core::OsRng;
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use serde_json::{json, Value};
+
+use autortr_rocket::prelude::{configure_jwt, snapshot_route_mappings, JwtAuth};
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+/// Generates the signing key of the run and configures its JWKS, as an app would.
+fn configure() -> &'static EncodingKey {
+    static SIGNING_KEY: OnceLock<EncodingKey> = OnceLock::new();
+
+    SIGNING_KEY.get_or_init(|| {
+        hello_rocket::__trigger_init__();
+
+        let key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let jwks = json!({ "keys": [{
+            "kty": "RSA",
+            "kid": "hello-rsa",
+            "use": "sig",
+            "alg": "RS256",
+            "n": URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+            "e": URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+        }] });
+        let path = concat!(env!("CARGO_TARGET_TMPDIR"), "/hello-rocket-jwks.json");
+        std::fs::write(path, jwks.to_string()).unwrap();
+
+        configure_jwt(
+            JwtAuth::jwks_file(path)
+                .issuer("https://id.hello-rocket.local")
+                .audience("hello-rocket"),
+        );
+
+        let pem = key.to_pkcs1_pem(LineEnding::LF).unwrap();
+        EncodingKey::from_rsa_pem(pem.as_bytes()).unwrap()
+    })
+}
+
+fn claims() -> Value {
+    json!({
+        "sub": "photowey",
+        "name": "Photowey",
+        "iss": "https://id.hello-rocket.local",
+        "aud": "hello-rocket",
+        "exp": get_current_timestamp() + 600,
+    })
+}
+
+fn sign(claims: &Value) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some("hello-rsa".to_string());
+
+    encode(&header, claims, configure()).unwrap()
+}
+
+fn bearer(token: &str) -> String {
+    format!("Bearer {}", token)
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn valid_tokens_authenticate() {
+    configure();
+
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&claims()))))
+        .expect_ok()
+        .expect_body("Hello, jwt.ping!");
+
+    perform(get("/jwt/me").header("Authorization", bearer(&sign(&claims()))))
+        .expect_ok()
+        .expect_body("Hello, jwt.me Photowey (photowey)!");
+}
+
+#[test]
+fn missing_tokens_are_unauthorized() {
+    configure();
+
+    perform(get("/jwt/ping")).expect_status(401);
+    perform(get("/jwt/ping").header("Authorization", "Basic cm9ja2V0")).expect_status(401);
+    perform(get("/jwt/ping").header("Authorization", "Bearer not-a-token")).expect_status(401);
+}
+
+#[test]
+fn time_claims_are_checked() {
+    configure();
+
+    let mut expired = claims();
+    expired["exp"] = json!(get_current_timestamp() - 3600);
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&expired)))).expect_status(401);
+
+    let mut early = claims();
+    early["nbf"] = json!(get_current_timestamp() + 3600);
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&early)))).expect_status(401);
+
+    let mut endless = claims();
+    endless.as_object_mut().unwrap().remove("exp");
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&endless)))).expect_status(401);
+}
+
+#[test]
+fn issuer_and_audience_are_checked() {
+    configure();
+
+    let mut foreign = claims();
+    foreign["iss"] = json!("https://id.elsewhere.local");
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&foreign)))).expect_status(401);
+
+    let mut misdirected = claims();
+    misdirected["aud"] = json!("elsewhere");
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&misdirected))))
+        .expect_status(401);
+
+    let mut anonymous = claims();
+    anonymous.as_object_mut().unwrap().remove("aud");
+    perform(get("/jwt/ping").header("Authorization", bearer(&sign(&anonymous)))).expect_status(401);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    configure();
+
+    let unknown = p256::SecretKey::random(&mut OsRng)
+        .to_pkcs8_pem(LineEnding::LF)
+        .unwrap();
+    let token = encode(
+        &Header::new(Algorithm::ES256),
+        &claims(),
+        &EncodingKey::from_ec_pem(unknown.as_bytes()).unwrap(),
+    )
+    .unwrap();
+
+    perform(get("/jwt/ping").header("Authorization", bearer(&token))).expect_status(401);
+}
+
+#[test]
+fn authenticated_guards_are_recorded() {
+    configure();
+
+    let guards = |function: &str| {
+        snapshot_route_mappings()
+            .into_iter()
+            .find(|mapping| mapping.function == function)
+            .map(|mapping| mapping.guards)
+            .unwrap()
+    };
+
+    assert_eq!(guards("jwt_ping_fn"), vec!["Authenticated"]);
+    assert_eq!(guards("jwt_me_fn"), vec!["Authenticated<JwtProfile>"]);
+}
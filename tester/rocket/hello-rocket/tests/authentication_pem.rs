@@ -0,0 +1,91 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/authentication_pem
+
+// ----------------------------------------------------------------
+
+use std::sync::OnceLock;
+
+use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use p256::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+use p256::SecretKey;
+use rand_core::OsRng;
+use serde_json::json;
+
+use autortr_rocket::prelude::{configure_jwt, JwtAuth};
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+/// Generates the key pair of the run and configures its public PEM, returning the private one.
+fn configure() -> &'static str {
+    static PRIVATE_PEM: OnceLock<String> = OnceLock::new();
+
+    PRIVATE_PEM.get_or_init(|| {
+        hello_rocket::__trigger_init__();
+
+        let key = SecretKey::random(&mut OsRng);
+        let public = key.public_key().to_public_key_pem(LineEnding::LF).unwrap();
+
+        // Replaces the JWT verification of `hello_rocket`, for every test of this binary.
+        configure_jwt(JwtAuth::ec_pem(public.as_bytes()).issuer("https://pem.hello-rocket.local"));
+
+        key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string()
+    })
+}
+
+fn sign(algorithm: Algorithm, key: &EncodingKey) -> String {
+    let claims = json!({
+        "sub": "photowey",
+        "iss": "https://pem.hello-rocket.local",
+        "exp": get_current_timestamp() + 600,
+    });
+
+    encode(&Header::new(algorithm), &claims, key).unwrap()
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn configured_pem_keys_authenticate() {
+    let private = configure();
+
+    let token = sign(
+        Algorithm::ES256,
+        &EncodingKey::from_ec_pem(private.as_bytes()).unwrap(),
+    );
+
+    perform(get("/jwt/ping").header("Authorization", format!("Bearer {}", token)))
+        .expect_ok()
+        .expect_body("Hello, jwt.ping!");
+}
+
+#[test]
+fn other_keys_are_rejected() {
+    configure();
+
+    let other = SecretKey::random(&mut OsRng)
+        .to_pkcs8_pem(LineEnding::LF)
+        .unwrap();
+    let token = sign(
+        Algorithm::ES256,
+        &EncodingKey::from_ec_pem(other.as_bytes()).unwrap(),
+    );
+
+    perform(get("/jwt/ping").header("Authorization", format!("Bearer {}", token)))
+        .expect_status(401);
+}
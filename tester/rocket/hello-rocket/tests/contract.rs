@@ -0,0 +1,273 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/contract
+
+// ----------------------------------------------------------------
+
+use rocket::http::{ContentType, Header};
+use rocket::local::blocking::Client;
+use serde_json::{json, Value};
+
+use autortr_rocket::prelude::contract::{ContractFairing, ContractMode};
+use autortr_rocket::prelude::interceptor::InterceptorFairing;
+use autortr_rocket::prelude::{build, snapshot_route_mappings, RocketBackend};
+use autortr_rocket::testing::rocket;
+
+// ----------------------------------------------------------------
+
+fn contract() -> Value {
+    let mut document = ContractFairing::from_registry().document().clone();
+
+    document["paths"]["/rest/profiles/{id}"]["get"]["responses"] = json!({
+        "200": {
+            "description": "OK",
+            "content": { "application/json": { "schema": {
+                "type": "object",
+                "properties": { "data": { "type": "object", "required": ["email"] } }
+            } } }
+        },
+        "4XX": { "description": "Not found" }
+    });
+    document["paths"]["/http/post"]["post"]["requestBody"] = json!({
+        "content": { "application/json": { "schema": {
+            "type": "object",
+            "required": ["account"]
+        } } }
+    });
+
+    document
+}
+
+fn violations(body: &str) -> Vec<String> {
+    let body: Value = serde_json::from_str(body).unwrap();
+
+    serde_json::from_value(body["violations"].clone()).unwrap()
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn violating_requests_are_rejected() {
+    hello_rocket::__trigger_init__();
+
+    let client = Client::tracked(rocket().attach(ContractFairing::new(contract()))).unwrap();
+
+    let response = client.get("/get?foo=1").dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["unknown query parameter `foo`"]
+    );
+
+    let response = client.get("/tenants/acme/users/x").dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["path parameter `id` is not of type `integer`"]
+    );
+
+    let response = client.get("/tenants/acme/users/7").dispatch();
+    assert_eq!(response.status().code, 200);
+}
+
+#[test]
+fn the_registry_document_types_parameters_and_declares_statuses() {
+    hello_rocket::__trigger_init__();
+
+    let fairing = ContractFairing::from_registry().mode(ContractMode::Log);
+    let document = fairing.document().clone();
+    let report = fairing.report();
+    let client = Client::tracked(rocket().attach(fairing)).unwrap();
+
+    let operation = &document["paths"]["/spring/users/{_0}/posts"]["get"];
+    assert_eq!(operation["parameters"][0]["name"], "_0");
+    let operation = &document["paths"]["/infer/users/{id}"]["put"];
+    assert_eq!(operation["parameters"][0]["schema"]["type"], "integer");
+    assert_eq!(operation["parameters"][1]["schema"]["type"], "string");
+    let statuses: Vec<&String> = operation["responses"].as_object().unwrap().keys().collect();
+    assert_eq!(statuses, vec!["2XX", "400", "413", "415", "422"]);
+
+    let response = client.get("/tenants/acme/users/x").dispatch();
+    assert_eq!(response.status().code, 422);
+    let response = client.get("/errors/status").dispatch();
+    assert_eq!(response.status().code, 409);
+    let response = client.get("/spring/users/7/posts").dispatch();
+    assert_eq!(response.status().code, 200);
+
+    let messages: Vec<String> = report
+        .violations()
+        .into_iter()
+        .map(|violation| violation.message)
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "path parameter `id` is not of type `integer`",
+            "status 409 is not documented",
+        ]
+    );
+}
+
+#[test]
+fn violating_responses_are_rejected() {
+    hello_rocket::__trigger_init__();
+
+    let client = Client::tracked(rocket().attach(ContractFairing::new(contract()))).unwrap();
+
+    let response = client.get("/errors/status").dispatch();
+    assert_eq!(response.status().code, 500);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["status 409 is not documented"]
+    );
+
+    let response = client.get("/rest/profiles/7").dispatch();
+    assert_eq!(response.status().code, 500);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["response.data misses the required `email`"]
+    );
+
+    let response = client.get("/rest/profiles/0").dispatch();
+    assert_eq!(response.status().code, 404);
+}
+
+#[test]
+fn violations_are_logged_and_reported() {
+    hello_rocket::__trigger_init__();
+
+    let fairing = ContractFairing::new(contract()).mode(ContractMode::Log);
+    let report = fairing.report();
+    let client = Client::tracked(rocket().attach(fairing)).unwrap();
+
+    let response = client.get("/get?foo=1").dispatch();
+    assert_eq!(response.status().code, 200);
+
+    let violations = report.violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].operation, "get_fn");
+    assert_eq!(violations[0].path, "/get");
+    assert_eq!(violations[0].message, "unknown query parameter `foo`");
+}
+
+#[test]
+fn request_bodies_are_checked_against_their_content_type_and_schema() {
+    hello_rocket::__trigger_init__();
+
+    let client = Client::tracked(rocket().attach(ContractFairing::new(contract()))).unwrap();
+
+    let response = client
+        .post("/http/post")
+        .header(ContentType::Form)
+        .body("account=rocket")
+        .dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["body content type `application/x-www-form-urlencoded` is not documented"]
+    );
+
+    let response = client
+        .post("/http/post")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"rocket"}"#)
+        .dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["body misses the required `account`"]
+    );
+}
+
+#[test]
+fn bodies_longer_than_the_peek_limit_are_reported() {
+    hello_rocket::__trigger_init__();
+
+    let fairing = ContractFairing::new(contract())
+        .mode(ContractMode::Log)
+        .peek_limit(8);
+    let report = fairing.report();
+    let client = Client::tracked(rocket().attach(fairing)).unwrap();
+
+    let response = client
+        .post("/http/post")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"rocket"}"#)
+        .dispatch();
+    assert_ne!(response.status().code, 400);
+
+    let violations = report.violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].operation, "http_post_mapping_fn");
+    assert_eq!(
+        violations[0].message,
+        "body too large to check: longer than 8 bytes"
+    );
+}
+
+#[test]
+fn bodies_longer_than_the_peek_limit_are_rejected() {
+    hello_rocket::__trigger_init__();
+
+    let fairing = ContractFairing::new(contract()).peek_limit(8);
+    let client = Client::tracked(rocket().attach(fairing)).unwrap();
+
+    let response = client
+        .post("/http/post")
+        .header(ContentType::JSON)
+        .body(r#"{"account":"rocket"}"#)
+        .dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(
+        violations(&response.into_string().unwrap()),
+        vec!["body too large to check: longer than 8 bytes"]
+    );
+}
+
+#[test]
+fn rejected_requests_skip_the_post_handles_of_interceptors_attached_ahead() {
+    hello_rocket::__trigger_init__();
+
+    // The router attaches the interceptors ahead of the contract.
+    let client = Client::tracked(rocket().attach(ContractFairing::new(contract()))).unwrap();
+
+    let response = client
+        .get("/api/items?foo=1")
+        .header(Header::new("X-Api-Key", "secret"))
+        .dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(response.headers().get_one("X-Interceptors"), None);
+
+    // Short-circuited by an interceptor, the request never reaches the contract.
+    let response = client.get("/api/items?foo=1").dispatch();
+    assert_eq!(response.status().code, 401);
+}
+
+#[test]
+fn rejected_requests_are_not_intercepted_by_interceptors_attached_behind() {
+    hello_rocket::__trigger_init__();
+
+    let app = build::<RocketBackend>(snapshot_route_mappings(), Vec::new())
+        .attach(ContractFairing::new(contract()))
+        .attach(InterceptorFairing::from_registry());
+    let client = Client::tracked(app).unwrap();
+
+    // Rejected ahead of the `ApiKeyInterceptor`, which would answer a `401`.
+    let response = client.get("/api/items?foo=1").dispatch();
+    assert_eq!(response.status().code, 400);
+    assert_eq!(response.headers().get_one("X-Interceptors"), None);
+}
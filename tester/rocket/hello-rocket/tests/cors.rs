@@ -0,0 +1,174 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/cors
+
+// ----------------------------------------------------------------
+
+use rocket::error::ErrorKind;
+use rocket::local::blocking::Client;
+
+use autortr_rocket::prelude::{build_with, snapshot_route_mappings};
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+const PARTNER: &str = "https://partner.example.com";
+
+fn preflight(uri: &str, origin: &str, method: &str) -> MockRequest {
+    options(uri)
+        .header("Origin", origin)
+        .header("Access-Control-Request-Method", method)
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn public_policies_allow_any_origin() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/cors/catalog").header("Origin", "https://shop.example.com"))
+        .expect_ok()
+        .expect_body("Hello, cors.catalog!")
+        .expect_header("Access-Control-Allow-Origin", "*");
+
+    let actions = perform(get("/cors/catalog")).expect_ok();
+    assert_eq!(actions.header("Access-Control-Allow-Origin"), None);
+    assert_eq!(actions.header("Vary"), None);
+
+    perform(preflight(
+        "/cors/catalog",
+        "https://shop.example.com",
+        "GET",
+    ))
+    .expect_status(204)
+    .expect_header("Access-Control-Allow-Origin", "*")
+    .expect_header("Access-Control-Allow-Methods", "GET")
+    .expect_header("Access-Control-Max-Age", "3600");
+}
+
+#[test]
+fn partner_policies_restrict_origins_methods_and_headers() {
+    hello_rocket::__trigger_init__();
+
+    perform(preflight("/cors/partner/orders/7", PARTNER, "GET").header(
+        "Access-Control-Request-Headers",
+        "content-type, x-request-id",
+    ))
+    .expect_status(204)
+    .expect_header("Access-Control-Allow-Origin", PARTNER)
+    .expect_header("Access-Control-Allow-Methods", "GET")
+    .expect_header("Access-Control-Allow-Headers", "content-type, x-request-id")
+    .expect_header("Access-Control-Allow-Credentials", "true")
+    .expect_header("Access-Control-Max-Age", "600");
+
+    perform(preflight("/cors/partner/orders", PARTNER, "POST"))
+        .expect_status(204)
+        .expect_header("Access-Control-Allow-Methods", "POST");
+
+    perform(preflight("/cors/partner/orders/7", PARTNER, "DELETE")).expect_status(403);
+    perform(preflight(
+        "/cors/partner/orders/7",
+        "https://elsewhere.example.com",
+        "GET",
+    ))
+    .expect_status(403);
+    perform(
+        preflight("/cors/partner/orders/7", PARTNER, "GET")
+            .header("Access-Control-Request-Headers", "X-Tracking"),
+    )
+    .expect_status(403);
+
+    perform(get("/cors/partner/orders/7").header("Origin", PARTNER))
+        .expect_ok()
+        .expect_header("Access-Control-Allow-Origin", PARTNER)
+        .expect_header("Access-Control-Allow-Credentials", "true")
+        .expect_header("Access-Control-Expose-Headers", "X-Request-Id")
+        .expect_header("Vary", "Origin");
+
+    let actions =
+        perform(get("/cors/partner/orders/7").header("Origin", "https://elsewhere.example.com"))
+            .expect_ok()
+            .expect_header("Vary", "Origin");
+    assert_eq!(actions.header("Access-Control-Allow-Origin"), None);
+
+    perform(get("/cors/partner/orders/7"))
+        .expect_ok()
+        .expect_header("Vary", "Origin");
+}
+
+#[test]
+fn plain_options_requests_list_the_methods() {
+    hello_rocket::__trigger_init__();
+
+    // `PUT` has no policy, and no preflight would allow it.
+    perform(options("/cors/partner/orders/7"))
+        .expect_status(204)
+        .expect_header("Allow", "GET, DELETE, PUT");
+    perform(preflight("/cors/partner/orders/7", PARTNER, "PUT")).expect_status(403);
+}
+
+#[test]
+fn policies_are_recorded() {
+    hello_rocket::__trigger_init__();
+
+    let mapping = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == "cors_partner_delete_fn")
+        .unwrap();
+
+    assert_eq!(mapping.cors.as_deref(), Some("partner"));
+    assert_eq!(
+        mapping.descriptors()[0]
+            .metadata
+            .get("cors")
+            .map(String::as_str),
+        Some("partner")
+    );
+}
+
+#[test]
+fn unknown_policies_fail_the_ignition() {
+    hello_rocket::__trigger_init__();
+
+    let mut mapping = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == "cors_catalog_fn")
+        .unwrap();
+    mapping.cors = Some("missing".to_string());
+
+    let error = Client::tracked(build_with(vec![mapping])).err().unwrap();
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+}
+
+#[test]
+fn credentials_from_any_origin_fail_the_ignition() {
+    hello_rocket::__trigger_init__();
+
+    let mut mapping = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == "cors_catalog_fn")
+        .unwrap();
+    mapping.cors = Some("anyone".to_string());
+
+    let figment = rocket::Config::figment()
+        .merge(("cors.anyone.allowed_origins", ["*"]))
+        .merge(("cors.anyone.allow_credentials", true));
+    let app = build_with(vec![mapping]).configure(figment);
+
+    let error = Client::tracked(app).err().unwrap();
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+}
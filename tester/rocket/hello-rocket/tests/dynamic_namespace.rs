@@ -0,0 +1,63 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/dynamic_namespace
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::*;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn dynamic_namespaces_are_folded() {
+    hello_rocket::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let user = mappings
+        .iter()
+        .find(|m| m.function == "tenant_user_fn")
+        .unwrap();
+
+    assert_eq!(user.namespace, "/tenants");
+    assert_eq!(user.dynamic_namespace, "/<tenant>");
+    assert_eq!(user.path, "/<tenant>/users/<id>");
+    assert_eq!(user.declared_namespace(), "/tenants/<tenant>");
+    assert_eq!(user.full_path(), "/tenants/<tenant>/users/<id>");
+
+    let admin = mappings
+        .iter()
+        .find(|m| m.function == "tenant_admin_fn")
+        .unwrap();
+
+    assert_eq!(admin.namespace, "/tenants");
+    assert_eq!(admin.dynamic_namespace, "/<tenant>/admin");
+    assert_eq!(admin.path, "/<tenant>/admin");
+}
+
+#[test]
+fn dynamic_namespaces_are_routed() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/tenants/acme/users/7"))
+        .expect_ok()
+        .expect_body("Hello, acme.user 7!");
+
+    perform(get("/tenants/acme/admin"))
+        .expect_ok()
+        .expect_body("Hello, acme.admin!");
+}
@@ -0,0 +1,105 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/exception_handler
+
+// ----------------------------------------------------------------
+
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::Debug;
+
+use autortr_rocket::prelude::*;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+struct UnmappedError;
+
+#[get_mapping(namespace = "/errors", path = "/unmapped", except = true)]
+fn errors_unmapped_fn(_: Probe) -> Result<&'static str, UnmappedError> {
+    Err(UnmappedError)
+}
+
+struct Probe;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Probe {
+    type Error = ();
+
+    async fn from_request(_: &'r Request<'_>) -> request::Outcome<Self, ()> {
+        request::Outcome::Success(Probe)
+    }
+}
+
+// Without `except`, the signature (and its non-`Send` error) is left to Rocket.
+#[get_mapping(namespace = "/errors", path = "/probe")]
+fn errors_probe_fn(_probe: Probe) -> Result<String, Debug<Box<dyn std::error::Error>>> {
+    Err(Debug("probe".into()))
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn exception_handlers_are_registered() {
+    hello_rocket::__trigger_init__();
+
+    let handlers = snapshot_exception_handlers();
+    let lookup = handlers.iter().find(|h| h.function == "on_lookup").unwrap();
+    let admin = handlers
+        .iter()
+        .find(|h| h.function == "on_admin_lookup")
+        .unwrap();
+
+    assert_eq!(lookup.namespace, "/");
+    assert_eq!(admin.namespace, "/errors/admin");
+    assert_eq!(lookup.type_id, admin.type_id);
+    assert!(lookup.type_name.ends_with("LookupError"));
+}
+
+#[test]
+fn errors_are_converted_by_the_most_specific_handler() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/errors/lookup/rocket"))
+        .expect_ok()
+        .expect_body("Hello, rocket!");
+
+    perform(get("/errors/lookup/poem"))
+        .expect_status(404)
+        .expect_body("lookup: poem");
+
+    perform(get("/errors/admin/lookup/poem"))
+        .expect_status(403)
+        .expect_body("admin lookup: poem");
+}
+
+#[test]
+fn unmapped_errors_fall_back() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/errors/status")).expect_status(409);
+
+    perform(get("/errors/unmapped"))
+        .expect_status(500)
+        .expect_body("Internal Server Error");
+}
+
+#[test]
+fn handlers_without_except_are_left_untouched() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/errors/probe")).expect_status(500);
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/fuzz
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::{snapshot_route_mappings, RouteMapping};
+use autortr_rocket::testing::fuzz::{replay, smoke_fuzz, smoke_fuzz_with, FuzzConfig};
+
+// ----------------------------------------------------------------
+
+#[test]
+fn smoke_fuzz_registered_routes() {
+    hello_rocket::__trigger_init__();
+
+    smoke_fuzz().assert_no_server_errors();
+}
+
+#[test]
+fn smoke_fuzz_is_reproducible() {
+    hello_rocket::__trigger_init__();
+
+    let http = |mapping: &RouteMapping| mapping.namespace == "/http";
+    let config = FuzzConfig::new(20240701).iterations(6);
+    let first = smoke_fuzz_with(config.clone(), http);
+    let second = smoke_fuzz_with(config.clone(), http);
+
+    assert_eq!(first.cases, 36);
+    assert_eq!(first.requests, second.requests);
+    assert_eq!(first.failures.len(), second.failures.len());
+
+    // each case is regenerated from its mapping, iteration and case seed
+    let case = &first.requests[7];
+    let mapping = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == case.function)
+        .unwrap();
+    assert_eq!(&replay(&mapping, case.iteration, case.seed, &config), case);
+
+    let other = smoke_fuzz_with(FuzzConfig::new(20240702).iterations(6), http);
+    assert_ne!(first.requests, other.requests);
+}
@@ -0,0 +1,70 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/interceptor
+
+// ----------------------------------------------------------------
+
+use rocket::http::Header;
+
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn pre_handle_short_circuits_the_request() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/api/items"))
+        .expect_status(401)
+        .expect_body("Unauthorized");
+}
+
+#[test]
+fn post_handle_runs_in_reverse_order() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/api/items").header("X-Api-Key", "secret"))
+        .expect_ok()
+        .expect_body("Hello, api.items!")
+        .expect_header("X-Audit", "api_items_fn");
+
+    let client = test_client();
+    let response = client
+        .get("/api/items")
+        .header(Header::new("X-Api-Key", "secret"))
+        .dispatch();
+    let applied: Vec<&str> = response.headers().get("X-Interceptors").collect();
+    assert_eq!(applied, vec!["audit", "api-key"]);
+}
+
+#[test]
+fn excluded_paths_are_not_intercepted() {
+    hello_rocket::__trigger_init__();
+
+    let actions = perform(get("/api/public/ping"))
+        .expect_ok()
+        .expect_body("Hello, api.public.ping!");
+    assert_eq!(actions.header("X-Interceptors"), None);
+}
+
+#[test]
+fn unmatched_routes_are_not_intercepted() {
+    hello_rocket::__trigger_init__();
+
+    let actions = perform(get("/get")).expect_ok();
+    assert_eq!(actions.header("X-Interceptors"), None);
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/path_constraint
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::*;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn constrained_variables_are_recorded() {
+    hello_rocket::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let order = mappings.iter().find(|m| m.function == "order_fn").unwrap();
+
+    assert_eq!(order.pattern, r"/{id:\d+}");
+    assert_eq!(order.path, "/<id>");
+}
+
+#[test]
+fn matching_segments_reach_the_handler() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/orders/42"))
+        .expect_ok()
+        .expect_body("Hello, order 42!");
+
+    perform(get("/files/report_2024.pdf"))
+        .expect_ok()
+        .expect_body("Hello, pdf report_2024.pdf!");
+}
+
+#[test]
+fn mismatching_segments_are_forwarded() {
+    hello_rocket::__trigger_init__();
+
+    // Forwarded to the next rank.
+    perform(get("/orders/A-42"))
+        .expect_ok()
+        .expect_body("Hello, order code A-42!");
+
+    // Nothing else matches.
+    let status = perform(get("/files/report.txt")).status();
+    assert!(status == 404 || status == 422, "{}", status);
+    assert_ne!(perform(get("/files/Report.pdf")).status(), 200);
+}
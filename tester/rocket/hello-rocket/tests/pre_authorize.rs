@@ -0,0 +1,99 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/pre_authorize
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::snapshot_route_mappings;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn roles_or_permissions_authorize() {
+    hello_rocket::__trigger_init__();
+
+    perform(post("/secured/orders")).expect_status(403);
+    perform(post("/secured/orders").header("X-User", "photowey")).expect_status(403);
+
+    perform(
+        post("/secured/orders")
+            .header("X-User", "photowey")
+            .header("X-Roles", "USER, ADMIN"),
+    )
+    .expect_ok()
+    .expect_body("Hello, secured.orders!");
+
+    perform(
+        post("/secured/orders")
+            .header("X-User", "photowey")
+            .header("X-Permissions", "orders:write"),
+    )
+    .expect_ok();
+}
+
+#[test]
+fn anonymous_requests_are_not_authenticated() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/secured/reports")).expect_status(403);
+    perform(get("/secured/reports").header("X-User", "photowey"))
+        .expect_ok()
+        .expect_body("Hello, secured.reports!");
+}
+
+#[test]
+fn negated_authorities_deny() {
+    hello_rocket::__trigger_init__();
+
+    let admin = |request: MockRequest| {
+        request
+            .header("X-User", "photowey")
+            .header("X-Roles", "ADMIN")
+    };
+
+    perform(admin(delete("/secured/orders/7")))
+        .expect_ok()
+        .expect_body("Hello, secured.delete 7!");
+    perform(admin(delete("/secured/orders/7")).header("X-Permissions", "orders:readonly"))
+        .expect_status(403);
+}
+
+#[test]
+fn authorities_are_recorded() {
+    hello_rocket::__trigger_init__();
+
+    let mapping = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == "secured_orders_fn")
+        .unwrap();
+
+    assert_eq!(
+        mapping.pre_authorize.as_deref(),
+        Some("has_role('ADMIN') or has_permission('orders:write')")
+    );
+    assert_eq!(
+        mapping.authorities,
+        vec!["role:ADMIN", "permission:orders:write"]
+    );
+
+    let descriptor = mapping.descriptors().remove(0);
+    assert_eq!(
+        descriptor.metadata.get("authorities").map(String::as_str),
+        Some("role:ADMIN, permission:orders:write")
+    );
+}
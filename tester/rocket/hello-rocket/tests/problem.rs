@@ -0,0 +1,75 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/problem
+
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+
+use autortr_rocket::prelude::problem::{register_problem_namespace, ProblemNamespace};
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+fn problem_of(response: ResultActions) -> Value {
+    assert_eq!(
+        response.header("Content-Type"),
+        Some("application/problem+json")
+    );
+    serde_json::from_str(response.body()).expect("problem+json body")
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn unmatched_requests_are_problems() {
+    hello_rocket::__trigger_init__();
+
+    let problem = problem_of(perform(get("/absent")).expect_status(404));
+
+    assert_eq!(problem["type"], "about:blank");
+    assert_eq!(problem["title"], "Not Found");
+    assert_eq!(problem["status"], 404);
+    assert_eq!(problem["instance"], "/absent");
+    assert!(problem.get("function").is_none());
+}
+
+#[test]
+fn problems_name_the_matched_mapping() {
+    hello_rocket::__trigger_init__();
+
+    let problem = problem_of(perform(get("/errors/status")).expect_status(409));
+
+    assert_eq!(problem["function"], "errors_status_fn");
+    assert_eq!(problem["path"], "/errors/status");
+}
+
+#[test]
+fn namespaces_override_type_and_extensions() {
+    hello_rocket::__trigger_init__();
+
+    register_problem_namespace(
+        ProblemNamespace::new("/errors/admin")
+            .type_uri("https://errors.example.com/admin/{status}")
+            .extension("service", "admin"),
+    );
+
+    let problem = problem_of(perform(get("/errors/admin/absent/x/y")).expect_status(404));
+
+    assert_eq!(problem["type"], "https://errors.example.com/admin/404");
+    assert_eq!(problem["service"], "admin");
+}
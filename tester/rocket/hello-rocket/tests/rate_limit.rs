@@ -0,0 +1,141 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/rate_limit
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::snapshot_route_mappings;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn limits_answer_429_with_rate_limit_headers() {
+    hello_rocket::__trigger_init__();
+
+    let mvc = MockMvc::new();
+    for remaining in ["2", "1", "0"] {
+        mvc.perform(get("/limits/burst"))
+            .expect_ok()
+            .expect_body("Hello, limits.burst!")
+            .expect_header("RateLimit-Limit", "3")
+            .expect_header("RateLimit-Remaining", remaining)
+            .expect_header("RateLimit-Policy", "3;w=60");
+    }
+
+    let actions = mvc
+        .perform(get("/limits/burst"))
+        .expect_status(429)
+        .expect_header("RateLimit-Remaining", "0")
+        .expect_header("Retry-After", "20");
+    assert_eq!(actions.header("RateLimit-Reset"), Some("60"));
+
+    let actions = mvc.perform(get("/http/get")).expect_ok();
+    assert_eq!(actions.header("RateLimit-Limit"), None);
+}
+
+#[test]
+fn limits_are_keyed_by_api_key() {
+    hello_rocket::__trigger_init__();
+
+    let mvc = MockMvc::new();
+    for _ in 0..2 {
+        mvc.perform(get("/limits/keys").header("X-Api-Key", "alpha"))
+            .expect_ok();
+    }
+    mvc.perform(get("/limits/keys").header("X-Api-Key", "alpha"))
+        .expect_status(429)
+        .expect_header("Retry-After", "30");
+
+    mvc.perform(get("/limits/keys").header("X-Api-Key", "beta"))
+        .expect_ok()
+        .expect_header("RateLimit-Remaining", "1");
+}
+
+#[test]
+fn limits_are_keyed_by_custom_extractors() {
+    hello_rocket::__trigger_init__();
+
+    let mvc = MockMvc::new();
+    for _ in 0..2 {
+        mvc.perform(get("/limits/tenants/reports").header("X-Tenant", "acme"))
+            .expect_ok();
+    }
+    mvc.perform(get("/limits/tenants/reports").header("X-Tenant", "acme"))
+        .expect_status(429)
+        .expect_header("Retry-After", "1800");
+    mvc.perform(get("/limits/tenants/reports").header("X-Tenant", "globex"))
+        .expect_ok();
+
+    mvc.perform(get("/limits/tenants/exports").header("X-Tenant", "acme"))
+        .expect_ok()
+        .expect_header("RateLimit-Policy", "1;w=86400");
+    mvc.perform(get("/limits/tenants/exports").header("X-Tenant", "acme"))
+        .expect_status(429);
+    mvc.perform(get("/limits/tenants/exports").header("X-Tenant", "globex"))
+        .expect_ok();
+}
+
+#[test]
+fn limits_are_checked_behind_the_guards() {
+    hello_rocket::__trigger_init__();
+
+    let mvc = MockMvc::new();
+    for _ in 0..3 {
+        mvc.perform(get("/limits/admin")).expect_status(403);
+    }
+
+    mvc.perform(get("/limits/admin").header("X-Role", "admin"))
+        .expect_ok()
+        .expect_header("RateLimit-Remaining", "0");
+    mvc.perform(get("/limits/admin").header("X-Role", "admin"))
+        .expect_status(429);
+}
+
+#[test]
+fn limits_are_recorded() {
+    hello_rocket::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let find = |function: &str| {
+        mappings
+            .iter()
+            .find(|mapping| mapping.function == function)
+            .unwrap()
+    };
+
+    let keys = find("limits_keys_fn");
+    assert_eq!(keys.rate_limit.as_deref(), Some("2/minute"));
+    assert_eq!(keys.rate_limit_key.as_deref(), Some("api-key"));
+
+    let metadata = &find("limits_tenant_exports_fn").descriptors()[0].metadata;
+    assert_eq!(
+        metadata.get("rate_limit").map(String::as_str),
+        Some("1/day")
+    );
+    assert_eq!(
+        metadata.get("rate_limit_key").map(String::as_str),
+        Some("TenantKey")
+    );
+
+    let burst = find("limits_burst_fn");
+    assert_eq!(burst.rate_limit_key.as_deref(), Some("ip"));
+    assert_eq!(
+        find("limits_tenant_reports_fn").rate_limit.as_deref(),
+        Some("2/hour")
+    );
+}
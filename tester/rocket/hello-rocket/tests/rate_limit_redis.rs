@@ -0,0 +1,203 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/rate_limit_redis
+
+// ----------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use autortr_rocket::prelude::{
+    configure_rate_limit_store, RateLimit, RateLimitStore, RedisRateLimitStore,
+};
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+/// The value and expiry of each key.
+type Keys = HashMap<String, (i64, Option<Instant>)>;
+
+/// A local stand-in for a Redis server, speaking enough of its protocol for
+/// `RedisRateLimitStore`: `SET .. PX .. NX`, `INCR`, `PTTL` and `MULTI`/`EXEC`.
+#[derive(Clone, Default)]
+struct StandIn {
+    keys: Arc<Mutex<Keys>>,
+}
+
+impl StandIn {
+    fn start() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let stand_in = StandIn::default();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let stand_in = stand_in.clone();
+                thread::spawn(move || stand_in.serve(stream));
+            }
+        });
+
+        format!("redis://{}/", address)
+    }
+
+    fn serve(&self, stream: TcpStream) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        let mut queued: Option<Vec<Vec<String>>> = None;
+
+        while let Some(command) = read_command(&mut reader) {
+            let name = command[0].to_uppercase();
+            let reply = match (name.as_str(), queued.as_mut()) {
+                ("MULTI", _) => {
+                    queued = Some(Vec::new());
+                    "+OK\r\n".to_string()
+                }
+                ("EXEC", Some(_)) => {
+                    let commands = queued.take().unwrap();
+                    let replies: Vec<String> = commands
+                        .iter()
+                        .map(|command| self.execute(command))
+                        .collect();
+                    format!("*{}\r\n{}", replies.len(), replies.concat())
+                }
+                (_, Some(commands)) => {
+                    commands.push(command);
+                    "+QUEUED\r\n".to_string()
+                }
+                _ => self.execute(&command),
+            };
+
+            if writer.write_all(reply.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn execute(&self, command: &[String]) -> String {
+        let mut keys = self.keys.lock().unwrap();
+        let now = Instant::now();
+        keys.retain(|_, (_, expiry)| expiry.map(|expiry| expiry > now).unwrap_or(true));
+
+        match command[0].to_uppercase().as_str() {
+            "SET" => {
+                let px = command
+                    .iter()
+                    .position(|arg| arg.eq_ignore_ascii_case("PX"))
+                    .map(|index| command[index + 1].parse::<u64>().unwrap());
+                let nx = command.iter().any(|arg| arg.eq_ignore_ascii_case("NX"));
+                if nx && keys.contains_key(&command[1]) {
+                    return "$-1\r\n".to_string();
+                }
+
+                let expiry = px.map(|px| now + Duration::from_millis(px));
+                keys.insert(command[1].clone(), (command[2].parse().unwrap(), expiry));
+                "+OK\r\n".to_string()
+            }
+            "INCR" => {
+                let entry = keys.entry(command[1].clone()).or_insert((0, None));
+                entry.0 += 1;
+                format!(":{}\r\n", entry.0)
+            }
+            "PTTL" => match keys.get(&command[1]) {
+                Some((_, Some(expiry))) => {
+                    format!(":{}\r\n", expiry.duration_since(now).as_millis())
+                }
+                Some((_, None)) => ":-1\r\n".to_string(),
+                None => ":-2\r\n".to_string(),
+            },
+            _ => "+OK\r\n".to_string(),
+        }
+    }
+}
+
+fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let count: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+
+    let mut command = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let length: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+
+        let mut bulk = vec![0; length + 2];
+        reader.read_exact(&mut bulk).ok()?;
+        bulk.truncate(length);
+        command.push(String::from_utf8(bulk).ok()?);
+    }
+
+    Some(command)
+}
+
+// ----------------------------------------------------------------
+
+#[rocket::async_test]
+async fn redis_stores_count_fixed_windows() {
+    let store = RedisRateLimitStore::open(&StandIn::start())
+        .unwrap()
+        .prefix("test:");
+    let limit = RateLimit::parse("2/minute").unwrap();
+
+    let first = store.acquire("client", limit).await.unwrap();
+    assert!(first.allowed);
+    assert_eq!(first.remaining, 1);
+    assert_eq!(first.reset, 60);
+
+    assert!(store.acquire("client", limit).await.unwrap().allowed);
+
+    let denied = store.acquire("client", limit).await.unwrap();
+    assert!(!denied.allowed);
+    assert_eq!(denied.remaining, 0);
+    assert_eq!(denied.retry_after, 60);
+
+    assert!(store.acquire("another", limit).await.unwrap().allowed);
+}
+
+#[rocket::async_test]
+async fn unreachable_redis_stores_fail() {
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let store = RedisRateLimitStore::open(&format!("redis://{}/", address)).unwrap();
+    let limit = RateLimit::parse("2/minute").unwrap();
+
+    assert!(store.acquire("client", limit).await.is_err());
+}
+
+#[test]
+fn configured_redis_stores_limit_the_routes() {
+    hello_rocket::__trigger_init__();
+    configure_rate_limit_store(RedisRateLimitStore::open(&StandIn::start()).unwrap());
+
+    let mvc = MockMvc::new();
+    for remaining in ["2", "1", "0"] {
+        mvc.perform(get("/limits/burst"))
+            .expect_ok()
+            .expect_header("RateLimit-Remaining", remaining);
+    }
+
+    mvc.perform(get("/limits/burst"))
+        .expect_status(429)
+        .expect_header("Retry-After", "60")
+        .expect_header("RateLimit-Reset", "60");
+}
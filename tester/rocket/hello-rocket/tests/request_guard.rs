@@ -0,0 +1,96 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/request_guard
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::snapshot_route_mappings;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn mapping_guards_protect_the_route() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/guarded/key")).expect_status(401);
+
+    perform(get("/guarded/key").header("X-Api-Key", "secret"))
+        .expect_ok()
+        .expect_body("Hello, guarded.key!");
+}
+
+#[test]
+fn controller_guards_come_ahead_of_mapping_guards() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/guarded/admin/users")).expect_status(401);
+    perform(get("/guarded/admin/users").header("X-Api-Key", "secret"))
+        .expect_ok()
+        .expect_body("Hello, guarded.admin.users!");
+
+    perform(delete("/guarded/admin/users/7").header("X-Role", "admin")).expect_status(401);
+    perform(delete("/guarded/admin/users/7").header("X-Api-Key", "secret")).expect_status(403);
+    perform(
+        delete("/guarded/admin/users/7")
+            .header("X-Api-Key", "secret")
+            .header("X-Role", "admin"),
+    )
+    .expect_ok()
+    .expect_body("Hello, guarded.admin.delete 7!");
+}
+
+#[test]
+fn rest_controllers_take_guards() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/guarded/rest/numbers")).expect_status(403);
+    perform(get("/guarded/rest/numbers").header("X-Role", "admin"))
+        .expect_ok()
+        .expect_body(r#"{"code":200,"data":[1,2,3],"message":"OK"}"#);
+}
+
+#[test]
+fn guards_are_recorded() {
+    hello_rocket::__trigger_init__();
+
+    let guards = |function: &str| {
+        snapshot_route_mappings()
+            .into_iter()
+            .find(|mapping| mapping.function == function)
+            .map(|mapping| mapping.guards)
+            .unwrap()
+    };
+
+    assert_eq!(guards("guarded_key_fn"), vec!["ApiKey"]);
+    assert_eq!(guards("guarded_admin_users_fn"), vec!["ApiKey"]);
+    assert_eq!(
+        guards("guarded_admin_delete_fn"),
+        vec!["ApiKey", "AdminOnly"]
+    );
+    assert!(guards("get_fn").is_empty());
+
+    let descriptor = snapshot_route_mappings()
+        .into_iter()
+        .find(|mapping| mapping.function == "guarded_admin_delete_fn")
+        .map(|mapping| mapping.descriptors().remove(0))
+        .unwrap();
+    assert_eq!(
+        descriptor.metadata.get("guards").map(String::as_str),
+        Some("ApiKey, AdminOnly")
+    );
+}
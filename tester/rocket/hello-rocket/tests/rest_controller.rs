@@ -0,0 +1,50 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/rest_controller
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn values_are_enveloped() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/rest/profiles/7"))
+        .expect_ok()
+        .expect_header("Content-Type", "application/json")
+        .expect_body(r#"{"code":200,"data":{"id":7,"name":"rocket"},"message":"OK"}"#);
+
+    perform(get("/rest/numbers"))
+        .expect_ok()
+        .expect_body(r#"{"code":200,"data":[1,2,3],"message":"OK"}"#);
+
+    perform(post("/rest/echo").form(&[("account", "photowey")]))
+        .expect_ok()
+        .expect_body(r#"{"code":200,"data":"photowey","message":"OK"}"#);
+}
+
+#[test]
+fn errors_are_converted() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/rest/profiles/0"))
+        .expect_status(404)
+        .expect_body(r#"{"code":40401,"data":null,"message":"profile 0 not found"}"#);
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/route_snapshot
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::testing::snapshot::{assert_route_snapshot, assert_route_snapshot_with};
+
+// ----------------------------------------------------------------
+
+#[test]
+fn route_table_snapshot() {
+    hello_rocket::__trigger_init__();
+
+    assert_route_snapshot(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/snapshots/routes.txt"
+    ));
+}
+
+#[test]
+fn http_route_table_json_snapshot() {
+    hello_rocket::__trigger_init__();
+
+    assert_route_snapshot_with(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/snapshots/http_routes.json"
+        ),
+        |mapping| mapping.namespace == "/http",
+    );
+}
@@ -0,0 +1,59 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/signature_binding
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::*;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn bindings_are_inferred_from_the_signature() {
+    hello_rocket::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+
+    let post = mappings
+        .iter()
+        .find(|m| m.function == "infer_post_fn")
+        .unwrap();
+    assert_eq!(post.path, "/post");
+    assert_eq!(post.data, "<form>");
+
+    let put = mappings
+        .iter()
+        .find(|m| m.function == "infer_put_fn")
+        .unwrap();
+    assert_eq!(put.path, "/users/<id>?<verbose>");
+    assert_eq!(put.pattern, "/users");
+    assert_eq!(put.data, "<account>");
+}
+
+#[test]
+fn inferred_bindings_are_routed() {
+    hello_rocket::__trigger_init__();
+
+    perform(post("/infer/post").form(&[("account", "rocket")]))
+        .expect_ok()
+        .expect_body("Hello, infer.post rocket!");
+
+    perform(put("/infer/users/7?verbose=true").form(&[("account", "rocket")]))
+        .expect_ok()
+        .expect_body("Hello, infer.put 7 rocket!");
+}
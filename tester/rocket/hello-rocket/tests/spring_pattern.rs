@@ -0,0 +1,66 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/spring_pattern
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::prelude::*;
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn spring_patterns_are_translated() {
+    hello_rocket::__trigger_init__();
+
+    let mappings = snapshot_route_mappings();
+    let find = |function: &str| {
+        let mapping = mappings.iter().find(|m| m.function == function).unwrap();
+        (mapping.pattern.clone(), mapping.path.clone())
+    };
+
+    assert_eq!(
+        find("spring_user_fn"),
+        ("/users/{id}".to_string(), "/users/<id>".to_string())
+    );
+    assert_eq!(
+        find("spring_posts_fn"),
+        ("/users/*/posts".to_string(), "/users/<_>/posts".to_string())
+    );
+    assert_eq!(
+        find("spring_static_fn"),
+        ("/static/**".to_string(), "/static/<path..>".to_string())
+    );
+    assert_eq!(find("get_fn"), ("/get".to_string(), "/get".to_string()));
+}
+
+#[test]
+fn spring_patterns_are_routed() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/spring/users/42"))
+        .expect_ok()
+        .expect_body("Hello, spring.user 42!");
+
+    perform(get("/spring/users/42/posts"))
+        .expect_ok()
+        .expect_body("Hello, spring.posts!");
+
+    perform(get("/spring/static/css/app.css"))
+        .expect_ok()
+        .expect_body("Hello, spring.static css/app.css!");
+}
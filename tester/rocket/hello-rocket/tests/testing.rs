@@ -0,0 +1,75 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/testing
+
+// ----------------------------------------------------------------
+
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_client_serves_registered_routes() {
+    hello_rocket::__trigger_init__();
+
+    let client = test_client();
+    let response = client.get("/http/get").dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "Hello, http.get mapping!");
+}
+
+#[test]
+fn test_client_with_filters_routes() {
+    hello_rocket::__trigger_init__();
+
+    let client = test_client_with(|mapping| mapping.namespace == "/rocket");
+
+    assert_eq!(
+        client.get("/rocket/namespace").dispatch().status().code,
+        200
+    );
+    assert_eq!(client.get("/http/get").dispatch().status().code, 404);
+}
+
+#[test]
+fn perform_fluent_assertions() {
+    hello_rocket::__trigger_init__();
+
+    perform(get("/http/get"))
+        .expect_status(200)
+        .expect_body_contains("http.get");
+
+    perform(post("/rocket/data").form(&[("account", "photowey")]))
+        .expect_ok()
+        .expect_body("Hello, data!");
+
+    perform(get("/absent")).expect_status(404);
+}
+
+#[test]
+fn perform_by_handler_name() {
+    hello_rocket::__trigger_init__();
+
+    let mvc = MockMvc::new();
+    mvc.perform(handler("http_put_mapping_fn").form(&[("account", "photowey")]))
+        .expect_ok()
+        .expect_body("Hello, http.put mapping!");
+
+    mvc.perform(handler("namespace_fn"))
+        .expect_ok()
+        .expect_body("Hello, namespace!");
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/validation
+
+// ----------------------------------------------------------------
+
+use serde_json::{json, Value};
+
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn valid_bodies_reach_the_handler() {
+    hello_rocket::__trigger_init__();
+
+    perform(
+        post("/valid/signup").form(&[("account", "photowey"), ("email", "photowey@gmail.com")]),
+    )
+    .expect_ok()
+    .expect_body("Hello, valid.signup photowey!");
+
+    perform(put("/valid/signup").form(&[("account", "photowey"), ("email", "photowey@gmail.com")]))
+        .expect_ok()
+        .expect_body("Hello, valid.update photowey@gmail.com!");
+}
+
+#[test]
+fn invalid_bodies_are_rejected_with_field_errors() {
+    hello_rocket::__trigger_init__();
+
+    for request in [post("/valid/signup"), put("/valid/signup")] {
+        let response = perform(request.form(&[("account", "ph"), ("email", "photowey")]))
+            .expect_status(422)
+            .expect_header("Content-Type", "application/json");
+        let body: Value = serde_json::from_str(response.body()).unwrap();
+
+        assert_eq!(body["status"], 422);
+        assert_eq!(
+            body["errors"],
+            json!([
+                {"field": "account", "code": "length", "message": "too short"},
+                {"field": "email", "code": "email", "message": null},
+            ])
+        );
+    }
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tester/rocket/hello-rocket/tests/webhook
+
+// ----------------------------------------------------------------
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use autortr_rocket::testing::*;
+
+// ----------------------------------------------------------------
+
+fn sign(secret: &str, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+// ----------------------------------------------------------------
+
+#[test]
+fn github_signatures_are_verified() {
+    hello_rocket::__trigger_init__();
+
+    let body = r#"{"action":"opened"}"#;
+    let signature = format!("sha256={}", sign("It's a Secret to Everybody", body));
+
+    perform(
+        post("/hooks/github")
+            .header("X-Hub-Signature-256", signature.clone())
+            .header("X-GitHub-Delivery", "delivery-1")
+            .body(body),
+    )
+    .expect_ok()
+    .expect_body("Hello, hooks.github opened!");
+
+    perform(
+        post("/hooks/github")
+            .header("X-Hub-Signature-256", signature)
+            .header("X-GitHub-Delivery", "delivery-2")
+            .body(r#"{"action":"closed"}"#),
+    )
+    .expect_status(401);
+
+    perform(post("/hooks/github").body(body)).expect_status(401);
+}
+
+#[test]
+fn github_deliveries_are_not_replayed() {
+    hello_rocket::__trigger_init__();
+
+    let body = r#"{"action":"synchronize"}"#;
+    let signature = format!("sha256={}", sign("It's a Secret to Everybody", body));
+    let delivery = || {
+        post("/hooks/github")
+            .header("X-Hub-Signature-256", signature.clone())
+            .header("X-GitHub-Delivery", "delivery-replayed")
+            .body(body)
+    };
+
+    perform(delivery()).expect_ok();
+    perform(delivery()).expect_status(401);
+}
+
+#[test]
+fn github_deliveries_require_their_id() {
+    hello_rocket::__trigger_init__();
+
+    let body = r#"{"action":"labeled"}"#;
+    let signature = format!("sha256={}", sign("It's a Secret to Everybody", body));
+
+    perform(
+        post("/hooks/github")
+            .header("X-Hub-Signature-256", signature)
+            .body(body),
+    )
+    .expect_status(401);
+}
+
+#[test]
+fn github_deliveries_are_not_replayed_under_another_id() {
+    hello_rocket::__trigger_init__();
+
+    let body = r#"{"action":"reopened"}"#;
+    let signature = format!("sha256={}", sign("It's a Secret to Everybody", body));
+    let delivery = |id: &'static str| {
+        post("/hooks/github")
+            .header("X-Hub-Signature-256", signature.clone())
+            .header("X-GitHub-Delivery", id)
+            .body(body)
+    };
+
+    perform(delivery("delivery-original")).expect_ok();
+    perform(delivery("delivery-changed")).expect_status(401);
+}
+
+#[test]
+fn stripe_timestamps_are_signed() {
+    hello_rocket::__trigger_init__();
+
+    let body = r#"{"type":"charge.succeeded"}"#;
+    let timestamp = now();
+    let signature = sign("whsec_hello_rocket", &format!("{}.{}", timestamp, body));
+    let header = format!("t={},v1=deadbeef,v1={}", timestamp, signature);
+
+    perform(
+        post("/hooks/stripe")
+            .header("Stripe-Signature", header.clone())
+            .body(body),
+    )
+    .expect_ok()
+    .expect_body(&format!("Hello, hooks.stripe {}!", timestamp));
+
+    perform(
+        post("/hooks/stripe")
+            .header("Stripe-Signature", header)
+            .body(body),
+    )
+    .expect_status(401);
+
+    let stale = now() - 3600;
+    let signature = sign("whsec_hello_rocket", &format!("{}.{}", stale, body));
+    perform(
+        post("/hooks/stripe")
+            .header("Stripe-Signature", format!("t={},v1={}", stale, signature))
+            .body(body),
+    )
+    .expect_status(401);
+}
+
+#[test]
+fn hmac_signatures_cover_the_timestamp() {
+    hello_rocket::__trigger_init__();
+
+    let timestamp = now();
+    let signature = sign("partner-secret", &format!("{}.ping", timestamp));
+
+    perform(
+        post("/hooks/partner")
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header("X-Webhook-Signature", signature.clone())
+            .body("ping"),
+    )
+    .expect_ok()
+    .expect_body("Hello, hooks.partner ping!");
+
+    perform(
+        post("/hooks/partner")
+            .header("X-Webhook-Timestamp", (timestamp + 1).to_string())
+            .header("X-Webhook-Signature", signature)
+            .body("ping"),
+    )
+    .expect_status(401);
+}
+
+#[test]
+fn api_keys_are_compared() {
+    hello_rocket::__trigger_init__();
+
+    perform(
+        post("/hooks/legacy")
+            .header("X-Api-Key", "legacy-api-key")
+            .body("ping"),
+    )
+    .expect_ok()
+    .expect_body("Hello, hooks.legacy 4!");
+
+    perform(
+        post("/hooks/legacy")
+            .header("X-Api-Key", "legacy")
+            .body("ping"),
+    )
+    .expect_status(401);
+    perform(post("/hooks/legacy").body("ping")).expect_status(401);
+}
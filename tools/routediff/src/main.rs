@@ -0,0 +1,71 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tools/routediff/main
+
+// ----------------------------------------------------------------
+
+use std::process::ExitCode;
+
+use autortr_core::{diff_route_tables, RouteTable};
+
+// ----------------------------------------------------------------
+
+const USAGE: &str = "Usage: autortr-routediff <OLD_ROUTE_TABLE> <NEW_ROUTE_TABLE>";
+
+// ----------------------------------------------------------------
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (old, new) = match args.as_slice() {
+        [old, new] => (old, new),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let (old, new) = match (load(old), load(new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let diff = diff_route_tables(&old, &new);
+    print!("{}", diff.to_report());
+
+    match diff.has_breaking_changes() {
+        true => ExitCode::from(1),
+        false => ExitCode::SUCCESS,
+    }
+}
+
+// ----------------------------------------------------------------
+
+/// Loads a route table exported with `RouteTable::to_json` (`*.json`) or `RouteTable::to_text`.
+fn load(path: &str) -> Result<RouteTable, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read `{}`: {}", path, e))?;
+
+    match path.ends_with(".json") {
+        true => RouteTable::from_json(&content)
+            .map_err(|e| format!("Invalid route table `{}`: {}", path, e)),
+        false => RouteTable::from_text(&content)
+            .map_err(|line| format!("Invalid route table `{}`: `{}`", path, line)),
+    }
+}
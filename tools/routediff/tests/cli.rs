@@ -0,0 +1,100 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// tools/routediff/tests/cli
+
+// ----------------------------------------------------------------
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// ----------------------------------------------------------------
+
+const OLD: &str = concat!(
+    "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+    "DELETE /users/<id> rank=-5 format=- data=- -> delete_user\n",
+);
+
+// ----------------------------------------------------------------
+
+#[test]
+fn breaking_changes_exit_with_1() {
+    let output = routediff(
+        "breaking",
+        OLD,
+        "GET /users/<id> rank=-5 format=- data=- -> get_user\n",
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("Breaking changes (1):\n"));
+}
+
+#[test]
+fn non_breaking_changes_exit_with_0() {
+    let output = routediff(
+        "non-breaking",
+        OLD,
+        concat!(
+            "GET /users/<user_id> rank=-5 format=- data=- -> get_user deprecated\n",
+            "DELETE /users/<id> rank=-5 format=- data=- -> delete_user\n",
+            "GET /users rank=-9 format=- data=- -> list_users\n",
+        ),
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("Non-breaking changes (3):\n"));
+}
+
+#[test]
+fn unchanged_tables_exit_with_0() {
+    let output = routediff("unchanged", OLD, OLD);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "No route changes.\n");
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    let output = Command::new(env!("CARGO_BIN_EXE_autortr-routediff"))
+        .output()
+        .expect("autortr-routediff runs");
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = routediff("invalid", OLD, "GET /users\n");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// ----------------------------------------------------------------
+
+fn routediff(name: &str, old: &str, new: &str) -> Output {
+    let (old_path, new_path) = (table(name, "old", old), table(name, "new", new));
+
+    Command::new(env!("CARGO_BIN_EXE_autortr-routediff"))
+        .arg(&old_path)
+        .arg(&new_path)
+        .output()
+        .expect("autortr-routediff runs")
+}
+
+fn table(name: &str, version: &str, text: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}-{}.txt", name, version));
+    std::fs::write(&path, text).expect("a writable route table");
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}